@@ -0,0 +1,320 @@
+use anyhow::Result;
+
+/// Scale the side (L-R) signal of interleaved stereo samples.
+///
+/// 0.0 = mono, 1.0 = unchanged, 2.0 = extra wide. Mid (L+R) is preserved, so
+/// narrowing never loses centered material.
+pub fn apply_stereo_width(buffer: &mut [f32], width: f32) {
+    for frame in buffer.chunks_exact_mut(2) {
+        let mid = (frame[0] + frame[1]) * 0.5;
+        let side = (frame[0] - frame[1]) * 0.5 * width;
+        frame[0] = (mid + side).clamp(-1.0, 1.0);
+        frame[1] = (mid - side).clamp(-1.0, 1.0);
+    }
+}
+
+/// Fold stereo content below `crossover_hz` to mono, keeping highs stereo.
+///
+/// A two-band split of the side (L-R) signal: its low band, from a two-pole
+/// low-pass, is removed from both channels, so bass arrives in phase on both
+/// speakers. Mid (L+R) passes untouched.
+pub fn apply_bass_mono(buffer: &mut [f32], crossover_hz: f32, sample_rate: u32) {
+    if crossover_hz <= 0.0 {
+        return;
+    }
+
+    let alpha = 1.0 - (-2.0 * std::f32::consts::PI * crossover_hz / sample_rate as f32).exp();
+    let (mut stage1, mut stage2) = (0.0, 0.0);
+    for frame in buffer.chunks_exact_mut(2) {
+        let side = (frame[0] - frame[1]) * 0.5;
+        stage1 += (side - stage1) * alpha;
+        stage2 += (stage1 - stage2) * alpha;
+        frame[0] = (frame[0] - stage2).clamp(-1.0, 1.0);
+        frame[1] = (frame[1] + stage2).clamp(-1.0, 1.0);
+    }
+}
+
+/// Join interleaved stereo segments end to end, overlapping each boundary by
+/// `fade_frames` with an equal-power crossfade so the joins never click
+pub fn crossfade_concat(segments: &[Vec<f32>], fade_frames: usize) -> Vec<f32> {
+    let mut joined: Vec<f32> = Vec::new();
+    for segment in segments {
+        // Never overlap more than half of either side
+        let fade = fade_frames.min(joined.len() / 4).min(segment.len() / 4);
+        let start = joined.len() - fade * 2;
+        let overlap = joined[start..].chunks_exact_mut(2).zip(segment.chunks_exact(2));
+        for (i, (old, new)) in overlap.enumerate() {
+            let t = (i as f32 + 0.5) / fade as f32 * std::f32::consts::FRAC_PI_2;
+            for (o, n) in old.iter_mut().zip(new) {
+                *o = (*o * t.cos() + n * t.sin()).clamp(-1.0, 1.0);
+            }
+        }
+        joined.extend_from_slice(&segment[fade * 2..]);
+    }
+    joined
+}
+
+/// Root-mean-square level of `samples` (0.0 for an empty buffer)
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f32 = samples.iter().map(|s| s * s).sum();
+    (sum / samples.len() as f32).sqrt()
+}
+
+/// Sum interleaved stereo samples to a single channel
+pub fn downmix_to_mono(buffer: &[f32]) -> Vec<f32> {
+    buffer
+        .chunks_exact(2)
+        .map(|frame| (frame[0] + frame[1]) * 0.5)
+        .collect()
+}
+
+/// Largest sample value at `bit_depth` (16 → 32767, 24 → 8388607)
+fn full_scale(bit_depth: u16) -> f32 {
+    ((1i32 << (bit_depth - 1)) - 1) as f32
+}
+
+/// Write interleaved samples as a 16- or 24-bit WAV file
+pub fn write_wav(path: &str, samples: &[f32], channels: u16, sample_rate: u32, bit_depth: u16) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: bit_depth,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let scale = full_scale(bit_depth);
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    for &sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * scale) as i32)?;
+    }
+    writer.finalize()?;
+
+    Ok(())
+}
+
+/// Write interleaved samples as a 16- or 24-bit FLAC file
+pub fn write_flac(path: &str, samples: &[f32], channels: u16, sample_rate: u32, bit_depth: u16) -> Result<()> {
+    use flacenc::component::BitRepr;
+    use flacenc::error::Verify;
+    use flacenc::source::MemSource;
+
+    let scale = full_scale(bit_depth);
+    let samples_i32: Vec<i32> = samples
+        .iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * scale) as i32)
+        .collect();
+
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|e| anyhow::anyhow!("Invalid FLAC encoder config: {:?}", e))?;
+    let source = MemSource::from_samples(&samples_i32, channels as usize, bit_depth as usize, sample_rate as usize);
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| anyhow::anyhow!("FLAC encoding failed: {:?}", e))?;
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| anyhow::anyhow!("Failed to write FLAC stream: {:?}", e))?;
+    std::fs::write(path, sink.as_slice())?;
+
+    Ok(())
+}
+
+/// Bitrates LAME encodes at, in kbps
+const MP3_BITRATES_KBPS: [u32; 16] = [8, 16, 24, 32, 40, 48, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+
+/// The supported MP3 bitrate closest to `kbps`
+pub fn mp3_bitrate(kbps: u32) -> u32 {
+    MP3_BITRATES_KBPS
+        .into_iter()
+        .min_by_key(|&supported| supported.abs_diff(kbps))
+        .unwrap_or(192)
+}
+
+/// Write interleaved samples as a constant-bitrate MP3 file (`bitrate_kbps`
+/// snaps to the nearest rate LAME supports)
+pub fn write_mp3(path: &str, samples: &[f32], channels: u16, sample_rate: u32, bitrate_kbps: u32) -> Result<()> {
+    use mp3lame_encoder::{Bitrate, Builder, FlushNoGap, InterleavedPcm, MonoPcm, Quality};
+
+    let bitrate = match mp3_bitrate(bitrate_kbps) {
+        8 => Bitrate::Kbps8,
+        16 => Bitrate::Kbps16,
+        24 => Bitrate::Kbps24,
+        32 => Bitrate::Kbps32,
+        40 => Bitrate::Kbps40,
+        48 => Bitrate::Kbps48,
+        64 => Bitrate::Kbps64,
+        80 => Bitrate::Kbps80,
+        96 => Bitrate::Kbps96,
+        112 => Bitrate::Kbps112,
+        128 => Bitrate::Kbps128,
+        160 => Bitrate::Kbps160,
+        224 => Bitrate::Kbps224,
+        256 => Bitrate::Kbps256,
+        320 => Bitrate::Kbps320,
+        _ => Bitrate::Kbps192,
+    };
+
+    let mut builder = Builder::new().ok_or_else(|| anyhow::anyhow!("Failed to create MP3 encoder"))?;
+    builder
+        .set_num_channels(channels as u8)
+        .map_err(|e| anyhow::anyhow!("Invalid MP3 channel count: {:?}", e))?;
+    builder
+        .set_sample_rate(sample_rate)
+        .map_err(|e| anyhow::anyhow!("Invalid MP3 sample rate: {:?}", e))?;
+    builder
+        .set_brate(bitrate)
+        .map_err(|e| anyhow::anyhow!("Invalid MP3 bitrate: {:?}", e))?;
+    builder
+        .set_quality(Quality::Best)
+        .map_err(|e| anyhow::anyhow!("Invalid MP3 quality: {:?}", e))?;
+    let mut encoder = builder
+        .build()
+        .map_err(|e| anyhow::anyhow!("Invalid MP3 encoder config: {:?}", e))?;
+
+    let scale = full_scale(16);
+    let samples_i16: Vec<i16> = samples
+        .iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * scale) as i16)
+        .collect();
+
+    let mut encoded = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(samples_i16.len()));
+    let written = if channels == 1 {
+        encoder.encode_to_vec(MonoPcm(&samples_i16), &mut encoded)
+    } else {
+        encoder.encode_to_vec(InterleavedPcm(&samples_i16), &mut encoded)
+    };
+    written.map_err(|e| anyhow::anyhow!("MP3 encoding failed: {:?}", e))?;
+    encoder
+        .flush_to_vec::<FlushNoGap>(&mut encoded)
+        .map_err(|e| anyhow::anyhow!("Failed to finish MP3 stream: {:?}", e))?;
+    std::fs::write(path, encoded)?;
+
+    Ok(())
+}
+
+/// Write interleaved samples as an Ogg Vorbis file, averaging `bitrate_kbps`
+pub fn write_ogg(path: &str, samples: &[f32], channels: u16, sample_rate: u32, bitrate_kbps: u32) -> Result<()> {
+    use std::num::{NonZeroU32, NonZeroU8};
+    use vorbis_rs::{VorbisBitrateManagementStrategy, VorbisEncoderBuilder};
+
+    let rate = NonZeroU32::new(sample_rate).ok_or_else(|| anyhow::anyhow!("Invalid Ogg sample rate 0"))?;
+    let channel_count = NonZeroU8::new(channels as u8).ok_or_else(|| anyhow::anyhow!("Invalid Ogg channel count 0"))?;
+    let target_bitrate = NonZeroU32::new(bitrate_kbps.max(32) * 1000).unwrap_or(NonZeroU32::MIN);
+
+    let mut builder = VorbisEncoderBuilder::new(rate, channel_count, std::fs::File::create(path)?)?;
+    builder.bitrate_management_strategy(VorbisBitrateManagementStrategy::Vbr { target_bitrate });
+    let mut encoder = builder.build()?;
+
+    // Vorbis takes planar blocks: one buffer per channel
+    let channels = channels as usize;
+    for block in samples.chunks(4096 * channels) {
+        let planar: Vec<Vec<f32>> = (0..channels)
+            .map(|channel| block.iter().skip(channel).step_by(channels).map(|s| s.clamp(-1.0, 1.0)).collect())
+            .collect();
+        encoder.encode_audio_block(&planar)?;
+    }
+    encoder.finish()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_width_is_mono() {
+        let mut buffer = vec![0.75, 0.25, -0.5, 0.5];
+        apply_stereo_width(&mut buffer, 0.0);
+        assert_eq!(buffer, vec![0.5, 0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_unit_width_is_unchanged() {
+        let mut buffer = vec![0.75, 0.25, -0.5, 0.5];
+        apply_stereo_width(&mut buffer, 1.0);
+        assert_eq!(buffer, vec![0.75, 0.25, -0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_bass_mono_folds_lows_and_keeps_highs() {
+        // Constant L/R difference (0 Hz): settles to identical channels
+        let mut lows = [0.5, -0.5].repeat(48_000);
+        apply_bass_mono(&mut lows, 120.0, 48_000);
+        let last = &lows[lows.len() - 2..];
+        assert!(last[0].abs() < 1e-3 && last[1].abs() < 1e-3);
+
+        // Side signal at Nyquist: well above the crossover, barely touched
+        let mut highs = [0.5, -0.5, -0.5, 0.5].repeat(12_000);
+        apply_bass_mono(&mut highs, 120.0, 48_000);
+        let last = &highs[highs.len() - 2..];
+        assert!((last[0] - -0.5).abs() < 0.01 && (last[1] - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_crossfade_overlaps_each_boundary() {
+        let a = vec![0.5; 200]; // 100 stereo frames
+        let b = vec![-0.5; 200];
+        let joined = crossfade_concat(&[a.clone(), b.clone(), a], 10);
+        assert_eq!(joined.len(), 600 - 2 * 10 * 2);
+
+        // Starts on the first segment, ends on the last, passes through the middle one
+        assert_eq!(joined[0], 0.5);
+        assert_eq!(joined[200], -0.5);
+        assert_eq!(*joined.last().unwrap(), 0.5);
+
+        // No fade (or nothing to fade into) is plain concatenation
+        assert_eq!(crossfade_concat(&[b.clone(), b], 0).len(), 400);
+    }
+
+    #[test]
+    fn test_full_scale_per_bit_depth() {
+        assert_eq!(full_scale(16), 32_767.0);
+        assert_eq!(full_scale(24), 8_388_607.0);
+    }
+
+    #[test]
+    fn test_mp3_bitrate_snaps_to_supported() {
+        assert_eq!(mp3_bitrate(192), 192);
+        assert_eq!(mp3_bitrate(200), 192);
+        assert_eq!(mp3_bitrate(1000), 320);
+        assert_eq!(mp3_bitrate(0), 8);
+    }
+
+    #[test]
+    fn test_compressed_exports_are_written() {
+        // One second of a 440 Hz tone, stereo
+        let samples: Vec<f32> = (0..44100)
+            .flat_map(|i| {
+                let s = (i as f32 / 44100.0 * 440.0 * std::f32::consts::TAU).sin() * 0.5;
+                [s, s]
+            })
+            .collect();
+
+        let mp3 = std::env::temp_dir().join("syssonic_export_test.mp3");
+        write_mp3(mp3.to_str().unwrap(), &samples, 2, 44100, 192).unwrap();
+        let bytes = std::fs::read(&mp3).unwrap();
+        assert!(bytes.len() > 1000 && bytes[0] == 0xFF && bytes[1] & 0xE0 == 0xE0, "MP3 frame sync");
+        let _ = std::fs::remove_file(&mp3);
+
+        let ogg = std::env::temp_dir().join("syssonic_export_test.ogg");
+        write_ogg(ogg.to_str().unwrap(), &downmix_to_mono(&samples), 1, 44100, 192).unwrap();
+        assert!(std::fs::read(&ogg).unwrap().starts_with(b"OggS"));
+        let _ = std::fs::remove_file(&ogg);
+    }
+
+    #[test]
+    fn test_rms_of_square_wave_is_its_amplitude() {
+        assert_eq!(rms(&[0.5, -0.5, 0.5, -0.5]), 0.5);
+        assert_eq!(rms(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_downmix_averages_channels() {
+        assert_eq!(downmix_to_mono(&[0.75, 0.25, -0.5, 0.5]), vec![0.5, 0.0]);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::auto_gain::{AutoGain, AutoGainSettings};
+use crate::composer::{BarClock, SystemComposer, DrumMap, EffectSettings, ExportFormat, Focus, Layers, NoAudioDevice, VoiceLimit, WaveformTap, DEFAULT_SAMPLE_RATE};
+use crate::mapper::MusicalParams;
+use anyhow::Result;
+use crossbeam_channel::{Sender, Receiver, bounded, unbounded};
+use std::path::PathBuf;
+use std::thread::{self, JoinHandle};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long dropping an `AudioThread` waits for it to finish
+const DROP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub enum AudioCommand {
+    Play(MusicalParams, usize), // params, duration_bars
+    Stop, // Send with `AudioThread::stop` to also cut the playing composition short
+    Pause,
+    Resume,
+    SetVolume(f32),
+    SetOutputFormat { sample_rate: u32, bit_depth: Option<u16> },
+    SetBufferSize(Option<u32>), // Playback frames per callback (None = device default)
+    SetFocus(Option<Focus>),
+    SetEffects(EffectSettings),
+    SetVoiceLimit(Option<VoiceLimit>),
+    SetLayers(Layers), // Layers composed at all
+    SetDrumMap(DrumMap), // Percussion notes for MIDI exports
+    SetAutoGain(AutoGainSettings),
+    SetQuantize(bool), // Start each composition on the next downbeat of the last one
+    Export {
+        path: PathBuf,
+        format: String,
+        params: MusicalParams,
+        bars: usize,
+    },
+    Shutdown, // Finish the current command, then exit the thread
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum AudioEvent {
+    Playing,
+    Stopped,
+    Paused,
+    Resumed,
+    Error(String),
+    NoAudioDevice, // Playback impossible on this machine; exports still work
+    ExportStarted,
+    ExportProgress(f32),
+    ExportComplete(String),
+    Idle(bool), // Live playback paused (true) or resumed (false) for idle detection
+    Frozen(bool), // Live playback holding the last params (true) or following metrics again (false)
+}
+
+pub struct AudioThread {
+    cmd_tx: Sender<AudioCommand>,
+    cmd_rx: Receiver<AudioCommand>, // Kept to drain queued commands on emergency stop
+    event_tx: Sender<AudioEvent>,   // Kept to report state decided outside the thread (idle)
+    event_rx: Receiver<AudioEvent>,
+    thread_handle: Option<JoinHandle<()>>,
+    is_playing: Arc<AtomicBool>,
+    volume: Arc<AtomicU32>, // f32 bits (std has no AtomicF32)
+    output_format: Arc<Mutex<(u32, Option<u16>)>>, // sample rate, bit depth
+    buffer_size: Arc<Mutex<Option<u32>>>,
+    focus: Arc<Mutex<Option<Focus>>>,
+    effects: Arc<Mutex<EffectSettings>>,
+    voice_limit: Arc<Mutex<Option<VoiceLimit>>>,
+    layers: Arc<Mutex<Layers>>,
+    drum_map: Arc<Mutex<DrumMap>>,
+    auto_gain: Arc<Mutex<AutoGain>>, // Settings plus the gain reached so far
+    quantize: Arc<Mutex<bool>>,
+    halt: Arc<AtomicBool>,  // Silences the current composition; one per thread
+    waveform: WaveformTap,  // Recent output samples, written lock-free by playback
+    silent: bool,
+}
+
+impl AudioThread {
+    pub fn new() -> Self {
+        Self::spawn(false)
+    }
+
+    /// Audio thread that renders compositions without opening an audio device
+    pub fn new_silent() -> Self {
+        Self::spawn(true)
+    }
+
+    fn spawn(silent: bool) -> Self {
+        let (cmd_tx, cmd_rx) = bounded::<AudioCommand>(32);
+        let (event_tx, event_rx) = unbounded::<AudioEvent>();
+
+        let is_playing = Arc::new(AtomicBool::new(false));
+        let volume = Arc::new(AtomicU32::new(0.8f32.to_bits()));
+        let output_format = Arc::new(Mutex::new((DEFAULT_SAMPLE_RATE, None)));
+        let buffer_size = Arc::new(Mutex::new(None));
+        let focus = Arc::new(Mutex::new(None));
+        let effects = Arc::new(Mutex::new(EffectSettings::default()));
+        let voice_limit = Arc::new(Mutex::new(None));
+        let layers = Arc::new(Mutex::new(Layers::default()));
+        let drum_map = Arc::new(Mutex::new(DrumMap::default()));
+        let auto_gain = Arc::new(Mutex::new(AutoGain::new(AutoGainSettings::default())));
+        let quantize = Arc::new(Mutex::new(false));
+        let halt = Arc::new(AtomicBool::new(false));
+        let waveform = WaveformTap::default();
+
+        let is_playing_clone = is_playing.clone();
+        let volume_clone = volume.clone();
+        let output_format_clone = output_format.clone();
+        let buffer_size_clone = buffer_size.clone();
+        let focus_clone = focus.clone();
+        let effects_clone = effects.clone();
+        let voice_limit_clone = voice_limit.clone();
+        let layers_clone = layers.clone();
+        let drum_map_clone = drum_map.clone();
+        let auto_gain_clone = auto_gain.clone();
+        let quantize_clone = quantize.clone();
+        let halt_clone = halt.clone();
+        let waveform_clone = waveform.clone();
+        let cmd_rx_clone = cmd_rx.clone();
+        let event_tx_clone = event_tx.clone();
+
+        let thread_handle = thread::spawn(move || {
+            // Bar grid of the compositions played so far, for quantized starts
+            let mut bar_clock = BarClock::default();
+
+            // Audio thread main loop
+            loop {
+                match cmd_rx_clone.recv() {
+                    Ok(AudioCommand::Play(params, bars)) => {
+                        is_playing_clone.store(true, Ordering::SeqCst);
+                        let _ = event_tx.send(AudioEvent::Playing);
+
+                        let composer = if silent {
+                            Ok(SystemComposer::new_silent())
+                        } else {
+                            SystemComposer::new()
+                        };
+                        let (sample_rate, _) = *output_format_clone.lock().unwrap();
+                        let buffer_size = *buffer_size_clone.lock().unwrap();
+                        let focus = *focus_clone.lock().unwrap();
+                        let effects = *effects_clone.lock().unwrap();
+                        let voice_limit = voice_limit_clone.lock().unwrap().clone();
+                        let layers = *layers_clone.lock().unwrap();
+                        let gain = auto_gain_clone.lock().unwrap().gain();
+                        let volume = f32::from_bits(volume_clone.load(Ordering::SeqCst));
+                        let composer = composer.map(|c| {
+                            c.with_halt_flag(halt_clone.clone())
+                                .with_waveform_tap(waveform_clone.clone())
+                                .with_sample_rate(sample_rate)
+                                .with_buffer_size(buffer_size)
+                                .with_focus(focus)
+                                .with_effects(effects)
+                                .with_voice_limit(voice_limit)
+                                .with_layers(layers)
+                                .with_gain(gain)
+                                .with_volume(volume)
+                        });
+                        match composer {
+                            Ok(composer) => {
+                                if *quantize_clone.lock().unwrap() {
+                                    // Hold the new params until the previous bar grid reaches a downbeat
+                                    let downbeat = Instant::now() + bar_clock.until_next_bar(Instant::now());
+                                    while Instant::now() < downbeat && !halt_clone.load(Ordering::SeqCst) {
+                                        thread::sleep(downbeat.saturating_duration_since(Instant::now()).min(Duration::from_millis(10)));
+                                    }
+                                }
+                                bar_clock.start(Instant::now(), params.tempo);
+                                match composer.compose_and_play(&params, bars) {
+                                    Ok(level) => {
+                                        // Slowly level out quiet and busy compositions
+                                        // (a halted one only played its opening)
+                                        if !halt_clone.load(Ordering::SeqCst) {
+                                            auto_gain_clone.lock().unwrap().update(level);
+                                        }
+                                        is_playing_clone.store(false, Ordering::SeqCst);
+                                        let _ = event_tx.send(AudioEvent::Stopped);
+                                    }
+                                    Err(e) => {
+                                        is_playing_clone.store(false, Ordering::SeqCst);
+                                        let _ = event_tx.send(AudioEvent::Error(e.to_string()));
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                is_playing_clone.store(false, Ordering::SeqCst);
+                                let event = if e.is::<NoAudioDevice>() {
+                                    AudioEvent::NoAudioDevice
+                                } else {
+                                    AudioEvent::Error(e.to_string())
+                                };
+                                let _ = event_tx.send(event);
+                            }
+                        }
+                    }
+
+                    Ok(AudioCommand::Stop) => {
+                        // Everything queued before the stop has been halted; play what comes after
+                        halt_clone.store(false, Ordering::SeqCst);
+                        is_playing_clone.store(false, Ordering::SeqCst);
+                        let _ = event_tx.send(AudioEvent::Stopped);
+                    }
+
+                    Ok(AudioCommand::Pause) => {
+                        is_playing_clone.store(false, Ordering::SeqCst);
+                        let _ = event_tx.send(AudioEvent::Paused);
+                        // TODO: Implement pause
+                    }
+
+                    Ok(AudioCommand::Resume) => {
+                        is_playing_clone.store(true, Ordering::SeqCst);
+                        let _ = event_tx.send(AudioEvent::Resumed);
+                        // TODO: Implement resume
+                    }
+
+                    Ok(AudioCommand::SetVolume(vol)) => {
+                        // Read as the next composition starts
+                        volume_clone.store(vol.to_bits(), Ordering::SeqCst);
+                    }
+
+                    Ok(AudioCommand::SetOutputFormat { sample_rate, bit_depth }) => {
+                        *output_format_clone.lock().unwrap() = (sample_rate, bit_depth);
+                    }
+
+                    Ok(AudioCommand::SetBufferSize(frames)) => {
+                        *buffer_size_clone.lock().unwrap() = frames;
+                    }
+
+                    Ok(AudioCommand::SetFocus(focus)) => {
+                        *focus_clone.lock().unwrap() = focus;
+                    }
+
+                    Ok(AudioCommand::SetEffects(effects)) => {
+                        *effects_clone.lock().unwrap() = effects;
+                    }
+
+                    Ok(AudioCommand::SetVoiceLimit(limit)) => {
+                        *voice_limit_clone.lock().unwrap() = limit;
+                    }
+
+                    Ok(AudioCommand::SetLayers(layers)) => {
+                        *layers_clone.lock().unwrap() = layers;
+                    }
+
+                    Ok(AudioCommand::SetDrumMap(drum_map)) => {
+                        *drum_map_clone.lock().unwrap() = drum_map;
+                    }
+
+                    Ok(AudioCommand::SetAutoGain(settings)) => {
+                        auto_gain_clone.lock().unwrap().set_settings(settings);
+                    }
+
+                    Ok(AudioCommand::SetQuantize(quantize)) => {
+                        *quantize_clone.lock().unwrap() = quantize;
+                    }
+
+                    Ok(AudioCommand::Export { path, format, params, bars }) => {
+                        let _ = event_tx.send(AudioEvent::ExportStarted);
+
+                        let export_format = ExportFormat::from_name(&format).unwrap_or(ExportFormat::Wav);
+
+                        // Exports render offline, no audio device needed
+                        let (sample_rate, bit_depth) = *output_format_clone.lock().unwrap();
+                        let composer = SystemComposer::new_silent()
+                            .with_sample_rate(sample_rate)
+                            .with_bit_depth(bit_depth)
+                            .with_focus(*focus_clone.lock().unwrap())
+                            .with_effects(*effects_clone.lock().unwrap())
+                            .with_voice_limit(voice_limit_clone.lock().unwrap().clone())
+                            .with_layers(*layers_clone.lock().unwrap())
+                            .with_drum_map(*drum_map_clone.lock().unwrap());
+                        let mut report = |fraction: f32| {
+                            let _ = event_tx.send(AudioEvent::ExportProgress(fraction));
+                        };
+                        match composer.compose_and_export(&params, bars, path.to_str().unwrap(), export_format, Some(&mut report)) {
+                            Ok(_) => {
+                                let _ = event_tx.send(AudioEvent::ExportComplete(path.to_string_lossy().to_string()));
+                            }
+                            Err(e) => {
+                                let _ = event_tx.send(AudioEvent::Error(e.to_string()));
+                            }
+                        }
+                    }
+
+                    // Shutdown requested or channel closed, exit thread
+                    Ok(AudioCommand::Shutdown) | Err(_) => break,
+                }
+            }
+        });
+
+        AudioThread {
+            cmd_tx,
+            cmd_rx,
+            event_tx: event_tx_clone,
+            event_rx,
+            thread_handle: Some(thread_handle),
+            is_playing,
+            volume,
+            output_format,
+            buffer_size,
+            focus,
+            effects,
+            voice_limit,
+            layers,
+            drum_map,
+            auto_gain,
+            quantize,
+            halt,
+            waveform,
+            silent,
+        }
+    }
+
+    /// Panic button: silence all audio now, even if the thread is stuck.
+    ///
+    /// Halts the current composition, discards queued commands so buffered
+    /// `Play`s never fire, and swaps in a fresh thread without waiting for the
+    /// old one. Volume, output format, buffer size, focus, effects, the voice
+    /// limit, the layers, the drum map, auto-gain and bar quantizing carry
+    /// over; pending events are dropped.
+    pub fn emergency_stop(&mut self) {
+        self.halt.store(true, Ordering::SeqCst);
+        while self.cmd_rx.try_recv().is_ok() {}
+
+        let fresh = Self::spawn(self.silent);
+        fresh.volume.store(self.volume.load(Ordering::SeqCst), Ordering::SeqCst);
+        *fresh.output_format.lock().unwrap() = *self.output_format.lock().unwrap();
+        *fresh.buffer_size.lock().unwrap() = *self.buffer_size.lock().unwrap();
+        *fresh.focus.lock().unwrap() = *self.focus.lock().unwrap();
+        *fresh.effects.lock().unwrap() = *self.effects.lock().unwrap();
+        *fresh.voice_limit.lock().unwrap() = self.voice_limit.lock().unwrap().clone();
+        *fresh.layers.lock().unwrap() = *self.layers.lock().unwrap();
+        *fresh.drum_map.lock().unwrap() = *self.drum_map.lock().unwrap();
+        *fresh.auto_gain.lock().unwrap() = *self.auto_gain.lock().unwrap();
+        *fresh.quantize.lock().unwrap() = *self.quantize.lock().unwrap();
+
+        // Detach rather than join: the old thread exits once its channel closes
+        let mut old = std::mem::replace(self, fresh);
+        old.thread_handle = None;
+    }
+
+    /// Stop playback within a halt check (~0.1s).
+    ///
+    /// Halts the playing composition and any `Play`s queued before this call;
+    /// settings commands still apply, and later `Play`s play normally.
+    pub fn stop(&self) -> Result<()> {
+        self.halt.store(true, Ordering::SeqCst);
+        self.send_command(AudioCommand::Stop)
+    }
+
+    /// Stop playback and wait up to `timeout` for the thread to exit.
+    ///
+    /// Commands already queued still run, so an in-progress export finishes
+    /// writing its file; compositions are halted instead of played out.
+    /// Returns `false` if the thread was still busy at the deadline, in which
+    /// case it is detached.
+    pub fn shutdown(&mut self, timeout: Duration) -> bool {
+        self.halt.store(true, Ordering::SeqCst);
+        let deadline = Instant::now() + timeout;
+
+        let Some(handle) = self.thread_handle.take() else {
+            return true;
+        };
+        if self.cmd_tx.send_deadline(AudioCommand::Shutdown, deadline).is_err() {
+            return false;
+        }
+
+        while !handle.is_finished() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        handle.join().is_ok()
+    }
+
+    pub fn send_command(&self, cmd: AudioCommand) -> Result<()> {
+        self.cmd_tx.send(cmd)?;
+        Ok(())
+    }
+
+    /// Report an idle-state change to whoever polls events (the live loop skips `Play`s itself)
+    pub fn report_idle(&self, idle: bool) {
+        let _ = self.event_tx.send(AudioEvent::Idle(idle));
+    }
+
+    /// Report a freeze toggle, so every poller can show the held state
+    pub fn report_frozen(&self, frozen: bool) {
+        let _ = self.event_tx.send(AudioEvent::Frozen(frozen));
+    }
+
+    pub fn poll_events(&self) -> Vec<AudioEvent> {
+        self.event_rx.try_iter().collect()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.is_playing.load(Ordering::SeqCst)
+    }
+
+    pub fn get_volume(&self) -> f32 {
+        f32::from_bits(self.volume.load(Ordering::SeqCst))
+    }
+
+    /// The last `num_samples` samples played, oldest first (see `WaveformTap::recent`)
+    pub fn waveform(&self, num_samples: usize) -> Vec<f32> {
+        self.waveform.recent(num_samples)
+    }
+}
+
+impl Drop for AudioThread {
+    fn drop(&mut self) {
+        self.shutdown(DROP_TIMEOUT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emergency_stop_discards_queued_plays() {
+        let mut audio = AudioThread::new_silent();
+        for _ in 0..8 {
+            audio.send_command(AudioCommand::Play(MusicalParams::default(), 4)).unwrap();
+        }
+
+        audio.emergency_stop();
+        thread::sleep(std::time::Duration::from_millis(50));
+
+        assert!(!audio.is_playing());
+        assert!(audio.poll_events().is_empty());
+    }
+
+    #[test]
+    fn test_shutdown_finishes_queued_export() {
+        let mut audio = AudioThread::new_silent();
+        let path = std::env::temp_dir().join("syssonic_shutdown_test.mid");
+        audio
+            .send_command(AudioCommand::Export {
+                path: path.clone(),
+                format: "midi".to_string(),
+                params: MusicalParams { tempo: 100.0, ..Default::default() },
+                bars: 1,
+            })
+            .unwrap();
+
+        assert!(audio.shutdown(Duration::from_secs(10)));
+        assert!(path.exists());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_stop_halts_only_what_came_before() {
+        let audio = AudioThread::new_silent();
+        audio.send_command(AudioCommand::Play(MusicalParams::default(), 4)).unwrap();
+        audio.stop().unwrap();
+        audio.send_command(AudioCommand::Play(MusicalParams::default(), 1)).unwrap();
+
+        // Played, halted, stopped, then the later composition plays in full
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut events = Vec::new();
+        while events.iter().filter(|e| matches!(e, AudioEvent::Stopped)).count() < 3 && Instant::now() < deadline {
+            events.extend(audio.poll_events());
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(events.iter().filter(|e| matches!(e, AudioEvent::Playing)).count(), 2);
+        assert!(!audio.halt.load(Ordering::SeqCst), "the stop was cleared for later plays");
+        assert!(!audio.is_playing());
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+/// Renders quieter than this (-80 dBFS) are treated as silence and ignored
+const SILENCE_RMS: f32 = 1e-4;
+
+/// Loudness normalization across live compositions.
+///
+/// An idle machine produces a sparse, quiet mix and a busy one a dense, loud
+/// one; auto-gain measures each composition and nudges the next one's master
+/// gain toward `target_dbfs`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoGainSettings {
+    pub enabled: bool,
+    pub target_dbfs: f32, // RMS level to aim for
+    pub max_gain_db: f32, // Largest boost or cut
+    pub reaction: f32,    // Share of the remaining correction applied per composition (0-1)
+}
+
+impl Default for AutoGainSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_dbfs: -20.0,
+            max_gain_db: 12.0,
+            reaction: 0.25, // ~4 compositions to settle, slow enough not to pump
+        }
+    }
+}
+
+/// Master gain that follows the measured level of previous compositions
+#[derive(Debug, Clone, Copy)]
+pub struct AutoGain {
+    settings: AutoGainSettings,
+    gain_db: f32,
+}
+
+impl AutoGain {
+    pub fn new(settings: AutoGainSettings) -> Self {
+        Self { settings, gain_db: 0.0 }
+    }
+
+    /// Replace the settings, keeping the gain reached so far
+    pub fn set_settings(&mut self, settings: AutoGainSettings) {
+        self.settings = settings;
+    }
+
+    /// Linear gain for the next composition (1.0 when disabled)
+    pub fn gain(&self) -> f32 {
+        if self.settings.enabled {
+            10f32.powf(self.gain_db / 20.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Feed the RMS level of a composition rendered with `gain()` applied
+    pub fn update(&mut self, rms: f32) {
+        // A halted or empty render says nothing about loudness
+        if !self.settings.enabled || rms < SILENCE_RMS {
+            return;
+        }
+
+        let unity_db = 20.0 * rms.log10() - self.gain_db;
+        let max = self.settings.max_gain_db.max(0.0);
+        let wanted_db = (self.settings.target_dbfs - unity_db).clamp(-max, max);
+        self.gain_db += (wanted_db - self.gain_db) * self.settings.reaction.clamp(0.0, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db(linear: f32) -> f32 {
+        20.0 * linear.log10()
+    }
+
+    #[test]
+    fn test_gain_moves_gradually_toward_target() {
+        let mut auto_gain = AutoGain::new(AutoGainSettings { enabled: true, ..Default::default() });
+
+        // A quiet mix at -32 dBFS wants +12 dB, reached a quarter at a time
+        let quiet_rms = 10f32.powf(-32.0 / 20.0);
+        auto_gain.update(quiet_rms * auto_gain.gain());
+        assert!((db(auto_gain.gain()) - 3.0).abs() < 0.01);
+
+        for _ in 0..30 {
+            auto_gain.update(quiet_rms * auto_gain.gain());
+        }
+        assert!((db(auto_gain.gain()) - 12.0).abs() < 0.01);
+
+        // Silence (a halted composition) leaves the gain alone
+        let before = auto_gain.gain();
+        auto_gain.update(0.0);
+        assert_eq!(auto_gain.gain(), before);
+    }
+
+    #[test]
+    fn test_disabled_is_unity() {
+        let mut auto_gain = AutoGain::new(AutoGainSettings::default());
+        auto_gain.update(0.9);
+        assert_eq!(auto_gain.gain(), 1.0);
+    }
+}
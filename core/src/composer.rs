@@ -0,0 +1,2019 @@
+use crate::audio_export;
+use crate::export_metadata::{self, LoopInfo};
+use crate::mapper::MusicalParams;
+pub use crate::midi_export::DrumMap;
+use crate::playback::AudioOutput;
+pub use crate::playback::{NoAudioDevice, WaveformTap, WAVEFORM_CAPACITY};
+use crate::timeline::{self, NoteEvent};
+use tunes::prelude::*;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+pub const BEATS_PER_BAR: u32 = 4; // Compositions are in 4/4
+pub const SUPPORTED_SAMPLE_RATES: [u32; 3] = [44100, 48000, 96000];
+pub const SUPPORTED_BIT_DEPTHS: [u16; 2] = [16, 24];
+pub const DEFAULT_BITRATE_KBPS: u32 = 192; // MP3 and Ogg exports
+
+/// Share of export progress spent rendering; encoding and tagging take the rest
+const RENDER_PROGRESS: f32 = 0.9;
+
+/// Check a sample rate and optional bit depth against what export and playback support
+pub fn validate_output_format(sample_rate: u32, bit_depth: Option<u16>) -> Result<()> {
+    if !SUPPORTED_SAMPLE_RATES.contains(&sample_rate) {
+        anyhow::bail!("Unsupported sample rate {} Hz (use 44100, 48000 or 96000)", sample_rate);
+    }
+    if let Some(bits) = bit_depth.filter(|bits| !SUPPORTED_BIT_DEPTHS.contains(bits)) {
+        anyhow::bail!("Unsupported bit depth {} (use 16 or 24)", bits);
+    }
+    Ok(())
+}
+
+/// Playback buffer sizes (frames) accepted by `validate_buffer_size`
+pub const MIN_BUFFER_SIZE: u32 = 64;
+pub const MAX_BUFFER_SIZE: u32 = 8192;
+
+/// Check a playback buffer size: a power of two between 64 and 8192 frames
+pub fn validate_buffer_size(frames: u32) -> Result<()> {
+    if !frames.is_power_of_two() || !(MIN_BUFFER_SIZE..=MAX_BUFFER_SIZE).contains(&frames) {
+        anyhow::bail!(
+            "Unsupported buffer size {} (use a power of two from {} to {})",
+            frames, MIN_BUFFER_SIZE, MAX_BUFFER_SIZE
+        );
+    }
+    Ok(())
+}
+
+/// How long `bars` bars last at `tempo_bpm`, in seconds
+pub fn bars_to_seconds(tempo_bpm: f32, beats_per_bar: u32, bars: usize) -> f32 {
+    bars as f32 * beats_per_bar as f32 * 60.0 / tempo_bpm.max(1.0)
+}
+
+/// Tracks where the bar grid of the playing compositions falls, so the next
+/// composition can start on a downbeat instead of whenever it is ready.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BarClock {
+    downbeat: Option<(Instant, f32)>, // Start of the last composition and its bar length in seconds
+}
+
+impl BarClock {
+    /// Record that a composition at `tempo_bpm` starts at `at`
+    pub fn start(&mut self, at: Instant, tempo_bpm: f32) {
+        self.downbeat = Some((at, bars_to_seconds(tempo_bpm, BEATS_PER_BAR, 1)));
+    }
+
+    /// Time from `now` until the next bar line (zero before the first composition)
+    pub fn until_next_bar(&self, now: Instant) -> Duration {
+        let Some((downbeat, bar_secs)) = self.downbeat else {
+            return Duration::ZERO;
+        };
+        let into_bar = now.saturating_duration_since(downbeat).as_secs_f32() % bar_secs;
+        // Already on the line (within a millisecond): start now rather than a bar late
+        if into_bar < 0.001 || bar_secs - into_bar < 0.001 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f32(bar_secs - into_bar)
+    }
+}
+
+pub struct SystemComposer {
+    output: Option<AudioOutput>, // None = silent (no audio device)
+    halt: Arc<AtomicBool>,       // Set to cut playback short
+    waveform: WaveformTap,       // Receives every sample played, for oscilloscope views
+
+    // Output format (validate with `validate_output_format`)
+    sample_rate: u32,
+    buffer_size: Option<u32>, // Playback frames per callback (None = device default)
+    bit_depth: Option<u16>, // None = 16-bit WAV, 24-bit FLAC
+    bitrate_kbps: u32,      // MP3 and Ogg exports
+
+    // Featured subsystem (None = balanced mix)
+    focus: Option<Focus>,
+
+    // User overrides for the per-voice effects
+    effects: EffectSettings,
+
+    // Layers kept when a stressed machine would stack too many
+    voice_limit: Option<VoiceLimit>,
+
+    // Layers the arrangement includes at all
+    layers: Layers,
+
+    // Percussion notes in MIDI exports
+    drum_map: DrumMap,
+
+    // Live playback master gain (auto-gain) and the user's volume on top
+    gain: f32,
+    volume: f32,
+
+    // Export mixdown
+    stereo_width: f32,         // 0.0 = mono, 1.0 = normal, 2.0 = extra wide
+    bass_mono_hz: Option<f32>, // Crossover below which the mix is summed to mono
+    mono: bool,                // Sum exports to a single channel
+    intro_bars: usize,         // Build-up bars before the full arrangement (0-2)
+}
+
+impl SystemComposer {
+    /// Composer that plays on the default output device.
+    ///
+    /// Fails with `NoAudioDevice` when there is none; exports never need one (`new_silent`).
+    pub fn new() -> Result<Self> {
+        let output = AudioOutput::open()?;
+        Ok(Self { output: Some(output), ..Self::new_silent() })
+    }
+
+    /// Composer without an audio device, for CI, headless servers and exports.
+    ///
+    /// Playback still builds and renders the full composition, then discards the samples.
+    pub fn new_silent() -> Self {
+        Self {
+            output: None,
+            halt: Arc::new(AtomicBool::new(false)),
+            waveform: WaveformTap::default(),
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            buffer_size: None,
+            bit_depth: None,
+            bitrate_kbps: DEFAULT_BITRATE_KBPS,
+            focus: None,
+            effects: EffectSettings::default(),
+            voice_limit: None,
+            layers: Layers::default(),
+            drum_map: DrumMap::default(),
+            gain: 1.0,
+            volume: 1.0,
+            stereo_width: 1.0,
+            bass_mono_hz: None,
+            mono: false,
+            intro_bars: 0,
+        }
+    }
+
+    /// Share a flag that silences playback as soon as it is set
+    pub fn with_halt_flag(mut self, halt: Arc<AtomicBool>) -> Self {
+        self.halt = halt;
+        self
+    }
+
+    /// Share the tap that live playback copies its output into
+    pub fn with_waveform_tap(mut self, waveform: WaveformTap) -> Self {
+        self.waveform = waveform;
+        self
+    }
+
+    /// Sample rate for exports and live playback (falls back to the device
+    /// default if the output device can't run at it)
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Playback buffer in frames (see `validate_buffer_size`): larger survives
+    /// heavy arrangements on slow machines, smaller starts and stops sooner
+    pub fn with_buffer_size(mut self, frames: Option<u32>) -> Self {
+        self.buffer_size = frames;
+        self
+    }
+
+    /// Bit depth for WAV/FLAC exports; `None` keeps each format's default
+    pub fn with_bit_depth(mut self, bit_depth: Option<u16>) -> Self {
+        self.bit_depth = bit_depth;
+        self
+    }
+
+    /// Bitrate in kbps for MP3/Ogg exports (MP3 snaps to the nearest rate LAME supports)
+    pub fn with_bitrate(mut self, bitrate_kbps: u32) -> Self {
+        self.bitrate_kbps = bitrate_kbps.max(8);
+        self
+    }
+
+    /// Feature one subsystem's voices and fade the rest to background
+    pub fn with_focus(mut self, focus: Option<Focus>) -> Self {
+        self.focus = focus;
+        self
+    }
+
+    /// Set the mid/side stereo width applied to WAV/FLAC exports (0.0-2.0)
+    pub fn with_stereo_width(mut self, width: f32) -> Self {
+        self.stereo_width = width.clamp(0.0, 2.0);
+        self
+    }
+
+    /// Sum WAV/FLAC exports to mono below `crossover_hz` (e.g. 120), so widened
+    /// or panned bass can't cancel on club systems or vinyl. `None` keeps it stereo.
+    pub fn with_bass_mono(mut self, crossover_hz: Option<f32>) -> Self {
+        self.bass_mono_hz = crossover_hz.filter(|hz| *hz > 0.0);
+        self
+    }
+
+    /// Export a single-channel mixdown (phone speakers, PA systems)
+    pub fn with_mono(mut self, mono: bool) -> Self {
+        self.mono = mono;
+        self
+    }
+
+    /// Override the delay, chorus and reverb amounts (out-of-range values are clamped)
+    pub fn with_effects(mut self, effects: EffectSettings) -> Self {
+        self.effects = effects.clamped();
+        self
+    }
+
+    /// Drop the least important layers beyond `limit.max_voices` (None = keep all)
+    pub fn with_voice_limit(mut self, limit: Option<VoiceLimit>) -> Self {
+        self.voice_limit = limit;
+        self
+    }
+
+    /// Leave disabled layers out of the arrangement entirely
+    pub fn with_layers(mut self, layers: Layers) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// MIDI notes for the drum voices in MIDI exports (default General MIDI)
+    pub fn with_drum_map(mut self, drum_map: DrumMap) -> Self {
+        self.drum_map = drum_map;
+        self
+    }
+
+    /// Scale every voice during live playback (exports are unaffected)
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain.max(0.0);
+        self
+    }
+
+    /// Output volume during live playback, 0.0-1.0 (exports are unaffected)
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Prepend a 1-2 bar intro to exports where voices enter one at a time
+    pub fn with_intro(mut self, bars: usize) -> Self {
+        self.intro_bars = bars.min(2);
+        self
+    }
+
+    /// Play a composition, returning the RMS level of what was rendered.
+    ///
+    /// The level is measured before the volume, so auto-gain doesn't undo it.
+    pub fn compose_and_play(&self, params: &MusicalParams, duration_bars: usize) -> Result<f32> {
+        let mut mix = self.live_mix(params, duration_bars);
+        let level = match &self.output {
+            Some(output) => output.play(&mix, self.sample_rate, self.buffer_size, &self.halt, &self.waveform)?,
+            None => audio_export::rms(&mix.render_to_buffer(self.sample_rate as f32)),
+        };
+
+        Ok(if self.volume > 0.0 { level / self.volume } else { 0.0 })
+    }
+
+    /// The composition as played live: every voice scaled by gain and volume
+    fn live_mix(&self, params: &MusicalParams, duration_bars: usize) -> ShapedMix {
+        let mut mixer = self.mix(params, duration_bars);
+        let master = self.gain * self.volume;
+        if master != 1.0 {
+            for track in &mut mixer.tracks {
+                track.volume *= master;
+            }
+        }
+
+        self.shape(mixer, params)
+    }
+
+    /// Export a composition, reporting progress (0.0-1.0) to `progress`
+    /// after each rendered bar and once more, at exactly 1.0, when the file is written
+    pub fn compose_and_export(
+        &self,
+        params: &MusicalParams,
+        duration_bars: usize,
+        output_path: &str,
+        format: ExportFormat,
+        mut progress: Option<&mut dyn FnMut(f32)>,
+    ) -> Result<()> {
+        // Same composition as compose_and_play, plus the intro bars
+        let total_bars = duration_bars + self.intro_bars;
+        let mixer = self.mix_with_intro(params, duration_bars);
+
+        // Embed tempo/meter so DAWs can warp the loop without guessing the BPM
+        let info = LoopInfo {
+            bpm: params.tempo,
+            beats: total_bars as u32 * BEATS_PER_BAR,
+            time_signature: (BEATS_PER_BAR as u8, 4),
+        };
+
+        let mut report = |fraction: f32| {
+            if let Some(progress) = progress.as_deref_mut() {
+                progress(fraction);
+            }
+        };
+        write_atomically(output_path, |path| self.write_export(mixer, params, path, format, info, &mut report))?;
+        report(1.0);
+        Ok(())
+    }
+
+    /// Export one segment per snapshot, joined end to end into a single
+    /// evolving track (any format but MIDI).
+    ///
+    /// Each segment is `duration_bars` of its own params, and every boundary
+    /// crossfades over `crossfade_secs`. The intro, if any, opens the first
+    /// segment. Tempo changes between segments, so no loop tempo is embedded.
+    pub fn compose_and_export_sequence(
+        &self,
+        segments: &[MusicalParams],
+        duration_bars: usize,
+        crossfade_secs: f32,
+        output_path: &str,
+        format: ExportFormat,
+    ) -> Result<()> {
+        if format == ExportFormat::Midi {
+            anyhow::bail!("Snapshot sequences export as audio (WAV, FLAC, MP3 or Ogg), not MIDI");
+        }
+        if segments.is_empty() {
+            anyhow::bail!("No snapshots to export");
+        }
+
+        let rendered: Vec<Vec<f32>> = segments
+            .iter()
+            .enumerate()
+            .map(|(i, params)| {
+                let mixer = if i == 0 {
+                    self.mix_with_intro(params, duration_bars)
+                } else {
+                    self.mix(params, duration_bars)
+                };
+                self.shape(mixer, params).render_to_buffer(self.sample_rate as f32)
+            })
+            .collect();
+        let fade_frames = (crossfade_secs.max(0.0) * self.sample_rate as f32) as usize;
+        let samples = audio_export::crossfade_concat(&rendered, fade_frames);
+
+        write_atomically(output_path, |path| self.write_audio(samples, path, format))
+    }
+
+    /// The export mix for `params`: `duration_bars` after any intro bars
+    fn mix_with_intro(&self, params: &MusicalParams, duration_bars: usize) -> Mixer {
+        let mut mixer = self.mix(params, duration_bars + self.intro_bars);
+        if self.intro_bars > 0 {
+            let intro_secs = bars_to_seconds(params.tempo, BEATS_PER_BAR, self.intro_bars);
+            apply_intro(&mut mixer, intro_secs);
+        }
+        mixer
+    }
+
+    /// Encode and tag `mixer` at `path` in `format`
+    fn write_export(
+        &self,
+        mixer: Mixer,
+        params: &MusicalParams,
+        path: &str,
+        format: ExportFormat,
+        info: LoopInfo,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<()> {
+        if format == ExportFormat::Midi {
+            crate::midi_export::export_midi(&mixer, path, &self.drum_map)?;
+        } else {
+            // Render once, then shape the stereo image before encoding
+            let bar_secs = bars_to_seconds(params.tempo, BEATS_PER_BAR, 1);
+            let samples = self
+                .shape(mixer, params)
+                .render_with_progress(self.sample_rate as f32, bar_secs, |rendered| progress(rendered * RENDER_PROGRESS));
+            self.write_audio(samples, path, format)?;
+        }
+
+        match format {
+            ExportFormat::Wav => export_metadata::tag_wav(path, info)?,
+            ExportFormat::Flac => export_metadata::tag_flac(path, info)?,
+            ExportFormat::Midi => {} // Tempo and meter are already in the MIDI tempo track
+            ExportFormat::Mp3 | ExportFormat::Ogg => {} // Lossy clips for sharing, not DAW loops
+        }
+
+        Ok(())
+    }
+
+    /// Shape the stereo image of rendered samples, then encode them as WAV, FLAC, MP3 or Ogg
+    fn write_audio(&self, mut samples: Vec<f32>, path: &str, format: ExportFormat) -> Result<()> {
+        audio_export::apply_stereo_width(&mut samples, self.stereo_width);
+        if let Some(crossover_hz) = self.bass_mono_hz {
+            audio_export::apply_bass_mono(&mut samples, crossover_hz, self.sample_rate);
+        }
+        let (samples, channels) = if self.mono {
+            (audio_export::downmix_to_mono(&samples), 1)
+        } else {
+            (samples, 2)
+        };
+
+        match format {
+            ExportFormat::Wav => {
+                let bits = self.bit_depth.unwrap_or(16);
+                audio_export::write_wav(path, &samples, channels, self.sample_rate, bits)
+            }
+            ExportFormat::Mp3 => audio_export::write_mp3(path, &samples, channels, self.sample_rate, self.bitrate_kbps),
+            ExportFormat::Ogg => audio_export::write_ogg(path, &samples, channels, self.sample_rate, self.bitrate_kbps),
+            _ => {
+                let bits = self.bit_depth.unwrap_or(24);
+                audio_export::write_flac(path, &samples, channels, self.sample_rate, bits)
+            }
+        }
+    }
+
+    /// Render the composition to interleaved stereo samples without touching the audio device
+    pub fn render_to_buffer(&self, params: &MusicalParams, duration_bars: usize, sample_rate: u32) -> Vec<f32> {
+        let mixer = self.mix(params, duration_bars);
+        self.shape(mixer, params).render_to_buffer(sample_rate as f32)
+    }
+
+    /// The scheduled notes of the composition, without rendering any audio
+    pub fn arrange(&self, params: &MusicalParams, duration_bars: usize) -> Vec<NoteEvent> {
+        timeline::note_events(&self.mix(params, duration_bars))
+    }
+
+    /// Compose and mix down, rebalanced for the focused subsystem
+    fn mix(&self, params: &MusicalParams, duration_bars: usize) -> Mixer {
+        let mut mixer = self.compose(params, duration_bars).into_mixer();
+        if let Some(limit) = &self.voice_limit {
+            limit.apply(&mut mixer);
+        }
+        if let Some(focus) = self.focus {
+            focus.apply(&mut mixer);
+        }
+
+        // A draining battery plays quieter, down to half volume when empty
+        let battery = params.battery_volume_mult.clamp(0.5, 1.0);
+        if battery != 1.0 {
+            for track in &mut mixer.tracks {
+                track.volume *= battery;
+            }
+        }
+        mixer
+    }
+
+    /// Split the voices tunes can't process itself out of `mixer`: the
+    /// distorted ones when their distortion type needs a custom shaper (soft
+    /// clip stays on tunes' own distortion), and the melody and pad when the
+    /// kick ducks them
+    fn shape(&self, mut mixer: Mixer, params: &MusicalParams) -> ShapedMix {
+        let mut shaped = Vec::new();
+        let kind = self.effects.distortion_type;
+        if kind != DistortionType::SoftClip {
+            for (voice, amount) in [("bass", bass_distortion(params)), ("swap_burst", swap_burst_distortion(params))] {
+                let mut layer = mixer.clone();
+                layer.tracks.retain(|track| track.name.as_deref() == Some(voice));
+                if !layer.tracks.is_empty() {
+                    shaped.push((layer, Shaper::new(kind, amount)));
+                }
+            }
+            mixer.tracks.retain(|track| !matches!(track.name.as_deref(), Some("bass" | "swap_burst")));
+        }
+
+        let ducked = self.ducker(params).and_then(|ducker| {
+            let mut layer = mixer.clone();
+            layer.tracks.retain(|track| matches!(track.name.as_deref(), Some("melody" | "pad")));
+            mixer.tracks.retain(|track| !matches!(track.name.as_deref(), Some("melody" | "pad")));
+            (!layer.tracks.is_empty()).then_some((layer, ducker))
+        });
+
+        ShapedMix { dry: mixer, shaped, ducked }
+    }
+
+    /// The kick's sidechain envelope, or None when ducking is off or no kick plays
+    fn ducker(&self, params: &MusicalParams) -> Option<Ducker> {
+        let amount = self.effects.sidechain_amount;
+        if amount <= 0.0 || !self.layers.drums || params.kick_hits.is_empty() {
+            return None;
+        }
+        // A quiet network still pumps gently; heavy traffic digs in
+        let depth = amount * (0.3 + 0.7 * params.sidechain_pump.clamp(0.0, 1.0));
+        Some(Ducker::new(&params.kick_hits, params.grid.steps(), params.tempo, depth))
+    }
+
+    /// Build the full arrangement for `params`.
+    ///
+    /// Shared by playback and export so both always render the same layers.
+    fn compose(&self, params: &MusicalParams, duration_bars: usize) -> Composition {
+        let mut comp = Composition::new(Tempo::new(params.tempo));
+        let sixteenth = comp.tempo().sixteenth_note();
+        let eighth = comp.tempo().eighth_note();
+        let quarter = comp.tempo().quarter_note();
+        let steps = params.grid.steps(); // Percussion grid, 16 steps per bar by default
+        let grid_step = quarter * 4.0 / steps as f32;
+        let fx = &self.effects;
+        let layers = self.layers;
+        let key = 2f32.powf(params.key_shift as f32 / 12.0); // Stress modulation
+        // Am7 + octave unless the mapper drew the chord from the tonal scale (already in key)
+        let chord_tones = match params.chord_notes.as_slice() {
+            tones if tones.len() >= 3 => tones.to_vec(),
+            _ => vec![A3 * key, C4 * key, E4 * key, G4 * key, A4 * key],
+        };
+        let vram_room = vram_room_size(params); // GPU memory lengthens the melody and pad tails
+
+        // === MELODY (CPU Usage) ===
+        // Create an evolving melody using the CPU-driven notes
+        if layers.melody {
+            let mut melody = comp.instrument("melody", &Instrument::synth_lead())
+                .filter(Filter::low_pass(params.filter_cutoff, 0.6));
+            if let Some(sweep) = fx.filter_lfo(params.tempo) {
+                melody = melody.modulate(sweep);
+            }
+            for effect in fx.reverb(params.reverb_mix, vram_room.max(0.5)) {
+                melody = melody.effect(effect);
+            }
+            melody.effect(Effect::delay(quarter * fx.delay_subdivision.beats(), fx.delay_feedback, fx.wet(fx.delay_mix)));
+
+            // Play the melody pattern multiple times with variations. Notes keep the
+            // eighth/sixteenth grid; disk I/O only changes how long each one sounds.
+            let note_scale = params.melody_note_scale.clamp(0.5, 1.5);
+            let mut start = 0.0;
+            for _ in 0..duration_bars {
+                for (i, &note) in params.melody_notes.iter().enumerate() {
+                    let step = if i % 2 == 0 { eighth } else { sixteenth };
+                    comp.instrument("melody", &Instrument::synth_lead())
+                        .at(start)
+                        .note(&[note], step * note_scale);
+                    start += step;
+                }
+            }
+
+            // === HARMONY (Sustained Load) ===
+            // Each extra harmonic voice shadows the melody a third, fifth or seventh up
+            // the scale, softer the higher it stacks
+            for (voice, line) in params.harmony_notes.iter().enumerate() {
+                let name = format!("harmony{}", voice + 1);
+                let velocity = 0.4 - voice as f32 * 0.08;
+                comp.instrument(&name, &Instrument::synth_pad())
+                    .filter(Filter::low_pass(params.filter_cutoff, 0.5));
+
+                let mut start = 0.0;
+                for _ in 0..duration_bars {
+                    for (i, &note) in line.iter().enumerate() {
+                        let step = if i % 2 == 0 { eighth } else { sixteenth };
+                        comp.instrument(&name, &Instrument::synth_pad())
+                            .at(start)
+                            .note_with_velocity(&[note], step * note_scale, velocity);
+                        start += step;
+                    }
+                }
+            }
+        }
+
+        // === BASS (Memory Usage + Swap) ===
+        // Deep, sustained bass notes that reflect memory pressure
+        // Swap usage adds distortion (other types are shaped after rendering, see `shape`)
+        let soft_clip = fx.distortion_type == DistortionType::SoftClip;
+        if layers.bass {
+            let bass = comp.instrument("bass", &Instrument::sub_bass())
+                .filter(Filter::low_pass(800.0, 0.8));
+            if soft_clip {
+                bass.effect(Effect::distortion(bass_distortion(params)));
+            }
+
+            for _ in 0..duration_bars {
+                // Whole note bass pattern
+                comp.instrument("bass", &Instrument::sub_bass())
+                    .note_with_velocity(&[params.bass_note], quarter * 4.0, params.bass_velocity);
+            }
+        }
+
+        // === SWAP BURST (Swap In/Out Rate) ===
+        // Heavily distorted bass hit on active paging, halving every bar, so
+        // the moment of memory pressure stands out rather than a constant state
+        if layers.bass && params.swap_activity > 0.05 {
+            let burst = comp.instrument("swap_burst", &Instrument::sub_bass())
+                .filter(Filter::low_pass(1200.0, 0.7));
+            if soft_clip {
+                burst.effect(Effect::distortion(swap_burst_distortion(params)));
+            }
+
+            for bar in 0..duration_bars {
+                let velocity = params.swap_activity * 0.5f32.powi(bar as i32);
+                if velocity < 0.02 {
+                    break;
+                }
+                comp.instrument("swap_burst", &Instrument::sub_bass())
+                    .at(bar as f32 * quarter * 4.0)
+                    .note_with_velocity(&[params.bass_note], quarter * 2.0, velocity);
+            }
+        }
+
+        // === GPU POWER RUMBLE (GPU Power Draw) ===
+        // Sub-oscillator an octave below the bass that swells as the card pulls more watts
+        if layers.gpu && params.gpu_power_rumble > 0.05 {
+            comp.instrument("gpu_rumble", &Instrument::sub_bass())
+                .filter(Filter::low_pass(120.0, 0.9));
+
+            for _ in 0..duration_bars {
+                comp.instrument("gpu_rumble", &Instrument::sub_bass())
+                    .note_with_velocity(&[params.bass_note * 0.5], quarter * 4.0, params.gpu_power_rumble);
+            }
+        }
+
+        // === DRUMS (Disk I/O) ===
+        // Dynamic percussion based on disk activity
+        if layers.drums {
+            for bar in 0..duration_bars {
+                comp.track("drums")
+                    .drum_grid(steps, grid_step)
+                    .kick(&params.kick_hits)
+                    .snare(&params.snare_hits);
+            }
+        }
+
+        // === DISK READS / WRITES (Toms & Rimshots) ===
+        // Reads and writes get their own voices so read-heavy and
+        // write-heavy workloads are distinguishable
+        if layers.drums && !params.tom_hits.is_empty() {
+            for _ in 0..duration_bars {
+                comp.track("disk_reads")
+                    .drum_grid(steps, grid_step)
+                    .tom(&params.tom_hits);
+            }
+        }
+
+        if layers.drums && !params.rimshot_hits.is_empty() {
+            for _ in 0..duration_bars {
+                comp.track("disk_writes")
+                    .drum_grid(steps, grid_step)
+                    .rimshot(&params.rimshot_hits);
+            }
+        }
+
+        // === CONNECTION CHURN (Plucks) ===
+        // Short staccato plucks when connections open and close quickly,
+        // a different fingerprint than raw bandwidth
+        if !params.pluck_hits.is_empty() {
+            let pluck_note = params.melody_notes.first().copied().unwrap_or(A4) * 2.0;
+            let mut plucks = comp.instrument("connections", &Instrument::pluck());
+            for _ in 0..duration_bars {
+                for step in 0..steps {
+                    plucks = if params.pluck_hits.contains(&step) {
+                        plucks.note_with_velocity(&[pluck_note], grid_step * 0.5, 0.6).wait(grid_step * 0.5)
+                    } else {
+                        plucks.wait(grid_step)
+                    };
+                }
+            }
+        }
+
+        // === KERNEL TICKS (Context Switches + Interrupts) ===
+        // Faint high ticks on a 32nd-note grid, spreading to fill it as the
+        // scheduler gets busier: the kernel's pulse under everything else
+        if params.kernel_ticks > 0.1 {
+            comp.instrument("kernel", &Instrument::noise())
+                .filter(Filter::high_pass(6000.0, 0.7));
+
+            let tick_step = quarter / 8.0;
+            let ticks_per_bar = (params.kernel_ticks * 32.0).round() as usize;
+            let mut ticks = comp.instrument("kernel", &Instrument::noise());
+            for _ in 0..duration_bars {
+                for step in 0..32 {
+                    // 13 is coprime with 32, so each added tick lands away from the last
+                    ticks = if (step * 13) % 32 < ticks_per_bar {
+                        ticks.note_with_velocity(&[A3], tick_step * 0.25, 0.1 + params.kernel_ticks * 0.15).wait(tick_step * 0.75)
+                    } else {
+                        ticks.wait(tick_step)
+                    };
+                }
+            }
+        }
+
+        // === AMBIENT PAD (Temperature) ===
+        // Atmospheric layer that gets more present as temperature rises
+        if layers.pad && params.reverb_mix > 0.2 {
+            let mut pad = comp.instrument("pad", &Instrument::synth_pad())
+                .filter(Filter::low_pass(params.filter_cutoff * 1.5, 0.3));
+            if let Some(sweep) = fx.filter_lfo(params.tempo) {
+                pad = pad.modulate(sweep);
+            }
+            for effect in fx.reverb(params.reverb_mix, vram_room.max(0.8)) {
+                pad = pad.effect(effect);
+            }
+            pad.effect(Effect::chorus(fx.chorus_rate, fx.chorus_depth, fx.wet(0.3)));
+
+            // Battery drain pulses the pad faster as power draw rises
+            if params.battery_tremolo_rate > 0.0 {
+                comp.instrument("pad", &Instrument::synth_pad())
+                    .effect(Effect::tremolo(params.battery_tremolo_rate, 0.5));
+            }
+
+            // Sustained chords: the triad an octave under the arpeggio
+            let triad: Vec<f32> = chord_tones[..3].iter().map(|tone| tone * 0.5).collect();
+            for _ in 0..duration_bars {
+                comp.instrument("pad", &Instrument::synth_pad())
+                    .notes(&triad, quarter * 4.0);
+            }
+        }
+
+        // === ARPEGGIATOR (Load Average) ===
+        // Extra harmonic voices cycle through the chord; more load = more tones, faster arp
+        if params.harmonic_voices > 1 {
+            let arp_tones = &chord_tones[..(params.harmonic_voices + 1).min(chord_tones.len())];
+            let step = quarter / params.arp_rate;
+            let steps_per_bar = (4.0 * params.arp_rate).round() as usize;
+
+            comp.instrument("arp", &Instrument::pluck())
+                .filter(Filter::low_pass(params.filter_cutoff, 0.5));
+
+            for _ in 0..duration_bars {
+                for i in 0..steps_per_bar {
+                    comp.instrument("arp", &Instrument::pluck())
+                        .note_with_velocity(&[arp_tones[i % arp_tones.len()]], step, 0.5);
+                }
+            }
+        }
+
+        // === HI-HATS (Network Activity + Process Count) ===
+        // Hi-hat density driven by process count
+        let hihat_hits: Vec<usize> = if params.hihat_density < 0.3 {
+            // Sparse: every other eighth note
+            vec![0, 4, 8, 12]
+        } else if params.hihat_density < 0.7 {
+            // Medium: every eighth note
+            (0..16).filter(|i| i % 2 == 0).collect()
+        } else {
+            // Dense: every sixteenth note
+            (0..16).collect()
+        };
+        let hihat_hits = params.grid.rescale(&hihat_hits);
+
+        if layers.hihats {
+            for _ in 0..duration_bars {
+                comp.track("hihats")
+                    .drum_grid(steps, grid_step)
+                    .hihat(&hihat_hits);
+            }
+        }
+
+        // === GPU VOICE (GPU Utilization) ===
+        // Separate melodic voice for GPU activity
+        if let Some(gpu_notes) = &params.gpu_notes {
+            if layers.gpu && params.gpu_intensity > 0.1 {
+                // Memory clock brightens the voice (0.6-1.8x the main cutoff)
+                let gpu_cutoff = params.filter_cutoff * (0.6 + params.gpu_brightness * 1.2);
+                // GPU temperature sweeps the flanger: 0.5 Hz at 40°C up to 3.0 Hz at 80°C
+                // (`MetricsMapper::map_gpu_metrics`)
+                comp.instrument("gpu", &Instrument::analog_synth())
+                    .filter(Filter::low_pass(gpu_cutoff, 0.7))
+                    .effect(Effect::chorus(params.gpu_chorus_depth, 0.8, fx.wet(0.4)))
+                    .effect(Effect::flanger(params.gpu_flanger_rate, 3.0, 0.5, fx.wet(0.3)));
+
+                // Core clock detunes a second oscillator against each note
+                let detune_ratio = 2f32.powf(params.gpu_detune / 1200.0);
+
+                for _ in 0..duration_bars {
+                    for &note in gpu_notes.iter() {
+                        let duration = eighth * params.gpu_intensity.max(0.5); // Slower when low util
+                        let voices = if params.gpu_detune > 1.0 {
+                            vec![note, note * detune_ratio]
+                        } else {
+                            vec![note]
+                        };
+                        comp.instrument("gpu", &Instrument::analog_synth())
+                            .note_with_velocity(&voices, duration, params.gpu_intensity);
+                    }
+                }
+            }
+        }
+
+        // === PER-CORE POLYRHYTHMS (Per-Core CPU) ===
+        // Each core voice gets its own shaker pattern (the mapper caps how many)
+        for (core_idx, pattern) in params.core_patterns.iter().enumerate() {
+            if layers.cores && !pattern.is_empty() && params.rhythm_polyrhythm_factor > 0.2 {
+                for _ in 0..duration_bars {
+                    comp.track(&format!("core{}", core_idx))
+                        .drum_grid(steps, grid_step)
+                        .shaker(pattern);
+                }
+            }
+        }
+
+        // === PROCESS MELODIES (Top Processes) ===
+        // Mini-melodies for top processes (limit to top 3 for clarity)
+        let shown = if layers.processes { params.process_melodies.len().min(3) } else { 0 };
+        let top_melodies = &params.process_melodies[..shown];
+        for (instrument_name, (process, melody)) in process_instrument_names(top_melodies).iter().zip(top_melodies) {
+            // Disk-bound processes knock (marimba) instead of chime (music box)
+            let timbre = if params.io_bound_processes.contains(process) {
+                Instrument::marimba()
+            } else {
+                Instrument::music_box()
+            };
+            comp.instrument(instrument_name, &timbre);
+
+            for _ in 0..duration_bars {
+                for &note in melody.iter() {
+                    comp.instrument(instrument_name, &timbre)
+                        .note(&[note], sixteenth * 3.0);
+                }
+            }
+        }
+
+        // === FAN NOISE (Fan Speeds) ===
+        // Ambient wind noise based on fan RPM
+        if layers.fans && params.fan_noise_level > 0.1 {
+            comp.instrument("fans", &Instrument::noise())
+                .filter(Filter::high_pass(2000.0, 0.5));
+
+            for _ in 0..duration_bars {
+                comp.instrument("fans", &Instrument::noise())
+                    .note_with_velocity(&[A3], quarter * 4.0, params.fan_noise_level * 0.3);
+            }
+        }
+
+        // === BATTERY WARNING (Time to Empty) ===
+        // Repeated high ping that climbs in pitch and density as the battery runs out
+        if let Some(urgency) = params.battery_warning {
+            let warning_note = A5 * 2.0f32.powf(urgency); // A5 → A6
+            let pings_per_bar = 1 + (urgency * 3.0) as usize; // 1-4 pings
+
+            comp.instrument("battery_warning", &Instrument::music_box());
+
+            for _ in 0..duration_bars {
+                for _ in 0..pings_per_bar {
+                    comp.instrument("battery_warning", &Instrument::music_box())
+                        .note_with_velocity(&[warning_note], quarter * 4.0 / pings_per_bar as f32, 0.4 + urgency * 0.4);
+                }
+            }
+        }
+
+        comp
+    }
+}
+
+/// Longest reverb tail, reached with VRAM full
+const MAX_REVERB_DECAY_SECS: f32 = 5.0;
+
+/// Reverb tail set by VRAM usage: 0.3s when empty up to 5.0s when full
+fn vram_reverb_decay(params: &MusicalParams) -> f32 {
+    0.3 + params.vram_reverb_size.clamp(0.0, 1.0) * (MAX_REVERB_DECAY_SECS - 0.3)
+}
+
+/// Room size giving the VRAM reverb tail. The melody and pad keep their own
+/// room when it is larger, so machines without a GPU sound as before
+fn vram_room_size(params: &MusicalParams) -> f32 {
+    vram_reverb_decay(params) / MAX_REVERB_DECAY_SECS
+}
+
+/// Distortion amount of the bass: memory pressure, pushed further by swap
+fn bass_distortion(params: &MusicalParams) -> f32 {
+    params.bass_velocity * 0.3 + params.swap_distortion * 0.4
+}
+
+/// Distortion amount of the swap burst, heavier the faster the machine pages
+fn swap_burst_distortion(params: &MusicalParams) -> f32 {
+    0.5 + params.swap_activity * 0.5
+}
+
+/// When a voice enters during the intro, as a fraction of its length:
+/// drums first, then bass, then melody, then everything else.
+fn intro_entry(track: &str) -> f32 {
+    match track {
+        "drums" | "hihats" | "disk_reads" | "disk_writes" | "battery_warning" => 0.0,
+        name if name.starts_with("core") => 0.0,
+        "bass" | "swap_burst" | "gpu_rumble" => 0.25,
+        "melody" => 0.5,
+        name if name.starts_with("proc_") => 0.5,
+        _ => 0.75,
+    }
+}
+
+/// Drop each track's events before its intro entry point
+fn apply_intro(mixer: &mut Mixer, intro_secs: f32) {
+    for track in &mut mixer.tracks {
+        let entry = intro_entry(track.name.as_deref().unwrap_or("")) * intro_secs;
+        track.events.retain(|event| event.start_time() >= entry);
+    }
+}
+
+/// Build unique, track-safe instrument names for the process melodies.
+///
+/// Process names can contain spaces, slashes, or repeat (five `chrome`
+/// processes), which would make layers collide on one instrument key.
+fn process_instrument_names(melodies: &[(String, Vec<f32>)]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(melodies.len());
+
+    for (proc_name, _) in melodies {
+        let sanitized: String = proc_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let base = format!("proc_{}", sanitized);
+
+        // Disambiguate duplicates with an index suffix
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while names.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        names.push(name);
+    }
+
+    names
+}
+
+/// Voice names most important first; `core` and `proc` cover the numbered
+/// per-core and per-process layers
+pub const DEFAULT_VOICE_PRIORITY: [&str; 18] = [
+    "battery_warning", "melody", "bass", "drums", "pad", "gpu", "swap_burst", "gpu_rumble",
+    "hihats", "arp", "harmony", "disk_reads", "disk_writes", "connections", "proc", "core", "kernel", "fans",
+];
+
+/// Cap on simultaneous layers, so a stressed machine doesn't get a muddy mix
+/// that is itself expensive to render
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceLimit {
+    pub max_voices: usize,     // Layers kept (0 = unlimited)
+    pub priority: Vec<String>, // Voice names, most important first; unlisted voices go last
+}
+
+impl Default for VoiceLimit {
+    fn default() -> Self {
+        Self {
+            max_voices: 12,
+            priority: DEFAULT_VOICE_PRIORITY.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+}
+
+impl VoiceLimit {
+    /// Position of `track` in the priority list: an exact name, else a prefix
+    /// followed by a number or `_` (`core3`, `proc_firefox`)
+    fn rank(&self, track: &str) -> usize {
+        let exact = self.priority.iter().position(|voice| voice == track);
+        let prefix = || {
+            self.priority.iter().position(|voice| {
+                track.strip_prefix(voice.as_str())
+                    .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit() || c == '_'))
+            })
+        };
+        exact.or_else(prefix).unwrap_or(self.priority.len())
+    }
+
+    /// Remove all but the `max_voices` most important non-empty tracks
+    fn apply(&self, mixer: &mut Mixer) {
+        if self.max_voices == 0 {
+            return;
+        }
+
+        let mut ranked: Vec<(usize, usize)> = mixer.tracks.iter().enumerate()
+            .filter(|(_, track)| !track.events.is_empty())
+            .map(|(index, track)| (self.rank(track.name.as_deref().unwrap_or("")), index))
+            .collect();
+        if ranked.len() <= self.max_voices {
+            return;
+        }
+
+        ranked.sort();
+        let dropped: Vec<usize> = ranked[self.max_voices..].iter().map(|&(_, index)| index).collect();
+        let tracks = std::mem::take(&mut mixer.tracks);
+        mixer.tracks = tracks.into_iter().enumerate()
+            .filter(|(index, _)| !dropped.contains(index))
+            .map(|(_, track)| track)
+            .collect();
+    }
+}
+
+/// Layers the arrangement is built from: a persistent preference, unlike
+/// focus or the voice limit, so a disabled layer is never composed at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Layers {
+    pub melody: bool,
+    pub bass: bool,      // Also the swap burst
+    pub drums: bool,     // Kick and snare, plus the disk read/write toms and rimshots
+    pub pad: bool,
+    pub hihats: bool,
+    pub gpu: bool,       // GPU voice and power rumble
+    pub cores: bool,     // Per-core shakers
+    pub processes: bool, // Top-process melodies
+    pub fans: bool,
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Self {
+            melody: true,
+            bass: true,
+            drums: true,
+            pad: true,
+            hihats: true,
+            gpu: true,
+            cores: true,
+            processes: true,
+            fans: true,
+        }
+    }
+}
+
+/// Hidden sibling of `output_path` that exports are written to before the rename
+fn partial_path(output_path: &str) -> PathBuf {
+    let path = Path::new(output_path);
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!(".{}.partial", name))
+}
+
+/// Run `write` against a partial file next to `output_path` and rename it once
+/// complete, so an interrupted export leaves the previous file (or nothing)
+/// rather than a truncated one
+fn write_atomically(output_path: &str, write: impl FnOnce(&str) -> Result<()>) -> Result<()> {
+    let partial = partial_path(output_path);
+    if let Err(e) = write(&partial.to_string_lossy()) {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, output_path)?;
+
+    Ok(())
+}
+
+/// Gain for voices outside the focus, faint but still audible.
+/// Featured voices keep full level, so the mix gets quieter rather than clipping.
+const BACKGROUND_GAIN: f32 = 0.25;
+
+/// Subsystem featured by focus mode: a musical rebalance, not mute/solo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Focus {
+    Cpu,
+    Memory,
+    Disk,
+    Network,
+    Gpu,
+}
+
+impl Focus {
+    pub const ALL: [Focus; 5] = [Focus::Cpu, Focus::Memory, Focus::Disk, Focus::Network, Focus::Gpu];
+
+    /// Canonical name accepted by `from_name` (and the `focus` config key)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Focus::Cpu => "cpu",
+            Focus::Memory => "memory",
+            Focus::Disk => "disk",
+            Focus::Network => "network",
+            Focus::Gpu => "gpu",
+        }
+    }
+
+    /// Parse a focus target name (`cpu`, `memory`, `disk`, `network`, `gpu`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "cpu" => Some(Self::Cpu),
+            "memory" | "mem" => Some(Self::Memory),
+            "disk" => Some(Self::Disk),
+            "network" | "net" => Some(Self::Network),
+            "gpu" => Some(Self::Gpu),
+            _ => None,
+        }
+    }
+
+    /// Whether the track named `track` is one of this subsystem's voices
+    fn features(&self, track: &str) -> bool {
+        match self {
+            Focus::Cpu => {
+                matches!(track, "melody" | "arp" | "hihats" | "kernel")
+                    || track.starts_with("core")
+                    || track.starts_with("harmony")
+                    || track.starts_with("proc_")
+            }
+            Focus::Memory => matches!(track, "bass" | "swap_burst"),
+            Focus::Disk => matches!(track, "drums" | "disk_reads" | "disk_writes"),
+            Focus::Network => track == "connections",
+            Focus::Gpu => matches!(track, "gpu" | "gpu_rumble"),
+        }
+    }
+
+    /// Bring featured voices forward and to the center, push the rest back
+    fn apply(&self, mixer: &mut Mixer) {
+        for track in &mut mixer.tracks {
+            let name = track.name.as_deref().unwrap_or("");
+            if name == "battery_warning" {
+                continue; // Alerts stay audible whatever the focus
+            }
+
+            if self.features(name) {
+                track.pan = 0.0;
+            } else {
+                track.volume *= BACKGROUND_GAIN;
+            }
+        }
+    }
+}
+
+/// Effect amounts that the composer otherwise hardcodes, for taming or
+/// exaggerating the processing. Metric-driven amounts (reverb mix, GPU
+/// chorus and flanger) still modulate on top of these.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EffectSettings {
+    pub delay_feedback: f32, // Melody echo feedback (0.0-0.95)
+    pub delay_mix: f32,      // Melody echo level (0.0-1.0)
+    pub delay_subdivision: DelaySubdivision, // Melody echo time, locked to the tempo
+    pub chorus_rate: f32,    // Pad chorus speed (Hz, 0.05-10)
+    pub chorus_depth: f32,   // Pad chorus depth (0.0-10)
+    pub reverb_decay: f32,   // Scales every reverb's room size (1.0 = as composed, 0.0-2.0)
+    pub reverb_type: ReverbType, // Character of the melody and pad reverbs
+    pub distortion_type: DistortionType, // Character of the bass and swap burst distortion
+    pub wet_mix: f32,        // Scales every reverb/delay/chorus mix (0.0 = dry, 1.0 = as composed)
+    pub filter_lfo_rate: f32,  // Melody/pad cutoff sweeps per bar (0.0625-4, 0.25 = one every 4 bars)
+    pub filter_lfo_depth: f32, // Sweep around the temperature cutoff (0.0 = static, 1.0 = ±1 octave)
+    pub sidechain_amount: f32, // Melody/pad dip on each kick, scaled by network traffic (0.0 = off)
+}
+
+impl Default for EffectSettings {
+    fn default() -> Self {
+        Self {
+            delay_feedback: 0.3,
+            delay_mix: 0.4,
+            delay_subdivision: DelaySubdivision::DottedQuarter,
+            chorus_rate: 0.5,
+            chorus_depth: 2.0,
+            reverb_decay: 1.0,
+            reverb_type: ReverbType::Hall,
+            distortion_type: DistortionType::SoftClip,
+            wet_mix: 1.0,
+            filter_lfo_rate: 0.25,
+            filter_lfo_depth: 0.0,
+            sidechain_amount: 0.0,
+        }
+    }
+}
+
+impl EffectSettings {
+    /// Keep hand-edited values in a range that can't run away (e.g. feedback ≥ 1)
+    pub fn clamped(self) -> Self {
+        Self {
+            delay_feedback: self.delay_feedback.clamp(0.0, 0.95),
+            delay_mix: self.delay_mix.clamp(0.0, 1.0),
+            delay_subdivision: self.delay_subdivision,
+            chorus_rate: self.chorus_rate.clamp(0.05, 10.0),
+            chorus_depth: self.chorus_depth.clamp(0.0, 10.0),
+            reverb_decay: self.reverb_decay.clamp(0.0, 2.0),
+            reverb_type: self.reverb_type,
+            distortion_type: self.distortion_type,
+            wet_mix: self.wet_mix.clamp(0.0, 1.0),
+            filter_lfo_rate: self.filter_lfo_rate.clamp(0.0625, 4.0),
+            filter_lfo_depth: self.filter_lfo_depth.clamp(0.0, 1.0),
+            sidechain_amount: self.sidechain_amount.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Speed of the cutoff sweep at `tempo_bpm`, locked to the bar
+    fn filter_lfo_hz(&self, tempo_bpm: f32) -> f32 {
+        self.filter_lfo_rate / bars_to_seconds(tempo_bpm, BEATS_PER_BAR, 1)
+    }
+
+    /// Slow sine on the low-pass cutoff, or None when the sweep is off
+    fn filter_lfo(&self, tempo_bpm: f32) -> Option<ModRoute> {
+        if self.filter_lfo_depth <= 0.0 {
+            return None;
+        }
+        // A full-depth route spans ±2 octaves; halve it so depth 1.0 is ±1 octave
+        let lfo = LFO::new(Waveform::Sine, self.filter_lfo_hz(tempo_bpm), 1.0);
+        Some(ModRoute::new(lfo, ModTarget::FilterCutoff, self.filter_lfo_depth * 0.5))
+    }
+
+    /// An effect's mix level after the global dry/wet control
+    fn wet(&self, mix: f32) -> f32 {
+        mix * self.wet_mix
+    }
+
+    /// A voice's reverb room size after the decay override
+    fn reverb_size(&self, base: f32) -> f32 {
+        (base * self.reverb_decay).clamp(0.0, 1.0)
+    }
+
+    /// A voice's reverb chain: `mix` and `base` room size after the overrides,
+    /// shaped by the chosen reverb type
+    fn reverb(&self, mix: f32, base: f32) -> Vec<Effect> {
+        let (mix, size) = self.reverb_type.shape(self.wet(mix), self.reverb_size(base));
+        let mut chain = vec![Effect::reverb(mix, size)];
+        if self.reverb_type == ReverbType::Shimmer {
+            // Slow, deep modulation on the tail stands in for pitch-shifted feedback
+            chain.push(Effect::chorus(0.2, 4.0, mix));
+        }
+        chain
+    }
+}
+
+/// Reverb character for the melody and pad
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReverbType {
+    #[default]
+    Hall,    // Large and smooth: the composed room sizes as-is
+    Plate,   // Dense, even mid-size tail a little further forward, whatever the room size
+    Room,    // Small and close: short tail, lower level
+    Shimmer, // An even larger hall with a slowly modulated, sparkling tail
+}
+
+impl ReverbType {
+    pub const ALL: [ReverbType; 4] = [ReverbType::Hall, ReverbType::Plate, ReverbType::Room, ReverbType::Shimmer];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "hall" => Some(Self::Hall),
+            "plate" => Some(Self::Plate),
+            "room" => Some(Self::Room),
+            "shimmer" => Some(Self::Shimmer),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReverbType::Hall => "hall",
+            ReverbType::Plate => "plate",
+            ReverbType::Room => "room",
+            ReverbType::Shimmer => "shimmer",
+        }
+    }
+
+    /// Reverb (mix, room size) for this character, from the composed values.
+    /// Sizes keep their order within each type, so metrics still open the space.
+    fn shape(self, mix: f32, size: f32) -> (f32, f32) {
+        match self {
+            ReverbType::Hall => (mix, size),
+            ReverbType::Plate => ((mix * 1.2).min(1.0), 0.35 + size * 0.3),
+            ReverbType::Room => (mix * 0.7, size * 0.4),
+            ReverbType::Shimmer => (mix, (0.3 + size).min(1.0)),
+        }
+    }
+}
+
+/// Distortion character of the bass and swap burst; swap pressure sets its intensity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistortionType {
+    #[default]
+    SoftClip, // Warm saturation (tunes' own distortion)
+    HardClip, // Flat-topped and buzzy
+    Foldback, // Peaks fold back down: metallic, harmonically dense
+    Bitcrush, // Fewer bits and a lower sample rate: digital degradation
+}
+
+impl DistortionType {
+    pub const ALL: [DistortionType; 4] = [
+        DistortionType::SoftClip,
+        DistortionType::HardClip,
+        DistortionType::Foldback,
+        DistortionType::Bitcrush,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "soft_clip" | "soft" => Some(Self::SoftClip),
+            "hard_clip" | "hard" => Some(Self::HardClip),
+            "foldback" => Some(Self::Foldback),
+            "bitcrush" => Some(Self::Bitcrush),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DistortionType::SoftClip => "soft_clip",
+            DistortionType::HardClip => "hard_clip",
+            DistortionType::Foldback => "foldback",
+            DistortionType::Bitcrush => "bitcrush",
+        }
+    }
+}
+
+/// Sample-by-sample waveshaper for the distortion types tunes doesn't have.
+///
+/// Frames must be processed in order: bitcrush holds each crushed frame for
+/// several samples.
+#[derive(Debug, Clone, Copy)]
+pub struct Shaper {
+    kind: DistortionType,
+    amount: f32,      // Intensity, 0.0-1.0
+    held: (f32, f32), // Bitcrush: the frame being held
+    hold_left: u32,   // Bitcrush: samples until the next one is taken
+}
+
+impl Shaper {
+    pub fn new(kind: DistortionType, amount: f32) -> Self {
+        Self { kind, amount: amount.clamp(0.0, 1.0), held: (0.0, 0.0), hold_left: 0 }
+    }
+
+    /// Shape one stereo frame
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        if self.kind != DistortionType::Bitcrush {
+            return (self.shape(left), self.shape(right));
+        }
+
+        // Lower the sample rate by holding frames, up to 16x at full intensity
+        if self.hold_left == 0 {
+            self.held = (self.shape(left), self.shape(right));
+            self.hold_left = 1 + (self.amount * 15.0) as u32;
+        }
+        self.hold_left -= 1;
+        self.held
+    }
+
+    fn shape(&self, x: f32) -> f32 {
+        // Drive into the shaper, then back down to roughly the input level
+        let drive = 1.0 + self.amount * 9.0;
+        let makeup = drive.sqrt().recip();
+        match self.kind {
+            DistortionType::SoftClip => (x * drive).tanh() * makeup,
+            DistortionType::HardClip => (x * drive).clamp(-1.0, 1.0) * makeup,
+            DistortionType::Foldback => (1.0 - ((x * drive + 1.0).rem_euclid(4.0) - 2.0).abs()) * makeup,
+            DistortionType::Bitcrush => {
+                // 16 bits when clean, down to 4 at full intensity
+                let steps = 2f32.powf(15.0 - self.amount * 12.0);
+                (x * steps).round() / steps
+            }
+        }
+    }
+}
+
+/// Sidechain envelope that dips a bus at every kick and lets it swell back
+/// within half a beat, repeating each bar
+#[derive(Debug, Clone)]
+pub struct Ducker {
+    kicks: Vec<f32>,   // Kick offsets into the bar, seconds
+    bar_secs: f32,
+    depth: f32,        // Gain taken away right at the hit, 0.0-1.0
+    release_secs: f32, // Time back to full level
+}
+
+impl Ducker {
+    /// Duck at `kick_hits` on a grid of `steps` per bar at `tempo_bpm`
+    pub fn new(kick_hits: &[usize], steps: usize, tempo_bpm: f32, depth: f32) -> Self {
+        let bar_secs = bars_to_seconds(tempo_bpm, BEATS_PER_BAR, 1);
+        let step_secs = bar_secs / steps.max(1) as f32;
+        Self {
+            kicks: kick_hits.iter().map(|&step| step as f32 * step_secs).collect(),
+            bar_secs,
+            depth: depth.clamp(0.0, 1.0),
+            release_secs: bar_secs / (BEATS_PER_BAR * 2) as f32,
+        }
+    }
+
+    /// Bus gain at `time` seconds into the composition
+    pub fn gain(&self, time: f32) -> f32 {
+        let into_bar = time.rem_euclid(self.bar_secs);
+        let since_kick = self
+            .kicks
+            .iter()
+            .map(|&kick| (into_bar - kick).rem_euclid(self.bar_secs))
+            .fold(f32::INFINITY, f32::min);
+        if since_kick >= self.release_secs {
+            return 1.0;
+        }
+        // Fast recovery at first, easing into full level: the classic pump shape
+        let remaining = 1.0 - since_kick / self.release_secs;
+        1.0 - self.depth * remaining * remaining
+    }
+}
+
+/// A mix whose distorted voices render apart and pass through their shapers,
+/// and whose ducked voices follow the kick's envelope, before being summed
+/// back in
+#[derive(Clone)]
+pub struct ShapedMix {
+    dry: Mixer,                      // Every voice tunes renders as composed
+    shaped: Vec<(Mixer, Shaper)>,    // One voice each, with its shaper
+    ducked: Option<(Mixer, Ducker)>, // Melody and pad, sidechained to the kick
+}
+
+impl ShapedMix {
+    /// Length in seconds, as `Mixer::total_duration`
+    pub fn total_duration(&self) -> f32 {
+        self.shaped
+            .iter()
+            .map(|(layer, _)| layer)
+            .chain(self.ducked.iter().map(|(layer, _)| layer))
+            .map(Mixer::total_duration)
+            .fold(self.dry.total_duration(), f32::max)
+    }
+
+    /// One stereo frame at `time`, as `Mixer::sample_at` (call in frame order)
+    pub fn sample_at(&mut self, time: f32, sample_rate: f32, sample_clock: f32) -> (f32, f32) {
+        let (mut left, mut right) = self.dry.sample_at(time, sample_rate, sample_clock);
+        for (layer, shaper) in &mut self.shaped {
+            let (l, r) = layer.sample_at(time, sample_rate, sample_clock);
+            let (l, r) = shaper.process(l, r);
+            left += l;
+            right += r;
+        }
+        if let Some((layer, ducker)) = &mut self.ducked {
+            let (l, r) = layer.sample_at(time, sample_rate, sample_clock);
+            let gain = ducker.gain(time);
+            left += l * gain;
+            right += r * gain;
+        }
+        (left, right)
+    }
+
+    /// Render to interleaved stereo samples, as `Mixer::render_to_buffer`
+    pub fn render_to_buffer(&mut self, sample_rate: f32) -> Vec<f32> {
+        self.render_with_progress(sample_rate, f32::INFINITY, |_| {})
+    }
+
+    /// Render as `render_to_buffer`, calling `on_bar` with the fraction
+    /// rendered so far after every `bar_secs` of audio.
+    ///
+    /// Every layer is sampled frame by frame, as `Mixer::render_to_buffer`
+    /// does, and stays silent past its own length.
+    pub fn render_with_progress(&mut self, sample_rate: f32, bar_secs: f32, mut on_bar: impl FnMut(f32)) -> Vec<f32> {
+        let frames = |layer: &Mixer| (layer.total_duration() * sample_rate).ceil() as usize;
+        let dry_frames = frames(&self.dry);
+        let shaped_frames: Vec<usize> = self.shaped.iter().map(|(layer, _)| frames(layer)).collect();
+        let ducked_frames = self.ducked.as_ref().map_or(0, |(layer, _)| frames(layer));
+        let total_frames = shaped_frames.iter().copied().fold(dry_frames.max(ducked_frames), usize::max);
+        let bar_frames = ((bar_secs * sample_rate) as usize).max(1);
+
+        let mut buffer = Vec::with_capacity(total_frames * 2);
+        let mut sample_clock = 0.0;
+        for i in 0..total_frames {
+            let time = i as f32 / sample_rate;
+            let (mut left, mut right) = if i < dry_frames {
+                let (l, r) = self.dry.sample_at(time, sample_rate, sample_clock);
+                (l.clamp(-1.0, 1.0), r.clamp(-1.0, 1.0))
+            } else {
+                (0.0, 0.0)
+            };
+            for ((layer, shaper), &length) in self.shaped.iter_mut().zip(&shaped_frames) {
+                if i < length {
+                    let (l, r) = layer.sample_at(time, sample_rate, sample_clock);
+                    let (l, r) = shaper.process(l.clamp(-1.0, 1.0), r.clamp(-1.0, 1.0));
+                    left = (left + l).clamp(-1.0, 1.0);
+                    right = (right + r).clamp(-1.0, 1.0);
+                }
+            }
+            if let Some((layer, ducker)) = self.ducked.as_mut().filter(|_| i < ducked_frames) {
+                let (l, r) = layer.sample_at(time, sample_rate, sample_clock);
+                let gain = ducker.gain(time);
+                left = (left + l.clamp(-1.0, 1.0) * gain).clamp(-1.0, 1.0);
+                right = (right + r.clamp(-1.0, 1.0) * gain).clamp(-1.0, 1.0);
+            }
+            buffer.push(left);
+            buffer.push(right);
+
+            sample_clock = (sample_clock + 1.0) % sample_rate;
+            if (i + 1) % bar_frames == 0 {
+                on_bar((i + 1) as f32 / total_frames as f32);
+            }
+        }
+
+        buffer
+    }
+}
+
+/// Note value of the melody echo, so repeats land on the beat at any tempo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DelaySubdivision {
+    #[serde(rename = "1/4")]
+    Quarter,
+    #[serde(rename = "1/4 dotted")]
+    DottedQuarter,
+    #[serde(rename = "1/8")]
+    Eighth,
+    #[serde(rename = "1/8 dotted")]
+    DottedEighth,
+    #[serde(rename = "1/16")]
+    Sixteenth,
+}
+
+impl DelaySubdivision {
+    /// Length in quarter-note beats
+    pub fn beats(self) -> f32 {
+        match self {
+            Self::Quarter => 1.0,
+            Self::DottedQuarter => 1.5,
+            Self::Eighth => 0.5,
+            Self::DottedEighth => 0.75,
+            Self::Sixteenth => 0.25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Wav,
+    Flac,
+    Midi,
+    Mp3,
+    Ogg, // Vorbis
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 5] = [
+        ExportFormat::Wav,
+        ExportFormat::Flac,
+        ExportFormat::Midi,
+        ExportFormat::Mp3,
+        ExportFormat::Ogg,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "wav" => Some(Self::Wav),
+            "flac" => Some(Self::Flac),
+            "midi" | "mid" => Some(Self::Midi),
+            "mp3" => Some(Self::Mp3),
+            "ogg" | "vorbis" => Some(Self::Ogg),
+            _ => None,
+        }
+    }
+
+    /// Canonical name accepted by `from_name`, also the file extension
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Wav => "wav",
+            ExportFormat::Flac => "flac",
+            ExportFormat::Midi => "midi",
+            ExportFormat::Mp3 => "mp3",
+            ExportFormat::Ogg => "ogg",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_format_validation() {
+        assert!(validate_output_format(48000, Some(24)).is_ok());
+        assert!(validate_output_format(96000, None).is_ok());
+        assert!(validate_output_format(22050, None).is_err());
+        assert!(validate_output_format(44100, Some(32)).is_err());
+    }
+
+    #[test]
+    fn test_process_names_are_sanitized_and_unique() {
+        let melodies = vec![
+            ("a/b".to_string(), vec![E5]),
+            ("a b".to_string(), vec![G5]),
+            ("chrome".to_string(), vec![A5]),
+            ("chrome".to_string(), vec![B5]),
+        ];
+
+        let names = process_instrument_names(&melodies);
+        assert_eq!(names, vec!["proc_a_b", "proc_a_b_2", "proc_chrome", "proc_chrome_2"]);
+    }
+
+    #[test]
+    fn test_midi_export_keeps_colliding_processes_separate() {
+        let composer = SystemComposer::new_silent();
+
+        let params = MusicalParams {
+            tempo: 100.0,
+            melody_notes: vec![A4],
+            bass_note: A2,
+            bass_velocity: 0.5,
+            process_melodies: vec![
+                ("a/b".to_string(), vec![E5, A5]),
+                ("a b".to_string(), vec![G5, B5]),
+            ],
+            ..Default::default()
+        };
+
+        let mixer = composer.compose(&params, 1).into_mixer();
+        let proc_tracks: Vec<_> = mixer.tracks.iter()
+            .filter_map(|t| t.name.as_deref())
+            .filter(|name| name.starts_with("proc_"))
+            .collect();
+        assert_eq!(proc_tracks.len(), 2);
+
+        let path = std::env::temp_dir().join("syssonic_process_names.mid");
+        composer
+            .compose_and_export(&params, 1, path.to_str().unwrap(), ExportFormat::Midi, None)
+            .unwrap();
+        assert!(path.exists());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_export_is_written_atomically() {
+        let composer = SystemComposer::new_silent();
+        let params = MusicalParams { tempo: 100.0, melody_notes: vec![A4], ..Default::default() };
+
+        let path = std::env::temp_dir().join("syssonic_atomic_export.mid");
+        let output = path.to_str().unwrap();
+        composer.compose_and_export(&params, 1, output, ExportFormat::Midi, None).unwrap();
+        assert!(path.exists());
+        assert!(!partial_path(output).exists());
+        let _ = std::fs::remove_file(&path);
+
+        // A failed export leaves neither the target nor the partial file
+        let missing = std::env::temp_dir().join("syssonic_no_such_dir").join("out.wav");
+        let output = missing.to_str().unwrap();
+        assert!(composer.compose_and_export(&params, 1, output, ExportFormat::Wav, None).is_err());
+        assert!(!missing.exists());
+        assert!(!partial_path(output).exists());
+    }
+
+    #[test]
+    fn test_export_reports_progress_per_bar() {
+        let composer = SystemComposer::new_silent();
+        let params = MusicalParams { tempo: 120.0, melody_notes: vec![A4, C5], kick_hits: vec![0, 8], ..Default::default() };
+        let path = std::env::temp_dir().join("syssonic_progress_export.wav");
+
+        let mut reported = Vec::new();
+        let mut record = |fraction: f32| reported.push(fraction);
+        composer
+            .compose_and_export(&params, 2, path.to_str().unwrap(), ExportFormat::Wav, Some(&mut record))
+            .unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        // One report per rendered bar, then exactly 1.0 once the file is written
+        assert!(reported.len() >= 3, "{:?}", reported);
+        assert!(reported.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(reported[..reported.len() - 1].iter().all(|&f| f <= RENDER_PROGRESS));
+        assert_eq!(reported.last(), Some(&1.0));
+    }
+
+    #[test]
+    fn test_snapshot_sequence_joins_segments_with_crossfades() {
+        let composer = SystemComposer::new_silent().with_sample_rate(8_000);
+        let calm = MusicalParams { tempo: 120.0, melody_notes: vec![A4], ..Default::default() };
+        let busy = MusicalParams { tempo: 120.0, melody_notes: vec![E5], kick_hits: vec![0, 8], ..Default::default() };
+
+        let path = std::env::temp_dir().join("syssonic_sequence_export.wav");
+        let output = path.to_str().unwrap();
+        composer.compose_and_export_sequence(&[calm.clone(), busy.clone()], 1, 0.5, output, ExportFormat::Wav).unwrap();
+
+        // Both segments back to back, less the half-second overlap
+        let frames = |params: &MusicalParams| composer.render_to_buffer(params, 1, 8_000).len() as u32 / 2;
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.duration(), frames(&calm) + frames(&busy) - 4_000);
+        drop(reader);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(composer.compose_and_export_sequence(&[calm], 1, 0.5, output, ExportFormat::Midi).is_err());
+        assert!(composer.compose_and_export_sequence(&[], 1, 0.5, output, ExportFormat::Wav).is_err());
+    }
+
+    #[test]
+    fn test_focus_rebalances_voices() {
+        let params = MusicalParams {
+            tempo: 100.0,
+            melody_notes: vec![A4],
+            bass_note: A2,
+            bass_velocity: 0.5,
+            ..Default::default()
+        };
+        let volume = |mixer: &Mixer, name: &str| {
+            mixer.tracks.iter().find(|t| t.name.as_deref() == Some(name)).unwrap().volume
+        };
+
+        let balanced = SystemComposer::new_silent().mix(&params, 1);
+        let focused = SystemComposer::new_silent().with_focus(Some(Focus::Memory)).mix(&params, 1);
+
+        assert_eq!(volume(&focused, "bass"), volume(&balanced, "bass"));
+        assert_eq!(volume(&focused, "melody"), volume(&balanced, "melody") * BACKGROUND_GAIN);
+        assert_eq!(Focus::from_name("GPU"), Some(Focus::Gpu));
+        assert_eq!(Focus::from_name("everything"), None);
+    }
+
+    #[test]
+    fn test_listed_names_parse_back() {
+        for format in ExportFormat::ALL {
+            assert_eq!(ExportFormat::from_name(format.name()), Some(format));
+        }
+        for focus in Focus::ALL {
+            assert_eq!(Focus::from_name(focus.name()), Some(focus));
+        }
+        assert_eq!(ExportFormat::from_name("aiff"), None);
+    }
+
+    #[test]
+    fn test_buffer_size_validation() {
+        assert!(validate_buffer_size(4096).is_ok());
+        assert!(validate_buffer_size(64).is_ok());
+        assert!(validate_buffer_size(1000).is_err());
+        assert!(validate_buffer_size(32).is_err());
+        assert!(validate_buffer_size(16384).is_err());
+    }
+
+    #[test]
+    fn test_voice_limit_drops_least_important_layers() {
+        let params = MusicalParams {
+            tempo: 100.0,
+            melody_notes: vec![A4],
+            bass_note: A2,
+            bass_velocity: 0.5,
+            kick_hits: vec![0, 8],
+            rhythm_polyrhythm_factor: 1.0,
+            core_patterns: vec![vec![0, 4]],
+            process_melodies: vec![("make".to_string(), vec![E5])],
+            ..Default::default()
+        };
+        let names = |composer: SystemComposer| -> Vec<String> {
+            let mut names: Vec<String> = composer.mix(&params, 1).tracks.iter()
+                .filter(|t| !t.events.is_empty())
+                .filter_map(|t| t.name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let all = names(SystemComposer::new_silent());
+        assert!(all.iter().any(|n| n.starts_with("core")));
+
+        let limit = VoiceLimit { max_voices: 2, ..Default::default() };
+        let capped = names(SystemComposer::new_silent().with_voice_limit(Some(limit)));
+        assert_eq!(capped, vec!["bass".to_string(), "melody".to_string()]);
+
+        // A custom order keeps the per-core shakers first
+        let limit = VoiceLimit { max_voices: 1, priority: vec!["core".to_string()] };
+        let capped = names(SystemComposer::new_silent().with_voice_limit(Some(limit)));
+        assert_eq!(capped, vec!["core0".to_string()]);
+        assert_eq!(VoiceLimit::default().rank("proc_make"), 14);
+    }
+
+    #[test]
+    fn test_bars_to_seconds() {
+        assert_eq!(bars_to_seconds(120.0, 4, 4), 8.0);
+        assert_eq!(bars_to_seconds(90.0, 4, 1), 4.0 * 60.0 / 90.0);
+        assert_eq!(bars_to_seconds(100.0, 3, 0), 0.0);
+    }
+
+    #[test]
+    fn test_effect_overrides_are_clamped() {
+        let wild = EffectSettings { delay_feedback: 1.5, reverb_decay: -1.0, ..Default::default() }.clamped();
+        assert_eq!(wild.delay_feedback, 0.95);
+        assert_eq!(wild.reverb_decay, 0.0);
+        assert_eq!(wild.delay_mix, EffectSettings::default().delay_mix);
+
+        let doubled = EffectSettings { reverb_decay: 2.0, ..Default::default() };
+        assert_eq!(doubled.reverb_size(0.4), 0.8);
+        assert_eq!(doubled.reverb_size(0.8), 1.0);
+
+        let room = EffectSettings { reverb_type: ReverbType::Room, ..Default::default() };
+        assert_eq!(ReverbType::Hall.shape(0.5, 0.8), (0.5, 0.8));
+        assert!(ReverbType::Room.shape(0.5, 0.8).1 < ReverbType::Hall.shape(0.5, 0.8).1);
+        assert!(ReverbType::Shimmer.shape(0.5, 0.8).1 <= 1.0);
+        assert_eq!(room.reverb(0.5, 0.8).len(), 1);
+        for reverb_type in ReverbType::ALL {
+            assert_eq!(ReverbType::from_name(reverb_type.name()), Some(reverb_type));
+        }
+
+        let dry = EffectSettings { wet_mix: -0.5, ..Default::default() }.clamped();
+        assert_eq!(dry.wet(0.4), 0.0);
+        let half = EffectSettings { wet_mix: 0.5, ..Default::default() };
+        assert_eq!(half.wet(0.4), 0.2);
+    }
+
+    #[test]
+    fn test_filter_lfo_locks_to_the_bar() {
+        // 120 BPM: a bar is 2 seconds, so one sweep per bar is 0.5 Hz
+        let fx = EffectSettings { filter_lfo_rate: 1.0, filter_lfo_depth: 0.5, ..Default::default() };
+        assert!((fx.filter_lfo_hz(120.0) - 0.5).abs() < 1e-6);
+        assert!(fx.filter_lfo(120.0).is_some());
+        assert!(EffectSettings::default().filter_lfo(120.0).is_none(), "depth 0 keeps the cutoff static");
+
+        let wild = EffectSettings { filter_lfo_rate: 100.0, filter_lfo_depth: 3.0, ..Default::default() }.clamped();
+        assert_eq!((wild.filter_lfo_rate, wild.filter_lfo_depth), (4.0, 1.0));
+    }
+
+    #[test]
+    fn test_delay_subdivision_from_config() {
+        let fx: EffectSettings = toml::from_str("delay_subdivision = \"1/8 dotted\"").unwrap();
+        assert_eq!(fx.delay_subdivision, DelaySubdivision::DottedEighth);
+        assert_eq!(fx.delay_mix, EffectSettings::default().delay_mix);
+
+        // The default keeps the original dotted-quarter echo (three eighths)
+        assert_eq!(EffectSettings::default().delay_subdivision.beats(), 1.5);
+    }
+
+    #[test]
+    fn test_intro_staggers_voice_entries() {
+        let params = MusicalParams {
+            tempo: 120.0, // One bar = 2 seconds
+            melody_notes: vec![A4],
+            bass_note: A2,
+            bass_velocity: 0.5,
+            kick_hits: vec![0, 8],
+            ..Default::default()
+        };
+        let mut mixer = SystemComposer::new_silent().mix(&params, 2);
+        apply_intro(&mut mixer, 2.0);
+
+        let first_event = |name: &str| {
+            let track = mixer.tracks.iter().find(|t| t.name.as_deref() == Some(name)).unwrap();
+            track.events.iter().map(|e| e.start_time()).fold(f32::MAX, f32::min)
+        };
+        assert!(first_event("drums") < 0.5);
+        assert!(first_event("bass") >= 0.5);
+        assert!(first_event("melody") >= 1.0);
+    }
+
+    #[test]
+    fn test_silent_composer_plays_without_a_device() {
+        let composer = SystemComposer::new_silent();
+        let params = MusicalParams {
+            tempo: 120.0,
+            melody_notes: vec![A4, C5],
+            bass_note: A2,
+            bass_velocity: 0.5,
+            kick_hits: vec![0, 8],
+            snare_hits: vec![4, 12],
+            ..Default::default()
+        };
+
+        composer.compose_and_play(&params, 1).unwrap();
+
+        // One bar at 120 BPM is two seconds of stereo audio
+        let buffer = composer.render_to_buffer(&params, 1, 44100);
+        assert!(buffer.len() >= 2 * 44100 * 2);
+        assert!(buffer.iter().any(|s| s.abs() > 0.0));
+    }
+
+    #[test]
+    fn test_volume_scales_live_playback() {
+        let params = MusicalParams {
+            tempo: 120.0,
+            melody_notes: vec![A4, C5],
+            bass_note: A2,
+            bass_velocity: 0.5,
+            kick_hits: vec![0, 8],
+            ..Default::default()
+        };
+        let peak = |volume: f32| {
+            let composer = SystemComposer::new_silent().with_volume(volume);
+            let buffer = composer.live_mix(&params, 1).render_to_buffer(44100.0);
+            buffer.iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+        };
+
+        let (half, full) = (peak(0.5), peak(1.0));
+        assert!(half > 0.0 && half < full, "half {} vs full {}", half, full);
+        assert_eq!(peak(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_battery_scales_every_output() {
+        let composer = SystemComposer::new_silent();
+        let charged = MusicalParams {
+            tempo: 120.0,
+            melody_notes: vec![A4, C5],
+            bass_note: A2,
+            bass_velocity: 0.3,
+            battery_volume_mult: 1.0,
+            ..Default::default()
+        };
+        let low = MusicalParams { battery_volume_mult: 0.5, ..charged.clone() };
+
+        let level = |params: &MusicalParams| audio_export::rms(&composer.render_to_buffer(params, 1, 44100));
+        let ratio = level(&low) / level(&charged);
+        assert!((ratio - 0.5).abs() < 0.05, "ratio {}", ratio);
+
+        // Exports go through the same mix
+        let volume = |params: &MusicalParams| composer.mix(params, 1).tracks.iter().map(|t| t.volume).sum::<f32>();
+        assert!((volume(&low) / volume(&charged) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_vram_lengthens_the_reverb_tail() {
+        let empty = MusicalParams { vram_reverb_size: 0.0, ..Default::default() };
+        let full = MusicalParams { vram_reverb_size: 1.0, ..Default::default() };
+        assert!((vram_reverb_decay(&empty) - 0.3).abs() < 1e-6);
+        assert!((vram_reverb_decay(&full) - MAX_REVERB_DECAY_SECS).abs() < 1e-6);
+
+        // Low VRAM leaves the composed rooms alone; full VRAM opens them all the way
+        assert!(vram_room_size(&empty) < 0.5);
+        assert_eq!(vram_room_size(&full), 1.0);
+    }
+
+    #[test]
+    fn test_distortion_shapers() {
+        for kind in DistortionType::ALL {
+            assert_eq!(DistortionType::from_name(kind.name()), Some(kind));
+        }
+        let fx: EffectSettings = toml::from_str("distortion_type = \"bitcrush\"").unwrap();
+        assert_eq!(fx.distortion_type, DistortionType::Bitcrush);
+
+        let mut hard = Shaper::new(DistortionType::HardClip, 1.0);
+        assert_eq!(hard.process(0.9, -0.9), hard.process(0.5, -0.5), "both past the clip point");
+
+        // Foldback turns a hotter input back down instead of clipping it
+        let mut fold = Shaper::new(DistortionType::Foldback, 1.0);
+        let (gentle, _) = fold.process(0.1, 0.0);
+        let (folded, _) = fold.process(0.3, 0.0);
+        assert!(folded < gentle);
+
+        // Bitcrush holds each crushed frame for 16 samples at full intensity
+        let mut crush = Shaper::new(DistortionType::Bitcrush, 1.0);
+        let held = crush.process(0.3, 0.3);
+        for _ in 1..16 {
+            assert_eq!(crush.process(-0.8, 0.1), held);
+        }
+        assert_ne!(crush.process(-0.8, 0.1), held);
+    }
+
+    #[test]
+    fn test_distortion_type_keeps_the_arrangement() {
+        let params = MusicalParams {
+            tempo: 120.0,
+            melody_notes: vec![A4, C5],
+            bass_note: A2,
+            bass_velocity: 0.8,
+            swap_activity: 0.6,
+            ..Default::default()
+        };
+        let soft = SystemComposer::new_silent();
+        let crushed = SystemComposer::new_silent()
+            .with_effects(EffectSettings { distortion_type: DistortionType::Bitcrush, ..Default::default() });
+
+        assert_eq!(soft.arrange(&params, 1).len(), crushed.arrange(&params, 1).len());
+        let buffer = crushed.render_to_buffer(&params, 1, 8_000);
+        assert_eq!(buffer.len(), soft.render_to_buffer(&params, 1, 8_000).len());
+        assert!(buffer.iter().any(|s| s.abs() > 0.0));
+    }
+
+    #[test]
+    fn test_sidechain_ducks_on_each_kick() {
+        // 120 BPM, 16 steps: kicks at 0s and 1s, recovering over 0.25s
+        let ducker = Ducker::new(&[0, 8], 16, 120.0, 0.6);
+        assert!((ducker.gain(0.0) - 0.4).abs() < 1e-6);
+        assert!((ducker.gain(1.0) - 0.4).abs() < 1e-4);
+        assert!(ducker.gain(0.1) > ducker.gain(0.0) && ducker.gain(0.1) < 1.0);
+        assert_eq!(ducker.gain(0.5), 1.0);
+        assert!((ducker.gain(2.0) - 0.4).abs() < 1e-4, "repeats every bar");
+
+        let params = MusicalParams {
+            tempo: 120.0,
+            melody_notes: vec![A4, C5],
+            kick_hits: vec![0, 8],
+            sidechain_pump: 1.0,
+            ..Default::default()
+        };
+        let plain = SystemComposer::new_silent();
+        assert!(plain.shape(plain.mix(&params, 1), &params).ducked.is_none(), "amount 0 leaves the mix alone");
+
+        let pumped = SystemComposer::new_silent()
+            .with_effects(EffectSettings { sidechain_amount: 0.8, ..Default::default() });
+        let mix = pumped.shape(pumped.mix(&params, 1), &params);
+        assert!(mix.ducked.is_some());
+        assert!(!mix.dry.tracks.iter().any(|t| t.name.as_deref() == Some("melody")));
+        assert_eq!(
+            pumped.render_to_buffer(&params, 1, 8_000).len(),
+            plain.render_to_buffer(&params, 1, 8_000).len()
+        );
+    }
+
+    #[test]
+    fn test_harmonic_voices_add_harmony_tracks() {
+        let composer = SystemComposer::new_silent();
+        let params = MusicalParams {
+            tempo: 120.0,
+            melody_notes: vec![A4, C5],
+            harmonic_voices: 3,
+            harmony_notes: vec![vec![C5, E5], vec![E5, G5]],
+            ..Default::default()
+        };
+
+        let mixer = composer.mix(&params, 1);
+        let names: Vec<&str> = mixer.tracks.iter().filter_map(|t| t.name.as_deref()).collect();
+        assert!(names.contains(&"harmony1") && names.contains(&"harmony2"), "{:?}", names);
+        assert!(!names.contains(&"harmony3"));
+
+        let solo = composer.mix(&MusicalParams { harmony_notes: Vec::new(), ..params }, 1);
+        assert!(solo.tracks.iter().all(|t| !t.name.as_deref().unwrap_or("").starts_with("harmony")));
+    }
+
+    #[test]
+    fn test_disabled_layers_are_left_out() {
+        let params = MusicalParams {
+            tempo: 120.0,
+            melody_notes: vec![A4, C5],
+            bass_note: A2,
+            bass_velocity: 0.5,
+            kick_hits: vec![0, 8],
+            process_melodies: vec![("firefox".to_string(), vec![A4, C5])],
+            ..Default::default()
+        };
+        let voices = |composer: &SystemComposer| -> Vec<String> {
+            composer.mix(&params, 1).tracks.iter().filter_map(|t| t.name.clone()).collect()
+        };
+
+        let full = voices(&SystemComposer::new_silent());
+        assert!(full.iter().any(|v| v == "melody") && full.iter().any(|v| v.starts_with("proc_")));
+
+        let layers = Layers { melody: false, processes: false, ..Layers::default() };
+        let trimmed = voices(&SystemComposer::new_silent().with_layers(layers));
+        assert!(!trimmed.iter().any(|v| v == "melody" || v.starts_with("proc_")));
+        assert!(trimmed.iter().any(|v| v == "bass"));
+    }
+
+    #[test]
+    fn test_bar_clock_waits_for_the_next_downbeat() {
+        let mut clock = BarClock::default();
+        let start = Instant::now();
+        assert_eq!(clock.until_next_bar(start), Duration::ZERO, "nothing playing yet");
+
+        // 120 BPM: a bar every two seconds
+        clock.start(start, 120.0);
+        let wait = clock.until_next_bar(start + Duration::from_millis(4500));
+        assert!((wait.as_secs_f32() - 1.5).abs() < 0.01);
+        assert_eq!(clock.until_next_bar(start + Duration::from_secs(4)), Duration::ZERO);
+    }
+}
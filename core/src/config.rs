@@ -0,0 +1,447 @@
+use crate::auto_gain::AutoGainSettings;
+use crate::composer::{self, DrumMap, EffectSettings, Focus, Layers, VoiceLimit, DEFAULT_SAMPLE_RATE};
+use crate::idle::IdleThreshold;
+use crate::mapper::{
+    CoreGrouping, GridResolution, MappingInversions, MelodyContour, MetricsMapper, ResponseCurve, TemperatureFallback,
+};
+use crate::metrics::{Aggregation, EnvelopeSettings, GpuVendor, InterfaceFilter, MetricsCollector, ProcessSort, Smoothing};
+use anyhow::Result;
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Shortest and longest composition the tray and daemon will play
+pub const MIN_BARS: usize = 1;
+pub const MAX_BARS: usize = 64;
+
+// Missing fields fall back to `AppConfig::default()` so configs written by
+// older (or newer) versions still load instead of being discarded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    // Audio settings
+    pub audio_device: String,
+    pub volume: f32,
+    pub auto_play_on_start: bool,
+    pub sample_rate: u32,        // 44100, 48000 or 96000
+    pub bit_depth: Option<u16>,  // 16 or 24; unset = 16-bit WAV, 24-bit FLAC
+    pub audio_buffer_size: u32,  // Playback frames, power of two 64-8192 (0 = device default)
+
+    // Update settings
+    pub update_interval_ms: u64,
+    pub sample_count: usize,      // Readings combined per composition
+    pub sample_interval_ms: u64,  // Gap between those readings
+    pub ema_alpha: f32,           // Weight of each new reading in a running average across compositions (0 = off, use sample_count)
+    pub bars: usize,          // Bars per composition (1-64)
+    pub process_voices: usize,
+    pub core_voices: usize,    // Per-core shaker voices on many-core machines
+    pub core_grouping: String, // "average" or "busiest"
+    pub process_sort: String, // "cpu", "memory", "combined", or "io"
+    pub cpu_smoothing: String, // "mean", "max", "min", or "last"
+    pub io_smoothing: String,  // "mean", "max", "min", or "last"
+    pub temperature_smoothing_secs: f32, // Temperature moving-average time constant (0 = off)
+    pub fallback_temperature: f32,       // °C reported when no sensor is found
+    pub temperature_fallback: String,    // Filter source without a sensor: "fixed", "cpu", "memory", or "load"
+    pub temperature_sensor: String,      // Label fragment of the sensor to follow ("" = CPU package if found)
+    pub network_interfaces_allow: Vec<String>, // Interface prefixes to count (empty = all)
+    pub network_interfaces_deny: Vec<String>,  // Interface prefixes to skip (loopback, containers)
+
+    // Musical settings
+    pub base_tempo: f32,
+    pub scale_type: String, // One of mapper::SCALE_NAMES
+    pub melody_contour: String, // "classic", "ascending", "descending", "zigzag", or "random_walk"
+    pub melody_seed: u64,       // Random-walk seed, for repeatable sessions (0 = new every run)
+    pub grid_resolution: String, // "sixteenth", "thirty_second", "eighth_triplet", or "sixteenth_triplet"
+    pub quantize_to_bar: bool, // Hold new compositions until the next downbeat
+    pub gpu_tdp_watts: f32, // GPU power draw treated as full sub-bass rumble
+    pub battery_warning_minutes: f32, // Time-to-empty that triggers the warning cue
+    pub disk_curve: String,    // "linear", "log", or "exp"
+    pub network_curve: String, // "linear", "log", or "exp"
+    pub vram_curve: String,    // "linear", "log", or "exp"
+    pub focus: String,         // "none", "cpu", "memory", "disk", "network", or "gpu"
+    pub invert_cpu_pitch: bool,     // Busy CPU plays a lower melody
+    pub invert_temp_filter: bool,   // Hot machine sounds darker and drier
+    pub invert_network_tempo: bool, // Heavy traffic slows the tempo
+
+    // Arrangement: layers to compose at all (all on by default)
+    pub layer_melody: bool,
+    pub layer_bass: bool,      // Also the swap burst
+    pub layer_drums: bool,     // Kick/snare plus disk toms and rimshots
+    pub layer_pad: bool,
+    pub layer_hihats: bool,
+    pub layer_gpu: bool,       // GPU voice and power rumble
+    pub layer_cores: bool,     // Per-core shakers
+    pub layer_processes: bool, // Top-process melodies
+    pub layer_fans: bool,
+
+    // UI settings
+    pub theme: String,
+    pub start_minimized: bool,
+    pub show_3d_viz: bool,
+
+    // System settings
+    pub auto_start: bool,
+    pub enable_gpu_monitoring: bool,
+    pub gpu_vendor: String, // "auto", "nvidia", or "amd" (hybrid graphics)
+    pub enable_battery_monitoring: bool,
+    pub enable_fan_monitoring: bool,
+
+    // Tables last, so TOML tables follow plain keys
+    pub effects: EffectSettings,        // [effects] overrides
+    pub idle_threshold: IdleThreshold,  // [idle_threshold] silence-when-idle levels
+    pub voice_limit: VoiceLimit,        // [voice_limit] layer cap and priority
+    pub drum_map: DrumMap,              // [drum_map] MIDI export percussion notes
+    pub auto_gain: AutoGainSettings,    // [auto_gain] loudness leveling across compositions
+    pub envelope: EnvelopeSettings,     // [envelope.<metric>] attack/release per metric
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            audio_device: "default".to_string(),
+            volume: 0.8,
+            auto_play_on_start: false,
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            bit_depth: None,
+            audio_buffer_size: 0,
+            update_interval_ms: 16000,
+            sample_count: 3,
+            sample_interval_ms: 200,
+            ema_alpha: 0.0,
+            bars: 4,
+            process_voices: 5,
+            core_voices: 4,
+            core_grouping: "average".to_string(),
+            process_sort: "cpu".to_string(),
+            cpu_smoothing: "mean".to_string(),
+            io_smoothing: "max".to_string(),
+            temperature_smoothing_secs: 10.0,
+            fallback_temperature: 45.0,
+            temperature_fallback: "fixed".to_string(),
+            temperature_sensor: String::new(),
+            network_interfaces_allow: Vec::new(),
+            network_interfaces_deny: InterfaceFilter::default().deny,
+            base_tempo: 90.0,
+            scale_type: "minor_pentatonic".to_string(),
+            melody_contour: "classic".to_string(),
+            melody_seed: 0,
+            grid_resolution: "sixteenth".to_string(),
+            quantize_to_bar: false,
+            gpu_tdp_watts: 250.0,
+            battery_warning_minutes: 15.0,
+            disk_curve: "log".to_string(),
+            network_curve: "log".to_string(),
+            vram_curve: "linear".to_string(),
+            focus: "none".to_string(),
+            invert_cpu_pitch: false,
+            invert_temp_filter: false,
+            invert_network_tempo: false,
+            layer_melody: true,
+            layer_bass: true,
+            layer_drums: true,
+            layer_pad: true,
+            layer_hihats: true,
+            layer_gpu: true,
+            layer_cores: true,
+            layer_processes: true,
+            layer_fans: true,
+            theme: "dark".to_string(),
+            start_minimized: false,
+            show_3d_viz: true,
+            auto_start: false,
+            enable_gpu_monitoring: true,
+            gpu_vendor: "auto".to_string(),
+            enable_battery_monitoring: true,
+            enable_fan_monitoring: true,
+            effects: EffectSettings::default(),
+            idle_threshold: IdleThreshold::default(),
+            voice_limit: VoiceLimit::default(),
+            drum_map: DrumMap::default(),
+            auto_gain: AutoGainSettings::default(),
+            envelope: EnvelopeSettings::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// The SysSonic config directory, created if missing
+    pub fn config_dir() -> Result<PathBuf> {
+        let proj_dirs = ProjectDirs::from("com", "syssonic", "SysSonic")
+            .ok_or_else(|| anyhow::anyhow!("Failed to get project directories"))?;
+
+        let config_dir = proj_dirs.config_dir();
+        fs::create_dir_all(config_dir)?;
+
+        Ok(config_dir.to_path_buf())
+    }
+
+    /// Get the config file path
+    fn get_config_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("config.toml"))
+    }
+
+    /// Load configuration from disk
+    pub fn load() -> Result<Self> {
+        let config_path = Self::get_config_path()?;
+
+        if !config_path.exists() {
+            let default = Self::default();
+            default.save()?;
+            return Ok(default);
+        }
+
+        let contents = fs::read_to_string(&config_path)?;
+        let config: AppConfig = toml::from_str(&contents)?;
+
+        Ok(config)
+    }
+
+    /// Save configuration to disk
+    pub fn save(&self) -> Result<()> {
+        let config_path = Self::get_config_path()?;
+        let contents = toml::to_string_pretty(self)?;
+        fs::write(&config_path, contents)?;
+
+        Ok(())
+    }
+
+    /// Sample rate and bit depth, falling back to the defaults if either is unsupported
+    pub fn output_format(&self) -> (u32, Option<u16>) {
+        match composer::validate_output_format(self.sample_rate, self.bit_depth) {
+            Ok(()) => (self.sample_rate, self.bit_depth),
+            Err(e) => {
+                log::warn!("{}; using {} Hz with default bit depth", e, DEFAULT_SAMPLE_RATE);
+                (DEFAULT_SAMPLE_RATE, None)
+            }
+        }
+    }
+
+    /// Playback buffer in frames, or the device default if unset (0) or invalid
+    pub fn buffer_size(&self) -> Option<u32> {
+        if self.audio_buffer_size == 0 {
+            return None;
+        }
+        match composer::validate_buffer_size(self.audio_buffer_size) {
+            Ok(()) => Some(self.audio_buffer_size),
+            Err(e) => {
+                log::warn!("{}; using the device default", e);
+                None
+            }
+        }
+    }
+
+    /// Bars per composition, clamped to 1-64 in case the file was hand-edited
+    pub fn bars(&self) -> usize {
+        self.bars.clamp(MIN_BARS, MAX_BARS)
+    }
+
+    /// Readings to combine per composition (at least one) and the gap
+    /// between them, for `MetricsCollector::collect_averaged`
+    pub fn sampling(&self) -> (usize, u64) {
+        (self.sample_count.max(1), self.sample_interval_ms)
+    }
+
+    /// Featured subsystem, or None for a balanced mix
+    pub fn focus(&self) -> Option<Focus> {
+        Focus::from_name(&self.focus)
+    }
+
+    /// The composer layers switched on by the `layer_*` settings
+    pub fn layers(&self) -> Layers {
+        Layers {
+            melody: self.layer_melody,
+            bass: self.layer_bass,
+            drums: self.layer_drums,
+            pad: self.layer_pad,
+            hihats: self.layer_hihats,
+            gpu: self.layer_gpu,
+            cores: self.layer_cores,
+            processes: self.layer_processes,
+            fans: self.layer_fans,
+        }
+    }
+
+    /// Build a mapper from the musical settings
+    pub fn build_mapper(&self) -> MetricsMapper {
+        let curve = |name: &str, fallback| ResponseCurve::from_name(name).unwrap_or(fallback);
+
+        MetricsMapper::new()
+            .with_scale(&self.scale_type)
+            .with_gpu_tdp(self.gpu_tdp_watts)
+            .with_battery_warning_threshold(self.battery_warning_minutes)
+            .with_response_curves(
+                curve(&self.disk_curve, ResponseCurve::Logarithmic),
+                curve(&self.network_curve, ResponseCurve::Logarithmic),
+                curve(&self.vram_curve, ResponseCurve::Linear),
+            )
+            .with_inversions(MappingInversions {
+                cpu_pitch: self.invert_cpu_pitch,
+                temp_filter: self.invert_temp_filter,
+                network_tempo: self.invert_network_tempo,
+            })
+            .with_core_voices(
+                self.core_voices,
+                CoreGrouping::from_name(&self.core_grouping).unwrap_or(CoreGrouping::Average),
+            )
+            .with_melody_contour(
+                MelodyContour::from_name(&self.melody_contour).unwrap_or(MelodyContour::Classic),
+                self.melody_seed,
+            )
+            .with_grid_resolution(GridResolution::from_name(&self.grid_resolution).unwrap_or_default())
+            .with_temperature_fallback(
+                TemperatureFallback::from_name(&self.temperature_fallback).unwrap_or(TemperatureFallback::Fixed),
+            )
+    }
+
+    /// Build a collector from the update settings
+    pub fn build_collector(&self) -> MetricsCollector {
+        let process_sort = ProcessSort::from_name(&self.process_sort).unwrap_or(ProcessSort::Cpu);
+        let defaults = Smoothing::default();
+        let io = Aggregation::from_name(&self.io_smoothing).unwrap_or(defaults.disk);
+        let smoothing = Smoothing {
+            cpu: Aggregation::from_name(&self.cpu_smoothing).unwrap_or(defaults.cpu),
+            disk: io,
+            network: io,
+            ..defaults
+        };
+
+        MetricsCollector::new()
+            .with_process_selection(self.process_voices, process_sort)
+            .with_smoothing(smoothing)
+            .with_gpu_vendor(GpuVendor::from_name(&self.gpu_vendor))
+            .with_temperature_time_constant(self.temperature_smoothing_secs)
+            .with_fallback_temperature(self.fallback_temperature)
+            .with_temperature_sensor(Some(self.temperature_sensor.trim().to_string()).filter(|s| !s.is_empty()))
+            .with_interface_filter(InterfaceFilter {
+                allow: self.network_interfaces_allow.clone(),
+                deny: self.network_interfaces_deny.clone(),
+            })
+            .with_ema(Some(self.ema_alpha))
+    }
+
+    /// Update a specific setting and save
+    pub fn update<F>(&mut self, updater: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self),
+    {
+        updater(self);
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = AppConfig::default();
+        assert_eq!(config.volume, 0.8);
+        assert_eq!(config.theme, "dark");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut config = AppConfig::default();
+        config.volume = 0.5;
+
+        config.save().unwrap();
+
+        let loaded = AppConfig::load().unwrap();
+        assert_eq!(loaded.volume, 0.5);
+    }
+
+    #[test]
+    fn test_bars_are_clamped() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.bars(), 4);
+
+        config.bars = 0;
+        assert_eq!(config.bars(), MIN_BARS);
+        config.bars = 500;
+        assert_eq!(config.bars(), MAX_BARS);
+    }
+
+    #[test]
+    fn test_sampling_takes_at_least_one_reading() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.sampling(), (3, 200));
+
+        config.sample_count = 0;
+        config.sample_interval_ms = 50;
+        assert_eq!(config.sampling(), (1, 50));
+    }
+
+    #[test]
+    fn test_invalid_buffer_size_uses_device_default() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.buffer_size(), None);
+
+        config.audio_buffer_size = 1024;
+        assert_eq!(config.buffer_size(), Some(1024));
+        config.audio_buffer_size = 1000;
+        assert_eq!(config.buffer_size(), None);
+    }
+
+    #[test]
+    fn test_partial_effects_table() {
+        let partial = r#"
+            volume = 0.3
+
+            [effects]
+            delay_mix = 0.1
+        "#;
+
+        let config: AppConfig = toml::from_str(partial).unwrap();
+        assert_eq!(config.effects.delay_mix, 0.1);
+        assert_eq!(config.effects.delay_feedback, EffectSettings::default().delay_feedback);
+    }
+
+    #[test]
+    fn test_partial_drum_map_keeps_gm_notes() {
+        let partial = r#"
+            [drum_map]
+            kick = 24
+            hihat = 30
+        "#;
+
+        let config: AppConfig = toml::from_str(partial).unwrap();
+        assert_eq!(config.drum_map.kick, 24);
+        assert_eq!(config.drum_map.hihat, 30);
+        assert_eq!(config.drum_map.snare, 38);
+        assert_eq!(config.drum_map.shaker, 70);
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let partial = r#"
+            volume = 0.3
+            theme = "light"
+            base_tempo = 110.0
+        "#;
+
+        let config: AppConfig = toml::from_str(partial).unwrap();
+        let default = AppConfig::default();
+
+        // Present fields survive
+        assert_eq!(config.volume, 0.3);
+        assert_eq!(config.theme, "light");
+        assert_eq!(config.base_tempo, 110.0);
+
+        // Missing fields take their defaults
+        assert_eq!(config.scale_type, default.scale_type);
+        assert_eq!(config.update_interval_ms, default.update_interval_ms);
+        assert_eq!(config.enable_gpu_monitoring, default.enable_gpu_monitoring);
+    }
+
+    #[test]
+    fn test_layers_default_on() {
+        assert_eq!(AppConfig::default().layers(), Layers::default());
+
+        let config: AppConfig = toml::from_str("layer_processes = false").unwrap();
+        let layers = config.layers();
+        assert!(!layers.processes);
+        assert!(layers.melody && layers.fans);
+    }
+}
@@ -0,0 +1,352 @@
+use crate::audio_thread::{AudioCommand, AudioEvent, AudioThread};
+use crate::config::AppConfig;
+use crate::idle::IdleDetector;
+use crate::mapper::{MetricsMapper, MusicalParams};
+use crate::metrics::{EnvelopeFollower, MetricsCollector};
+use anyhow::{bail, Result};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Commands accepted on the daemon socket, one per line:
+/// `play`, `stop`, `panic`, `freeze`, `unfreeze`, `volume <0.0-1.0>`, `status`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DaemonCommand {
+    Play,
+    Stop,
+    Panic,    // Silence immediately and drop queued compositions
+    Freeze,   // Keep replaying the last-mapped params
+    Unfreeze, // Follow the metrics again
+    Volume(f32),
+    Status,
+}
+
+impl DaemonCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("play"), None, None) => Ok(Self::Play),
+            (Some("stop"), None, None) => Ok(Self::Stop),
+            (Some("panic"), None, None) => Ok(Self::Panic),
+            (Some("freeze"), None, None) => Ok(Self::Freeze),
+            (Some("unfreeze"), None, None) => Ok(Self::Unfreeze),
+            (Some("status"), None, None) => Ok(Self::Status),
+            // NaN and infinity parse as floats but would poison the mix
+            (Some("volume"), Some(value), None) => value
+                .parse::<f32>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .map(|v| Self::Volume(v.clamp(0.0, 1.0)))
+                .ok_or_else(|| format!("invalid volume '{}'", value)),
+            (Some(cmd), _, _) => Err(format!("unknown command '{}'", cmd)),
+            (None, _, _) => Err("empty command".to_string()),
+        }
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/syssonic.sock`, falling back to the temp directory
+pub fn default_socket_path() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("syssonic.sock")
+}
+
+/// A connected IPC client, read without blocking the tick loop
+struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    pending: Vec<u8>, // Start of a command line still waiting for its newline
+}
+
+/// Headless sonification engine: the tray backend without Tauri.
+///
+/// Runs the audio thread plus periodic collection, controlled over a Unix socket.
+pub struct Daemon {
+    audio: AudioThread,
+    collector: MetricsCollector,
+    mapper: MetricsMapper,
+    envelope: EnvelopeFollower,
+    config: AppConfig,
+    idle: IdleDetector,
+    active: bool, // Periodic playback enabled via `play`
+    next_sample: Instant,
+    last_params: Option<MusicalParams>, // Most recently played composition
+    frozen: bool,                       // Replay `last_params` instead of mapping new metrics
+    clients: Vec<Client>,               // Open IPC connections, served between ticks
+}
+
+impl Client {
+    fn new(stream: UnixStream) -> io::Result<Self> {
+        // Accepted sockets don't inherit the listener's non-blocking mode
+        stream.set_nonblocking(true)?;
+        Ok(Self { writer: stream.try_clone()?, reader: BufReader::new(stream), pending: Vec::new() })
+    }
+}
+
+impl Daemon {
+    /// `silent` renders compositions without an audio device (CI, servers without sound)
+    pub fn new(config: AppConfig, silent: bool) -> Self {
+        let audio = if silent { AudioThread::new_silent() } else { AudioThread::new() };
+        let _ = audio.send_command(AudioCommand::SetVolume(config.volume));
+        let (sample_rate, bit_depth) = config.output_format();
+        let _ = audio.send_command(AudioCommand::SetOutputFormat { sample_rate, bit_depth });
+        let _ = audio.send_command(AudioCommand::SetBufferSize(config.buffer_size()));
+        let _ = audio.send_command(AudioCommand::SetFocus(config.focus()));
+        let _ = audio.send_command(AudioCommand::SetEffects(config.effects));
+        let _ = audio.send_command(AudioCommand::SetVoiceLimit(Some(config.voice_limit.clone())));
+        let _ = audio.send_command(AudioCommand::SetLayers(config.layers()));
+        let _ = audio.send_command(AudioCommand::SetDrumMap(config.drum_map));
+        let _ = audio.send_command(AudioCommand::SetAutoGain(config.auto_gain));
+        let _ = audio.send_command(AudioCommand::SetQuantize(config.quantize_to_bar));
+
+        Self {
+            audio,
+            collector: config.build_collector(),
+            mapper: config.build_mapper(),
+            envelope: EnvelopeFollower::new(config.envelope),
+            idle: IdleDetector::new(config.idle_threshold),
+            active: config.auto_play_on_start,
+            next_sample: Instant::now(),
+            last_params: None,
+            frozen: false,
+            clients: Vec::new(),
+            config,
+        }
+    }
+
+    /// Serve IPC and play compositions until `running` is cleared
+    pub fn run(&mut self, socket_path: &Path, running: &AtomicBool) -> Result<()> {
+        if socket_path.exists() {
+            if UnixStream::connect(socket_path).is_ok() {
+                bail!("A daemon is already listening on {}", socket_path.display());
+            }
+            // Stale socket left behind by a previous run
+            fs::remove_file(socket_path)?;
+        }
+
+        let listener = UnixListener::bind(socket_path)?;
+        listener.set_nonblocking(true)?;
+        log::info!("Daemon listening on {}", socket_path.display());
+
+        while running.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => match Client::new(stream) {
+                    Ok(client) => self.clients.push(client),
+                    Err(e) => log::warn!("IPC client error: {}", e),
+                },
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => log::warn!("IPC accept failed: {}", e),
+            }
+
+            self.serve_clients();
+            self.tick();
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        let _ = self.audio.stop();
+        let _ = fs::remove_file(socket_path);
+        Ok(())
+    }
+
+    /// Answer what each client has sent so far, dropping closed connections.
+    ///
+    /// Clients are non-blocking, so one that stays connected without sending
+    /// anything never holds up the next composition.
+    fn serve_clients(&mut self) {
+        let mut clients = std::mem::take(&mut self.clients);
+        clients.retain_mut(|client| match self.serve_client(client) {
+            Ok(open) => open,
+            Err(e) => {
+                log::warn!("IPC client error: {}", e);
+                false
+            }
+        });
+        self.clients = clients;
+    }
+
+    /// Answer every complete command line from `client`; false once it hung up
+    fn serve_client(&mut self, client: &mut Client) -> io::Result<bool> {
+        loop {
+            match client.reader.read_until(b'\n', &mut client.pending) {
+                Ok(read) => {
+                    // A last line without a newline still counts once the client hangs up
+                    let hung_up = read == 0;
+                    if hung_up || client.pending.ends_with(b"\n") {
+                        let line = String::from_utf8_lossy(&client.pending).into_owned();
+                        client.pending.clear();
+                        if !line.trim().is_empty() {
+                            let reply = match DaemonCommand::parse(&line) {
+                                Ok(cmd) => self.execute(cmd),
+                                Err(e) => format!("error {}", e),
+                            };
+                            writeln!(client.writer, "{}", reply)?;
+                        }
+                    }
+                    if hung_up {
+                        return Ok(false);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(true),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn execute(&mut self, cmd: DaemonCommand) -> String {
+        match cmd {
+            DaemonCommand::Play => {
+                self.active = true;
+                self.next_sample = Instant::now();
+                "ok playing".to_string()
+            }
+            DaemonCommand::Stop => {
+                self.active = false;
+                let _ = self.audio.stop();
+                "ok stopped".to_string()
+            }
+            DaemonCommand::Panic => {
+                self.active = false;
+                self.audio.emergency_stop();
+                "ok silenced".to_string()
+            }
+            DaemonCommand::Freeze => {
+                if self.last_params.is_none() {
+                    // Nothing played yet: hold what the machine sounds like right now
+                    let (samples, interval_ms) = self.config.sampling();
+                    let metrics = self.collector.collect_averaged(samples, interval_ms);
+                    self.last_params = Some(self.mapper.map(&metrics));
+                }
+                self.set_frozen(true);
+                "ok frozen".to_string()
+            }
+            DaemonCommand::Unfreeze => {
+                self.set_frozen(false);
+                "ok unfrozen".to_string()
+            }
+            DaemonCommand::Volume(volume) => {
+                self.config.volume = volume;
+                if let Err(e) = self.config.save() {
+                    log::warn!("Failed to save config: {}", e);
+                }
+                match self.audio.send_command(AudioCommand::SetVolume(volume)) {
+                    Ok(_) => format!("ok volume {:.2}", volume),
+                    Err(e) => format!("error {}", e),
+                }
+            }
+            DaemonCommand::Status => format!(
+                "ok active={} playing={} idle={} frozen={} volume={:.2}",
+                self.active,
+                self.audio.is_playing(),
+                self.idle.is_idle(),
+                self.frozen,
+                self.audio.get_volume()
+            ),
+        }
+    }
+
+    fn set_frozen(&mut self, frozen: bool) {
+        if frozen != self.frozen {
+            self.frozen = frozen;
+            self.audio.report_frozen(frozen);
+        }
+    }
+
+    /// Start the next composition once the update interval has passed
+    fn tick(&mut self) {
+        for event in self.audio.poll_events() {
+            match event {
+                AudioEvent::Error(e) => log::warn!("Audio error: {}", e),
+                AudioEvent::NoAudioDevice => {
+                    log::warn!("No audio output device, pausing playback (use --silent on headless machines)");
+                    self.active = false;
+                }
+                _ => {}
+            }
+        }
+
+        if !self.active || Instant::now() < self.next_sample || self.audio.is_playing() {
+            return;
+        }
+
+        // Frozen: loop the held composition, ignoring new metrics (and idleness)
+        if let Some(params) = self.last_params.as_ref().filter(|_| self.frozen) {
+            self.next_sample = Instant::now() + Duration::from_millis(self.config.update_interval_ms);
+            if let Err(e) = self.audio.send_command(AudioCommand::Play(params.clone(), self.config.bars())) {
+                log::warn!("Failed to queue composition: {}", e);
+            }
+            return;
+        }
+
+        let (samples, interval_ms) = self.config.sampling();
+        let metrics = self.collector.collect_averaged(samples, interval_ms);
+        self.next_sample = Instant::now() + Duration::from_millis(self.config.update_interval_ms);
+
+        // Quiet machine: keep sampling, but don't play
+        let was_idle = self.idle.is_idle();
+        let idle = self.idle.update(&metrics);
+        if idle != was_idle {
+            self.audio.report_idle(idle);
+        }
+        if idle {
+            return;
+        }
+
+        let params = self.mapper.map(&self.envelope.follow(&metrics));
+        if let Err(e) = self.audio.send_command(AudioCommand::Play(params.clone(), self.config.bars())) {
+            log::warn!("Failed to queue composition: {}", e);
+        }
+        self.last_params = Some(params);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(DaemonCommand::parse("play"), Ok(DaemonCommand::Play));
+        assert_eq!(DaemonCommand::parse("  stop \n"), Ok(DaemonCommand::Stop));
+        assert_eq!(DaemonCommand::parse("panic"), Ok(DaemonCommand::Panic));
+        assert_eq!(DaemonCommand::parse("status"), Ok(DaemonCommand::Status));
+        assert_eq!(DaemonCommand::parse("freeze"), Ok(DaemonCommand::Freeze));
+        assert_eq!(DaemonCommand::parse("unfreeze"), Ok(DaemonCommand::Unfreeze));
+        assert_eq!(DaemonCommand::parse("volume 0.5"), Ok(DaemonCommand::Volume(0.5)));
+        assert_eq!(DaemonCommand::parse("volume 3"), Ok(DaemonCommand::Volume(1.0)));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        assert!(DaemonCommand::parse("").is_err());
+        assert!(DaemonCommand::parse("rewind").is_err());
+        assert!(DaemonCommand::parse("volume loud").is_err());
+        assert!(DaemonCommand::parse("volume nan").is_err());
+        assert!(DaemonCommand::parse("volume inf").is_err());
+        assert!(DaemonCommand::parse("play now").is_err());
+    }
+
+    #[test]
+    fn test_idle_client_does_not_block() {
+        let mut daemon = Daemon::new(AppConfig::default(), true);
+        let (mut remote, stream) = UnixStream::pair().unwrap();
+        let mut client = Client::new(stream).unwrap();
+
+        // Half a command: nothing to answer yet, and no waiting for the rest
+        remote.write_all(b"sta").unwrap();
+        assert!(daemon.serve_client(&mut client).unwrap());
+
+        remote.write_all(b"tus\n").unwrap();
+        assert!(daemon.serve_client(&mut client).unwrap());
+        let mut reply = String::new();
+        BufReader::new(remote.try_clone().unwrap()).read_line(&mut reply).unwrap();
+        assert!(reply.starts_with("ok active=false"), "{}", reply);
+
+        remote.shutdown(std::net::Shutdown::Write).unwrap();
+        assert!(!daemon.serve_client(&mut client).unwrap(), "hung up");
+    }
+}
@@ -0,0 +1,118 @@
+use crate::metrics::{BatteryMetrics, BatteryState, NvidiaGpuMetrics, SystemMetrics};
+
+/// One step of the guided tour: synthetic metrics plus what to listen for
+pub struct DemoScene {
+    pub title: &'static str,
+    pub narration: &'static str,
+    pub metrics: SystemMetrics,
+}
+
+/// Scripted tour of the mappings: idle → CPU ramp → disk burst → GPU load →
+/// low battery → thermal alarm. Each scene changes one subsystem from the
+/// scene before, so the difference is what it sounds like.
+pub fn tour() -> Vec<DemoScene> {
+    let idle = SystemMetrics::quiet();
+
+    let mut cpu_ramp = idle.clone();
+    cpu_ramp.cpu_usage = 85.0;
+    cpu_ramp.per_core_usage = vec![95.0, 80.0, 60.0, 30.0];
+    cpu_ramp.load_avg_1 = 3.5;
+    cpu_ramp.load_avg_5 = 2.0;
+
+    let mut disk_burst = cpu_ramp.clone();
+    disk_burst.cpu_usage = 40.0;
+    disk_burst.per_core_usage = vec![45.0; 4];
+    disk_burst.disk_read_bytes = 150_000_000;
+    disk_burst.disk_write_bytes = 60_000_000;
+
+    let mut gpu_load = idle.clone();
+    gpu_load.cpu_usage = 30.0;
+    gpu_load.gpu_nvidia = Some(NvidiaGpuMetrics {
+        name: Some("Demo GPU".to_string()),
+        utilization: 95.0,
+        temperature: 72.0,
+        memory_used: 7_000_000_000,
+        memory_total: 8_000_000_000,
+        power_draw: 220.0,
+        fan_speed: Some(65.0),
+        clocks: None,
+    });
+    gpu_load.gpu_vendor = Some("NVIDIA".to_string());
+    gpu_load.gpu_name = Some("Demo GPU".to_string());
+
+    let mut low_battery = idle.clone();
+    low_battery.battery = Some(BatteryMetrics {
+        state_of_charge: 8.0,
+        state: BatteryState::Discharging,
+        power_rate: -18.0,
+        temperature: None,
+        time_to_full: None,
+        time_to_empty: Some(5.0),
+    });
+
+    let mut thermal_alarm = cpu_ramp.clone();
+    thermal_alarm.cpu_usage = 98.0;
+    thermal_alarm.memory_usage = 92.0;
+    thermal_alarm.temperature = 95.0;
+    thermal_alarm.temperature_raw = 95.0;
+
+    vec![
+        DemoScene {
+            title: "Idle",
+            narration: "A quiet machine: a low, slow melody, sparse drums and a calm pad.",
+            metrics: idle,
+        },
+        DemoScene {
+            title: "CPU ramp",
+            narration: "CPU climbs to 85%: the melody rises up the scale and each busy core adds its own shaker.",
+            metrics: cpu_ramp,
+        },
+        DemoScene {
+            title: "Disk burst",
+            narration: "Heavy disk I/O: denser drums, toms for reads, rimshots for writes, and shorter melody notes.",
+            metrics: disk_burst,
+        },
+        DemoScene {
+            title: "GPU load",
+            narration: "A GPU at full tilt: its own chorused voice, a bigger reverb from VRAM and a sub-bass rumble from power draw.",
+            metrics: gpu_load,
+        },
+        DemoScene {
+            title: "Low battery",
+            narration: "On battery with 5 minutes left: quieter, darker tones, a pulsing pad and a rising warning ping.",
+            metrics: low_battery,
+        },
+        DemoScene {
+            title: "Thermal alarm",
+            narration: "Hot and saturated: open filters, wide reverb, and the piece lifts to the stress key.",
+            metrics: thermal_alarm,
+        },
+    ]
+}
+
+#[cfg(all(test, feature = "audio"))]
+mod tests {
+    use super::*;
+    use crate::composer::SystemComposer;
+    use crate::mapper::MetricsMapper;
+
+    #[test]
+    fn test_every_scene_composes() {
+        let composer = SystemComposer::new_silent();
+        let scenes = tour();
+        assert_eq!(scenes.len(), 6);
+
+        for scene in &scenes {
+            let params = MetricsMapper::new().map(&scene.metrics);
+            assert!(!composer.arrange(&params, 1).is_empty(), "{} rendered nothing", scene.title);
+        }
+
+        let params = |title| {
+            let scene = scenes.iter().find(|s| s.title == title).unwrap();
+            MetricsMapper::new().map(&scene.metrics)
+        };
+        assert!(params("Disk burst").rhythm_density > params("Idle").rhythm_density);
+        assert!(params("GPU load").gpu_notes.is_some());
+        assert!(params("Low battery").battery_warning.is_some());
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::{bail, Result};
+use std::fs;
+
+/// Tempo/meter information embedded into exported audio files
+#[derive(Debug, Clone, Copy)]
+pub struct LoopInfo {
+    pub bpm: f32,
+    pub beats: u32,
+    pub time_signature: (u16, u16), // (numerator, denominator)
+}
+
+impl LoopInfo {
+    fn comment(&self) -> String {
+        format!(
+            "BPM={:.2}; Time signature {}/{}",
+            self.bpm, self.time_signature.0, self.time_signature.1
+        )
+    }
+}
+
+/// Append an ACID `acid` chunk (tempo/meter) and a `LIST/INFO` comment to a WAV file.
+///
+/// DAWs that understand ACID metadata pick up the tempo and can auto-warp the loop.
+pub fn tag_wav(path: &str, info: LoopInfo) -> Result<()> {
+    let mut data = fs::read(path)?;
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        bail!("{} is not a RIFF/WAVE file", path);
+    }
+
+    // acid chunk: flags, root note, 2 reserved fields, beats, meter, tempo
+    let mut acid = Vec::with_capacity(24);
+    acid.extend_from_slice(&0u32.to_le_bytes()); // Loop (not one-shot)
+    acid.extend_from_slice(&60u16.to_le_bytes()); // Root note C4
+    acid.extend_from_slice(&0x8000u16.to_le_bytes());
+    acid.extend_from_slice(&0f32.to_le_bytes());
+    acid.extend_from_slice(&info.beats.to_le_bytes());
+    acid.extend_from_slice(&info.time_signature.1.to_le_bytes());
+    acid.extend_from_slice(&info.time_signature.0.to_le_bytes());
+    acid.extend_from_slice(&info.bpm.to_le_bytes());
+    push_riff_chunk(&mut data, b"acid", &acid);
+
+    // LIST/INFO/ICMT comment, readable by most audio tools
+    let mut comment = info.comment().into_bytes();
+    comment.push(0);
+    let mut list = b"INFO".to_vec();
+    push_riff_chunk(&mut list, b"ICMT", &comment);
+    push_riff_chunk(&mut data, b"LIST", &list);
+
+    // Fix up the RIFF size to cover the new chunks
+    let riff_size = (data.len() - 8) as u32;
+    data[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    fs::write(path, data)?;
+    Ok(())
+}
+
+/// Add `BPM=` and `TIME_SIGNATURE=` Vorbis comments to a FLAC file
+pub fn tag_flac(path: &str, info: LoopInfo) -> Result<()> {
+    let data = fs::read(path)?;
+    if data.len() < 4 || &data[0..4] != b"fLaC" {
+        bail!("{} is not a FLAC file", path);
+    }
+
+    let fields = vec![
+        format!("BPM={:.2}", info.bpm),
+        format!("TIME_SIGNATURE={}/{}", info.time_signature.0, info.time_signature.1),
+        format!("COMMENT={}", info.comment()),
+    ];
+
+    // Walk the metadata blocks: (type, body)
+    let mut blocks: Vec<(u8, Vec<u8>)> = Vec::new();
+    let mut pos = 4;
+    loop {
+        if pos + 4 > data.len() {
+            bail!("{} has truncated FLAC metadata", path);
+        }
+        let header = data[pos];
+        let len = u32::from_be_bytes([0, data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let body_start = pos + 4;
+        if body_start + len > data.len() {
+            bail!("{} has truncated FLAC metadata", path);
+        }
+        blocks.push((header & 0x7f, data[body_start..body_start + len].to_vec()));
+        pos = body_start + len;
+        if header & 0x80 != 0 {
+            break;
+        }
+    }
+    let audio_frames = &data[pos..];
+
+    // Only one VORBIS_COMMENT (type 4) block is allowed: extend it or insert one after STREAMINFO
+    const VORBIS_COMMENT: u8 = 4;
+    match blocks.iter_mut().find(|(kind, _)| *kind == VORBIS_COMMENT) {
+        Some((_, body)) => *body = extend_vorbis_comment(body, &fields)?,
+        None => {
+            let body = extend_vorbis_comment(&empty_vorbis_comment(), &fields)?;
+            blocks.insert(1.min(blocks.len()), (VORBIS_COMMENT, body));
+        }
+    }
+
+    let mut out = b"fLaC".to_vec();
+    let last = blocks.len() - 1;
+    for (i, (kind, body)) in blocks.iter().enumerate() {
+        let flag = if i == last { 0x80 } else { 0 };
+        let len = (body.len() as u32).to_be_bytes();
+        out.push(flag | kind);
+        out.extend_from_slice(&len[1..]);
+        out.extend_from_slice(body);
+    }
+    out.extend_from_slice(audio_frames);
+
+    fs::write(path, out)?;
+    Ok(())
+}
+
+fn push_riff_chunk(buf: &mut Vec<u8>, id: &[u8; 4], body: &[u8]) {
+    buf.extend_from_slice(id);
+    buf.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    buf.extend_from_slice(body);
+    if body.len() % 2 == 1 {
+        buf.push(0); // RIFF chunks are word-aligned
+    }
+}
+
+fn empty_vorbis_comment() -> Vec<u8> {
+    let vendor = b"SysSonic";
+    let mut body = (vendor.len() as u32).to_le_bytes().to_vec();
+    body.extend_from_slice(vendor);
+    body.extend_from_slice(&0u32.to_le_bytes());
+    body
+}
+
+fn extend_vorbis_comment(body: &[u8], fields: &[String]) -> Result<Vec<u8>> {
+    let read_u32 = |at: usize| -> Result<u32> {
+        match body.get(at..at + 4) {
+            Some(b) => Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            None => bail!("malformed Vorbis comment block"),
+        }
+    };
+
+    let vendor_len = read_u32(0)? as usize;
+    let count_at = 4 + vendor_len;
+    let count = read_u32(count_at)?;
+
+    let mut out = body[..count_at].to_vec();
+    out.extend_from_slice(&(count + fields.len() as u32).to_le_bytes());
+    out.extend_from_slice(&body[count_at + 4..]);
+    for field in fields {
+        out.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        out.extend_from_slice(field.as_bytes());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INFO: LoopInfo = LoopInfo { bpm: 112.0, beats: 16, time_signature: (4, 4) };
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir().join(name).to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_tag_wav_adds_acid_chunk() {
+        let path = temp_path("syssonic_tag_test.wav");
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&4u32.to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        fs::write(&path, &wav).unwrap();
+
+        tag_wav(&path, INFO).unwrap();
+
+        let data = fs::read(&path).unwrap();
+        let riff_size = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+        assert_eq!(riff_size, data.len() - 8);
+
+        let acid = data.windows(4).position(|w| w == b"acid").unwrap();
+        let tempo_at = acid + 8 + 20;
+        let tempo = f32::from_le_bytes([data[tempo_at], data[tempo_at + 1], data[tempo_at + 2], data[tempo_at + 3]]);
+        assert_eq!(tempo, 112.0);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_tag_flac_adds_bpm_comment() {
+        let path = temp_path("syssonic_tag_test.flac");
+        let mut flac = b"fLaC".to_vec();
+        flac.push(0x80); // Last block, STREAMINFO
+        flac.extend_from_slice(&[0, 0, 34]);
+        flac.extend_from_slice(&[0u8; 34]);
+        flac.extend_from_slice(&[0xff, 0xf8]); // Start of audio frames
+        fs::write(&path, &flac).unwrap();
+
+        tag_flac(&path, INFO).unwrap();
+
+        let data = fs::read(&path).unwrap();
+        assert_eq!(data[4] & 0x80, 0, "STREAMINFO is no longer the last block");
+        assert_eq!(data[4 + 4 + 34], 0x80 | 4, "Vorbis comment is the last block");
+        assert!(data.windows(10).any(|w| w == b"BPM=112.00"));
+        assert_eq!(&data[data.len() - 2..], &[0xff, 0xf8]);
+
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::metrics::SystemMetrics;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Activity levels below which the machine counts as idle.
+///
+/// Every subsystem has to stay under its threshold for `after_secs` before
+/// playback pauses, so a single quiet sample doesn't cut the music.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleThreshold {
+    pub after_secs: f32,    // Quiet time before playback pauses (0 = always play)
+    pub cpu_percent: f32,   // 0-100%
+    pub gpu_percent: f32,   // 0-100%
+    pub disk_bytes: u64,    // bytes/sec, reads + writes
+    pub network_bytes: u64, // bytes/sec, rx + tx
+}
+
+impl Default for IdleThreshold {
+    fn default() -> Self {
+        Self {
+            after_secs: 0.0,
+            cpu_percent: 5.0,
+            gpu_percent: 5.0,
+            disk_bytes: 200_000,
+            network_bytes: 50_000,
+        }
+    }
+}
+
+impl IdleThreshold {
+    /// Whether every subsystem is below its threshold in `metrics`
+    fn is_quiet(&self, metrics: &SystemMetrics) -> bool {
+        let gpu_util = metrics.gpu_nvidia.as_ref().map(|g| g.utilization)
+            .or_else(|| metrics.gpu_amd.as_ref().map(|g| g.utilization))
+            .unwrap_or(0.0);
+
+        metrics.cpu_usage < self.cpu_percent
+            && gpu_util < self.gpu_percent
+            && metrics.disk_read_bytes + metrics.disk_write_bytes < self.disk_bytes
+            && metrics.network_rx_bytes + metrics.network_tx_bytes < self.network_bytes
+    }
+}
+
+/// Tracks how long the machine has been quiet, for the live loops to skip playback
+pub struct IdleDetector {
+    threshold: IdleThreshold,
+    quiet_since: Option<Instant>,
+    idle: bool,
+}
+
+impl IdleDetector {
+    pub fn new(threshold: IdleThreshold) -> Self {
+        Self { threshold, quiet_since: None, idle: false }
+    }
+
+    /// Feed the latest metrics; returns whether the machine is now idle
+    pub fn update(&mut self, metrics: &SystemMetrics) -> bool {
+        self.update_at(metrics, Instant::now())
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    fn update_at(&mut self, metrics: &SystemMetrics, now: Instant) -> bool {
+        if self.threshold.after_secs <= 0.0 || !self.threshold.is_quiet(metrics) {
+            self.quiet_since = None;
+            self.idle = false;
+            return false;
+        }
+
+        let quiet_since = *self.quiet_since.get_or_insert(now);
+        self.idle = now.duration_since(quiet_since) >= Duration::from_secs_f32(self.threshold.after_secs);
+        self.idle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_after_sustained_quiet() {
+        let mut metrics = SystemMetrics::quiet();
+        metrics.cpu_usage = 1.0;
+        metrics.disk_read_bytes = 0;
+        metrics.disk_write_bytes = 0;
+        metrics.network_rx_bytes = 0;
+        metrics.network_tx_bytes = 0;
+
+        let start = Instant::now();
+        let mut detector = IdleDetector::new(IdleThreshold { after_secs: 10.0, ..Default::default() });
+        assert!(!detector.update_at(&metrics, start));
+        assert!(detector.update_at(&metrics, start + Duration::from_secs(11)));
+
+        // Any activity wakes it up and restarts the countdown
+        metrics.cpu_usage = 40.0;
+        assert!(!detector.update_at(&metrics, start + Duration::from_secs(12)));
+        metrics.cpu_usage = 1.0;
+        assert!(!detector.update_at(&metrics, start + Duration::from_secs(13)));
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let mut metrics = SystemMetrics::quiet();
+        metrics.cpu_usage = 0.0;
+
+        let mut detector = IdleDetector::new(IdleThreshold::default());
+        assert!(!detector.update_at(&metrics, Instant::now() + Duration::from_secs(3600)));
+    }
+}
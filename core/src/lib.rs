@@ -0,0 +1,35 @@
+// SysSonic engine: metrics collection, mapping, composition and playback.
+// Shared by the CLI, the tray app and the headless daemon.
+//
+// Everything that makes sound (or needs the audio stack to build) sits behind
+// the default `audio` feature; without it the crate is metrics and mapping only.
+#[cfg(feature = "audio")]
+mod audio_export;
+#[cfg(feature = "audio")]
+pub mod audio_thread;
+pub mod auto_gain;
+#[cfg(feature = "audio")]
+pub mod composer;
+#[cfg(feature = "audio")]
+pub mod config;
+#[cfg(all(unix, feature = "audio"))]
+pub mod daemon;
+pub mod demo;
+#[cfg(feature = "audio")]
+mod export_metadata;
+pub mod idle;
+pub mod mapper;
+pub mod metrics;
+#[cfg(feature = "audio")]
+pub mod midi_clock;
+#[cfg(feature = "audio")]
+mod midi_export;
+mod notes;
+#[cfg(feature = "audio")]
+mod playback;
+#[cfg(feature = "audio")]
+pub mod scene;
+#[cfg(feature = "audio")]
+pub mod snapshot;
+#[cfg(feature = "audio")]
+pub mod timeline;
@@ -0,0 +1,1965 @@
+use crate::metrics::SystemMetrics;
+use crate::notes::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicI32, AtomicU64, Ordering};
+
+/// Musical parameters derived from system metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MusicalParams {
+    // Original CPU-based melody
+    pub melody_notes: Vec<f32>,      // Frequencies for melody
+    pub bass_note: f32,               // Bass frequency
+    pub bass_velocity: f32,           // 0.0-1.0
+    pub rhythm_density: f32,          // 0.0-1.0 (how many percussion hits)
+    pub melody_note_scale: f32,       // 0.5-1.5 (melody note length, short under disk I/O)
+    pub tempo: f32,                   // BPM
+    pub filter_cutoff: f32,           // Hz
+    pub reverb_mix: f32,              // 0.0-1.0
+    pub kick_hits: Vec<usize>,        // Which grid steps get kicks
+    pub snare_hits: Vec<usize>,       // Which grid steps get snares
+    pub grid: GridResolution,         // Steps per bar for every hit pattern
+
+    // Disk reads/writes → separate percussion voices
+    pub read_density: f32,            // 0.0-1.0 (disk reads)
+    pub write_density: f32,           // 0.0-1.0 (disk writes)
+    pub tom_hits: Vec<usize>,         // Which grid steps get toms (reads)
+    pub rimshot_hits: Vec<usize>,     // Which grid steps get rimshots (writes)
+    pub pluck_hits: Vec<usize>,       // Which grid steps get plucks (connection churn)
+
+    // Context switches + interrupts → high tick texture
+    #[serde(default)]
+    pub kernel_ticks: f32,            // 0.0-1.0 (tick density)
+
+    // Network traffic → sidechain pump
+    #[serde(default)]
+    pub sidechain_pump: f32,          // 0.0-1.0 (how hard the kick ducks the melody/pad)
+
+    // GPU voice and effects
+    pub gpu_notes: Option<Vec<f32>>,  // GPU-driven melody (separate voice)
+    pub gpu_intensity: f32,           // 0.0-1.0 (utilization)
+    pub gpu_chorus_depth: f32,        // 0.0-1.0
+    pub gpu_flanger_rate: f32,        // Hz
+    pub gpu_detune: f32,              // cents (core clock)
+    pub gpu_brightness: f32,          // 0.0-1.0 (memory clock → filter)
+
+    // GPU memory → reverb
+    pub vram_reverb_size: f32,        // 0.0-1.0 (% VRAM used)
+
+    // GPU power draw → sub-bass rumble
+    pub gpu_power_rumble: f32,        // 0.0-1.0 (power relative to TDP)
+
+    // Load average → complexity
+    pub rhythm_polyrhythm_factor: f32, // 0.0-1.0 (how polyrhythmic)
+    pub harmonic_voices: usize,        // Number of additional voices
+    #[serde(default)]
+    pub harmony_notes: Vec<Vec<f32>>,  // Melody harmonized a third, fifth, seventh up in the scale (harmonic_voices - 1 lines)
+    pub arp_rate: f32,                 // Arpeggio notes per beat (1.0-4.0)
+    #[serde(default)]
+    pub chord_notes: Vec<f32>,         // Pad/arpeggio chord from the tonal scale: root, third, fifth, seventh, octave (A3 up, in key)
+
+    // Swap → distortion
+    pub swap_distortion: f32,         // 0.0-1.0 (usage, subtle floor)
+    pub swap_activity: f32,           // 0.0-1.0 (active paging, decaying crunch)
+
+    // Battery → dynamics
+    pub battery_volume_mult: f32,     // 0.5-1.0 (volume multiplier)
+    pub battery_tonality: f32,        // -1.0 (minor) to 1.0 (major)
+    pub battery_tremolo_rate: f32,    // Hz (0.0 = off when charging/on AC)
+    pub battery_warning: Option<f32>, // 0.0-1.0 urgency when time-to-empty is low
+
+    // Per-core → polyrhythm patterns
+    pub core_patterns: Vec<Vec<usize>>, // Rhythmic pattern per core voice
+
+    // Process count → hi-hat density
+    pub hihat_density: f32,           // 0.0-1.0
+
+    // Top processes → mini-melodies
+    pub process_melodies: Vec<(String, Vec<f32>)>, // (name, melody)
+    pub io_bound_processes: Vec<String>, // Processes played with the disk timbre
+
+    // Fan speeds → ambience
+    pub fan_noise_level: f32,         // 0.0-1.0
+
+    // Stress modulation
+    pub key_shift: i32,               // Semitones above the home key (0 or 7)
+
+    // Scale/key the notes above were drawn from (for display)
+    pub context: MusicalContext,
+}
+
+/// Human-readable musical context for the UI
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MusicalContext {
+    pub scale_name: String,    // e.g. "minor pentatonic"
+    pub root_note: String,     // e.g. "A"
+    pub key_signature: String, // e.g. "A minor"
+}
+
+/// One metric → parameter mapping behind the current sound, for a
+/// "why does it sound like this" view
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MappingExplanation {
+    pub metric: &'static str, // e.g. "temperature"
+    pub raw: f32,             // Metric as measured (%, bytes/s, °C, ...)
+    pub normalized: f32,      // 0.0-1.0 after curves and inversions
+    pub target: &'static str, // MusicalParams field it drives, e.g. "filter_cutoff"
+    pub value: f32,           // Resulting parameter value
+}
+
+impl MusicalParams {
+    /// Blend continuous parameters from `previous` toward these values.
+    ///
+    /// `amount` 0.0 keeps the previous values, 1.0 keeps these. Discrete
+    /// parameters (notes, hit patterns, voice counts) and transients (swap
+    /// activity) always take the new values.
+    pub fn slewed_from(mut self, previous: &MusicalParams, amount: f32) -> Self {
+        let t = amount.clamp(0.0, 1.0);
+        let blend = |from: f32, to: &mut f32| *to = from + (*to - from) * t;
+
+        blend(previous.tempo, &mut self.tempo);
+        blend(previous.filter_cutoff, &mut self.filter_cutoff);
+        blend(previous.reverb_mix, &mut self.reverb_mix);
+        blend(previous.bass_velocity, &mut self.bass_velocity);
+        blend(previous.rhythm_density, &mut self.rhythm_density);
+        blend(previous.melody_note_scale, &mut self.melody_note_scale);
+        blend(previous.gpu_intensity, &mut self.gpu_intensity);
+        blend(previous.gpu_chorus_depth, &mut self.gpu_chorus_depth);
+        blend(previous.gpu_flanger_rate, &mut self.gpu_flanger_rate);
+        blend(previous.gpu_detune, &mut self.gpu_detune);
+        blend(previous.gpu_brightness, &mut self.gpu_brightness);
+        blend(previous.vram_reverb_size, &mut self.vram_reverb_size);
+        blend(previous.gpu_power_rumble, &mut self.gpu_power_rumble);
+        blend(previous.arp_rate, &mut self.arp_rate);
+        blend(previous.swap_distortion, &mut self.swap_distortion);
+        blend(previous.battery_volume_mult, &mut self.battery_volume_mult);
+        blend(previous.battery_tonality, &mut self.battery_tonality);
+        blend(previous.battery_tremolo_rate, &mut self.battery_tremolo_rate);
+        blend(previous.hihat_density, &mut self.hihat_density);
+        blend(previous.fan_noise_level, &mut self.fan_noise_level);
+        blend(previous.kernel_ticks, &mut self.kernel_ticks);
+        blend(previous.sidechain_pump, &mut self.sidechain_pump);
+
+        self
+    }
+
+    /// e.g. "A minor pentatonic, 112 BPM"
+    pub fn now_playing(&self) -> String {
+        format!("{} {}, {:.0} BPM", self.context.root_note, self.context.scale_name, self.tempo)
+    }
+}
+
+/// Scales the mapper can draw notes from, by `scale_type` config name
+pub const SCALE_NAMES: [&str; 6] = ["minor_pentatonic", "major", "minor", "blues", "dorian", "chromatic"];
+
+/// Scale the melody is drawn from, rooted on A
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Scale {
+    #[default]
+    MinorPentatonic, // A C D E G
+    Major,           // Bright
+    Minor,           // Natural minor
+    Blues,           // Minor pentatonic plus the flat fifth
+    Dorian,          // Minor with a raised sixth
+    Chromatic,       // All twelve semitones
+}
+
+impl Scale {
+    pub const ALL: [Scale; 6] =
+        [Scale::MinorPentatonic, Scale::Major, Scale::Minor, Scale::Blues, Scale::Dorian, Scale::Chromatic];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "minor_pentatonic" | "pentatonic" => Some(Self::MinorPentatonic),
+            "major" => Some(Self::Major),
+            "minor" | "natural_minor" => Some(Self::Minor),
+            "blues" => Some(Self::Blues),
+            "dorian" => Some(Self::Dorian),
+            "chromatic" => Some(Self::Chromatic),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        SCALE_NAMES[Self::ALL.iter().position(|s| s == self).unwrap_or(0)]
+    }
+
+    /// Semitones above the root in one octave
+    fn intervals(&self) -> &'static [i32] {
+        match self {
+            Scale::MinorPentatonic => &[0, 3, 5, 7, 10],
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Scale::Blues => &[0, 3, 5, 6, 7, 10],
+            Scale::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+
+    /// Melody notes from A3 up to D6, the range the pentatonic always covered
+    pub fn notes(&self) -> Vec<f32> {
+        if *self == Scale::MinorPentatonic {
+            // The note table's values, unchanged from before scales were selectable
+            return vec![A3, C4, D4, E4, G4, A4, C5, D5, E5, G5, A5, C6, D6];
+        }
+        (0..=29) // A3 to D6
+            .filter(|semitones| self.intervals().contains(&(semitones % 12)))
+            .map(|semitones| {
+                // Rounded like the note table, to the hundredth of a hertz
+                let freq = A3 * 2f32.powf(semitones as f32 / 12.0);
+                (freq * 100.0).round() / 100.0
+            })
+            .collect()
+    }
+
+    /// Context shown in the UI, e.g. "minor pentatonic" in "A minor"
+    fn context(&self) -> MusicalContext {
+        let (scale_name, mode) = match self {
+            Scale::MinorPentatonic => ("minor pentatonic", "minor"),
+            Scale::Major => ("major", "major"),
+            Scale::Minor => ("natural minor", "minor"),
+            Scale::Blues => ("blues", "minor"),
+            Scale::Dorian => ("dorian", "dorian"),
+            Scale::Chromatic => ("chromatic", "chromatic"),
+        };
+        MusicalContext {
+            scale_name: scale_name.to_string(),
+            root_note: "A".to_string(),
+            key_signature: format!("A {}", mode),
+        }
+    }
+}
+
+/// Shape applied when normalizing a metric to 0.0-1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseCurve {
+    Linear,
+    Logarithmic, // Low activity audible, compresses near the top (byte rates)
+    Exponential, // Quiet until the metric nears its maximum
+}
+
+/// Metric → music directions to flip (`1.0 - x` on the normalized input),
+/// e.g. high CPU = a calm, low melody instead of a rising one
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MappingInversions {
+    pub cpu_pitch: bool,     // Busy CPU → lower melody
+    pub temp_filter: bool,   // Hot → darker, drier
+    pub network_tempo: bool, // Heavy traffic → slower
+}
+
+/// How per-core usage is reduced to a handful of shaker voices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoreGrouping {
+    Average, // Neighbouring cores averaged into equal groups
+    Busiest, // The busiest cores, each on its own
+}
+
+impl CoreGrouping {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "average" | "mean" => Some(Self::Average),
+            "busiest" | "top" => Some(Self::Busiest),
+            _ => None,
+        }
+    }
+}
+
+/// Shape of the 4-note melody around the CPU-derived scale index
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MelodyContour {
+    Classic,    // base, down one, up two, base
+    Ascending,  // Four-note run upward
+    Descending, // Four-note run downward
+    Zigzag,     // Alternating leaps up and down
+    RandomWalk, // Seeded steps of one or two scale degrees
+}
+
+impl MelodyContour {
+    pub const ALL: [MelodyContour; 5] = [
+        MelodyContour::Classic,
+        MelodyContour::Ascending,
+        MelodyContour::Descending,
+        MelodyContour::Zigzag,
+        MelodyContour::RandomWalk,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "classic" => Some(Self::Classic),
+            "ascending" | "up" => Some(Self::Ascending),
+            "descending" | "down" => Some(Self::Descending),
+            "zigzag" | "wave" => Some(Self::Zigzag),
+            "random_walk" | "random" => Some(Self::RandomWalk),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MelodyContour::Classic => "classic",
+            MelodyContour::Ascending => "ascending",
+            MelodyContour::Descending => "descending",
+            MelodyContour::Zigzag => "zigzag",
+            MelodyContour::RandomWalk => "random_walk",
+        }
+    }
+}
+
+/// Steps per bar of the percussion grid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridResolution {
+    #[default]
+    Sixteenth,        // 16 steps
+    ThirtySecond,     // 32 steps, for rolls and ghost notes
+    EighthTriplet,    // 12 steps, three per beat
+    SixteenthTriplet, // 24 steps, six per beat
+}
+
+impl GridResolution {
+    pub const ALL: [GridResolution; 4] = [
+        GridResolution::Sixteenth,
+        GridResolution::ThirtySecond,
+        GridResolution::EighthTriplet,
+        GridResolution::SixteenthTriplet,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "sixteenth" | "16" | "1/16" => Some(Self::Sixteenth),
+            "thirty_second" | "32" | "1/32" => Some(Self::ThirtySecond),
+            "eighth_triplet" | "triplet" | "12" => Some(Self::EighthTriplet),
+            "sixteenth_triplet" | "24" => Some(Self::SixteenthTriplet),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GridResolution::Sixteenth => "sixteenth",
+            GridResolution::ThirtySecond => "thirty_second",
+            GridResolution::EighthTriplet => "eighth_triplet",
+            GridResolution::SixteenthTriplet => "sixteenth_triplet",
+        }
+    }
+
+    pub fn steps(self) -> usize {
+        match self {
+            GridResolution::Sixteenth => 16,
+            GridResolution::ThirtySecond => 32,
+            GridResolution::EighthTriplet => 12,
+            GridResolution::SixteenthTriplet => 24,
+        }
+    }
+
+    /// Move sorted 16th-grid positions onto this grid (beats stay on beats)
+    pub fn rescale(self, sixteenths: &[usize]) -> Vec<usize> {
+        let mut steps: Vec<usize> = sixteenths.iter().map(|&i| i * self.steps() / 16).collect();
+        steps.dedup();
+        steps
+    }
+}
+
+/// What drives the filter and reverb when no temperature sensor is found
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemperatureFallback {
+    Fixed,  // The collector's fallback temperature
+    Cpu,    // CPU usage
+    Memory, // Memory usage
+    Load,   // 1-minute load average per core
+}
+
+impl TemperatureFallback {
+    pub const ALL: [TemperatureFallback; 4] = [
+        TemperatureFallback::Fixed,
+        TemperatureFallback::Cpu,
+        TemperatureFallback::Memory,
+        TemperatureFallback::Load,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "fixed" | "none" => Some(Self::Fixed),
+            "cpu" => Some(Self::Cpu),
+            "memory" | "mem" => Some(Self::Memory),
+            "load" => Some(Self::Load),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TemperatureFallback::Fixed => "fixed",
+            TemperatureFallback::Cpu => "cpu",
+            TemperatureFallback::Memory => "memory",
+            TemperatureFallback::Load => "load",
+        }
+    }
+}
+
+// Dynamic range of the log/exp curves (~3 decades below the maximum)
+const CURVE_STRENGTH: f32 = 1000.0;
+
+// Stress modulation: sustained load lifts the piece to the dominant key and it
+// resolves home on recovery. Exit thresholds sit lower so it doesn't flip-flop.
+const STRESS_CPU_ENTER: f32 = 80.0;
+const STRESS_CPU_EXIT: f32 = 65.0;
+const STRESS_MEMORY_ENTER: f32 = 90.0;
+const STRESS_MEMORY_EXIT: f32 = 80.0;
+const STRESS_KEY_SHIFT: i32 = 7; // Up a fifth (A minor → E minor)
+
+const NOTE_NAMES: [&str; 12] = ["A", "A#", "B", "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#"];
+
+impl ResponseCurve {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "linear" => Some(Self::Linear),
+            "log" | "logarithmic" => Some(Self::Logarithmic),
+            "exp" | "exponential" => Some(Self::Exponential),
+            _ => None,
+        }
+    }
+
+    /// Normalize `value` against `max` (`value >= max` → 1.0)
+    pub fn apply(self, value: f32, max: f32) -> f32 {
+        let x = (value / max).clamp(0.0, 1.0);
+        match self {
+            Self::Linear => x,
+            Self::Logarithmic => (1.0 + CURVE_STRENGTH * x).ln() / (1.0 + CURVE_STRENGTH).ln(),
+            Self::Exponential => ((1.0 + CURVE_STRENGTH).powf(x) - 1.0) / CURVE_STRENGTH,
+        }
+    }
+}
+
+/// Metrics normalized to 0.0-1.0 the way `map` scales them
+struct MetricLevels {
+    cpu: f32,         // Melody pitch
+    disk: f32,        // Rhythm density
+    disk_read: f32,   // Toms
+    disk_write: f32,  // Rimshots
+    network: f32,     // Tempo
+    temperature: f32, // Filter and reverb
+    connections: f32, // Plucks
+    kernel: f32,      // Ticks
+    load: f32,        // Arpeggiator speed
+}
+
+pub struct MetricsMapper {
+    // Musical constants
+    base_tempo: f32,
+    scale: Vec<f32>, // Melody notes, minor pentatonic by default
+    context: MusicalContext,
+
+    // Power draw (watts) treated as full GPU rumble
+    gpu_tdp_watts: f32,
+
+    // Battery time-to-empty (minutes) below which the warning layer plays
+    battery_warning_minutes: f32,
+
+    // Normalization curves for metrics spanning orders of magnitude
+    disk_curve: ResponseCurve,
+    network_curve: ResponseCurve,
+    vram_curve: ResponseCurve,
+
+    // Flipped mapping directions
+    inversions: MappingInversions,
+
+    // Per-core shaker voices, however many cores the machine has
+    core_voices: usize,
+    core_grouping: CoreGrouping,
+
+    // Melody shape, and the random walk's generator state (never 0)
+    melody_contour: MelodyContour,
+    walk_state: AtomicU64,
+    melody_seed: u64, // Seed `walk_state` started from
+
+    // Resolution of every percussion pattern
+    grid: GridResolution,
+
+    // Filter/reverb source on machines without a temperature sensor
+    temperature_fallback: TemperatureFallback,
+
+    // Current key shift, carried across calls for hysteresis
+    key_shift: AtomicI32,
+}
+
+impl MetricsMapper {
+    pub fn new() -> Self {
+        // A minor pentatonic until `with_scale` picks another
+        let scale = Scale::MinorPentatonic;
+
+        Self {
+            base_tempo: 90.0,
+            scale: scale.notes(),
+            context: scale.context(),
+            gpu_tdp_watts: 250.0,
+            battery_warning_minutes: 15.0,
+            disk_curve: ResponseCurve::Logarithmic,
+            network_curve: ResponseCurve::Logarithmic,
+            vram_curve: ResponseCurve::Linear,
+            inversions: MappingInversions::default(),
+            core_voices: 4,
+            core_grouping: CoreGrouping::Average,
+            melody_contour: MelodyContour::Classic,
+            walk_state: AtomicU64::new(1),
+            melody_seed: 1,
+            grid: GridResolution::Sixteenth,
+            temperature_fallback: TemperatureFallback::Fixed,
+            key_shift: AtomicI32::new(0),
+        }
+    }
+
+    /// Draw the melody from the scale named `scale_type` (one of `SCALE_NAMES`).
+    ///
+    /// An unknown name logs a warning and keeps minor pentatonic.
+    pub fn with_scale(mut self, scale_type: &str) -> Self {
+        let scale = Scale::from_name(scale_type).unwrap_or_else(|| {
+            log::warn!("Unknown scale '{}', using minor pentatonic", scale_type);
+            Scale::MinorPentatonic
+        });
+        self.scale = scale.notes();
+        self.context = scale.context();
+        self
+    }
+
+    /// Set the GPU power (watts) that maps to full sub-bass rumble
+    pub fn with_gpu_tdp(mut self, watts: f32) -> Self {
+        self.gpu_tdp_watts = watts.max(1.0);
+        self
+    }
+
+    /// Set the battery time-to-empty (minutes) that triggers the warning layer
+    pub fn with_battery_warning_threshold(mut self, minutes: f32) -> Self {
+        self.battery_warning_minutes = minutes.max(0.0);
+        self
+    }
+
+    /// Set the normalization curves for disk I/O, network traffic and GPU memory
+    pub fn with_response_curves(mut self, disk: ResponseCurve, network: ResponseCurve, vram: ResponseCurve) -> Self {
+        self.disk_curve = disk;
+        self.network_curve = network;
+        self.vram_curve = vram;
+        self
+    }
+
+    /// Flip the direction of individual mappings
+    pub fn with_inversions(mut self, inversions: MappingInversions) -> Self {
+        self.inversions = inversions;
+        self
+    }
+
+    /// Reduce per-core usage to at most `voices` shaker patterns.
+    ///
+    /// `Average` splits the cores into `voices` contiguous groups and plays each
+    /// group's mean usage, so a group only reaches sixteenths when all of its
+    /// cores are pegged. `Busiest` plays the `voices` busiest cores as they are.
+    pub fn with_core_voices(mut self, voices: usize, grouping: CoreGrouping) -> Self {
+        self.core_voices = voices.max(1);
+        self.core_grouping = grouping;
+        self
+    }
+
+    /// Shape the melody around the CPU-derived note.
+    ///
+    /// `seed` makes `RandomWalk` reproducible: the same seed replays the same
+    /// walks in the same order. 0 picks a fresh seed for every run.
+    pub fn with_melody_contour(mut self, contour: MelodyContour, seed: u64) -> Self {
+        let seed = if seed == 0 {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(1)
+        } else {
+            seed
+        };
+        self.melody_contour = contour;
+        self.walk_state = AtomicU64::new(seed.max(1));
+        self.melody_seed = seed.max(1);
+        self
+    }
+
+    /// Seed the random-walk melody started from (None for the fixed contours),
+    /// to replay the same session with `with_melody_contour`
+    pub fn melody_seed(&self) -> Option<u64> {
+        (self.melody_contour == MelodyContour::RandomWalk).then_some(self.melody_seed)
+    }
+
+    /// Set the percussion grid. The 16th-note patterns scale onto it, so the
+    /// four-on-the-floor kick stays on the beat at any resolution.
+    pub fn with_grid_resolution(mut self, grid: GridResolution) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Choose what opens the filter when there is no temperature sensor.
+    ///
+    /// `Fixed` plays the collector's fallback temperature, pinning the filter
+    /// and reverb; the others let a live metric stand in.
+    pub fn with_temperature_fallback(mut self, fallback: TemperatureFallback) -> Self {
+        self.temperature_fallback = fallback;
+        self
+    }
+
+    pub fn map(&self, metrics: &SystemMetrics) -> MusicalParams {
+        // Backends can overshoot (sysinfo briefly reports >100% aggregate CPU
+        // on some platforms), so pin usage before any of it becomes an index
+        let metrics = &with_usage_clamped(metrics);
+
+        // Sustained stress → Key change (everything pitched moves together)
+        let (key_shift, previous_shift) = self.update_key_shift(metrics);
+        let key_ratio = 2f32.powf(key_shift as f32 / 12.0);
+        let transpose = |notes: Vec<f32>| -> Vec<f32> { notes.into_iter().map(|n| n * key_ratio).collect() };
+        // Battery → Tonality: charging brightens the scale toward major, a draining battery darkens it
+        let (battery_volume_mult, battery_tonality) = self.map_battery(metrics);
+        let tonal = tonal_scale(&self.scale, battery_tonality);
+        let chord_notes = transpose(chord_tones(&tonal));
+        let scale = transpose(tonal);
+
+        let levels = self.levels(metrics);
+
+        // CPU Usage → Melody Pitch
+        // Map 0-100% to our scale indices
+        let scale_index = scale_index(levels.cpu, scale.len());
+        
+        // Create a 4-note melody pattern based on CPU
+        let mut walk_state = self.walk_state.load(Ordering::Relaxed);
+        let indices = contour_indices(self.melody_contour, scale_index, scale.len(), &mut walk_state);
+        self.walk_state.store(walk_state, Ordering::Relaxed);
+        let mut melody_notes: Vec<f32> = indices.iter().map(|&i| scale[i]).collect();
+
+        // Right after a key change, open on a tone both keys share
+        if key_shift != previous_shift {
+            melody_notes[0] = pivot_note(melody_notes[0], &scale, previous_shift - key_shift);
+        }
+
+        // Memory Usage → Bass Intensity, an octave under the chord so it follows the tonality
+        let bass_note = if metrics.memory_usage > 75.0 {
+            chord_notes[0] * 0.5 // Root: lower bass when memory is high (more ominous)
+        } else if metrics.memory_usage > 50.0 {
+            chord_notes[1] * 0.5 // Third: mid bass
+        } else {
+            chord_notes[2] * 0.5 // Fifth: higher bass when memory is comfortable
+        };
+        let bass_velocity = (metrics.memory_usage / 100.0).clamp(0.3, 1.0);
+
+        // Disk I/O → Rhythm Density
+        let io_normalized = levels.disk;
+        let melody_note_scale = 1.5 - io_normalized; // Idle disk = legato, heavy I/O = clipped
+        
+        // Network Traffic → Tempo Modulation
+        let tempo = self.base_tempo + (levels.network * 40.0); // 90-130 BPM range
+
+        // Temperature → Filter & Reverb
+        let filter_cutoff = 400.0 + (levels.temperature * 2600.0); // 400Hz - 3000Hz
+        let reverb_mix = levels.temperature * 0.5; // 0% - 50% reverb
+
+        // Generate percussion patterns based on I/O
+        let (kick_hits, snare_hits) = self.generate_rhythm_pattern(io_normalized);
+
+        // Disk reads → toms, disk writes → rimshots (independent densities)
+        let read_density = levels.disk_read;
+        let write_density = levels.disk_write;
+        let tom_hits = self.grid.rescale(&self.generate_read_pattern(read_density));
+        let rimshot_hits = self.grid.rescale(&self.generate_write_pattern(write_density));
+
+        // Connection churn → Staccato plucks
+        let pluck_hits = self.grid.rescale(&self.generate_pluck_pattern(levels.connections));
+
+        // Context switches + interrupts → The kernel's pulse as fine ticks
+        let kernel_ticks = levels.kernel;
+
+        // Network Traffic → Sidechain pump (not inverted with the tempo: busy links always pump harder)
+        let total_network = (metrics.network_rx_bytes + metrics.network_tx_bytes) as f32;
+        let sidechain_pump = self.network_curve.apply(total_network, 5_000_000.0);
+
+        // === NEW MAPPINGS ===
+
+        // GPU → Separate voice with unique scale (Dorian mode for contrast)
+        let (gpu_notes, gpu_intensity, gpu_chorus_depth, gpu_flanger_rate, vram_reverb_size) =
+            self.map_gpu_metrics(metrics);
+        let gpu_notes = gpu_notes.map(transpose);
+
+        // GPU clocks → GPU voice detune and brightness
+        let (gpu_detune, gpu_brightness) = self.map_gpu_clocks(metrics);
+
+        // GPU power draw → Sub-bass rumble
+        let gpu_power_rumble = self.map_gpu_power(metrics);
+
+        // Load average → Polyrhythmic complexity
+        let (rhythm_polyrhythm_factor, harmonic_voices) = self.map_load_average(metrics);
+
+        // Sustained load → Chord voices stacked on the melody, in scale
+        let harmony_notes = harmony_lines(&scale, &indices, harmonic_voices.saturating_sub(1));
+
+        // 1-min load average → Arpeggiator speed (1-4 notes per beat)
+        let arp_rate = 1.0 + levels.load * 3.0;
+
+        // Swap usage → Subtle bass distortion floor
+        let swap_distortion = self.map_swap_usage(metrics);
+
+        // Swap in/out rate → Distorted bass burst that decays (10 MB/s = full)
+        let swap_paging = (metrics.swap_in_bytes + metrics.swap_out_bytes) as f32;
+        let swap_activity = self.disk_curve.apply(swap_paging, 10_000_000.0);
+
+        // Battery discharge wattage → Pad tremolo rate
+        let battery_tremolo_rate = self.map_battery_tremolo(metrics);
+
+        // Battery time-to-empty → Escalating warning cue
+        let battery_warning = self.map_battery_warning(metrics);
+
+        // Per-core CPU → Polyrhythmic patterns
+        let core_patterns = self.map_per_core_cpu(&metrics.per_core_usage);
+        let core_patterns = self.apply_polyrhythm(core_patterns, rhythm_polyrhythm_factor);
+
+        // Process count → Hi-hat density
+        let hihat_density = self.map_process_count(metrics.process_count);
+
+        // Top processes → Mini-melodies
+        let process_melodies = self.map_top_processes(&metrics.top_processes)
+            .into_iter()
+            .map(|(name, melody)| (name, transpose(melody)))
+            .collect();
+
+        // Processes thrashing the disk (not the CPU) → Distinct timbre
+        let io_bound_processes = metrics.top_processes.iter()
+            .filter(|p| p.disk_read + p.disk_write >= 1_000_000) // 1 MB/s
+            .map(|p| p.name.clone())
+            .collect();
+
+        // Fan speeds → Ambient noise level
+        let fan_noise_level = self.map_fan_speeds(metrics);
+
+        MusicalParams {
+            // Original params
+            melody_notes,
+            bass_note,
+            bass_velocity,
+            rhythm_density: io_normalized,
+            melody_note_scale,
+            tempo,
+            filter_cutoff,
+            reverb_mix,
+            kick_hits,
+            snare_hits,
+            grid: self.grid,
+            read_density,
+            write_density,
+            tom_hits,
+            rimshot_hits,
+            pluck_hits,
+            kernel_ticks,
+            sidechain_pump,
+
+            // New params
+            gpu_notes,
+            gpu_intensity,
+            gpu_chorus_depth,
+            gpu_flanger_rate,
+            gpu_detune,
+            gpu_brightness,
+            vram_reverb_size,
+            gpu_power_rumble,
+            rhythm_polyrhythm_factor,
+            harmonic_voices,
+            harmony_notes,
+            arp_rate,
+            chord_notes,
+            swap_distortion,
+            swap_activity,
+            battery_volume_mult,
+            battery_tonality,
+            battery_tremolo_rate,
+            battery_warning,
+            core_patterns,
+            hihat_density,
+            process_melodies,
+            io_bound_processes,
+            fan_noise_level,
+            key_shift,
+            context: self.context_in_key(key_shift),
+        }
+    }
+
+    /// Normalize the metrics behind the core mappings (shared by `map` and
+    /// `explain_mapping`, so the explanation can't drift from the sound)
+    fn levels(&self, metrics: &SystemMetrics) -> MetricLevels {
+        let total_disk_io = (metrics.disk_read_bytes + metrics.disk_write_bytes) as f32;
+        let total_network = (metrics.network_rx_bytes + metrics.network_tx_bytes) as f32;
+
+        MetricLevels {
+            cpu: invert((metrics.cpu_usage / 100.0).clamp(0.0, 1.0), self.inversions.cpu_pitch),
+            disk: self.disk_curve.apply(total_disk_io, 10_000_000.0), // 10MB/s = full density
+            disk_read: self.disk_curve.apply(metrics.disk_read_bytes as f32, 5_000_000.0), // 5MB/s = full density
+            disk_write: self.disk_curve.apply(metrics.disk_write_bytes as f32, 5_000_000.0),
+            network: invert(self.network_curve.apply(total_network, 5_000_000.0), self.inversions.network_tempo), // 5MB/s = max tempo
+            // 30°C = closed/dry, 70°C = open/wet
+            temperature: invert(self.temperature_level(metrics), self.inversions.temp_filter),
+            connections: (metrics.connection_churn / 20.0).clamp(0.0, 1.0), // 20 connections/s = full density
+            kernel: ((metrics.context_switches + metrics.interrupts) as f32 / 200_000.0).clamp(0.0, 1.0), // 200k/s = densest ticks
+            load: (metrics.load_avg_1 / 8.0).clamp(0.0, 1.0), // Load 8 = fastest arpeggio
+        }
+    }
+
+    fn generate_rhythm_pattern(&self, density: f32) -> (Vec<usize>, Vec<usize>) {
+        // Base patterns (16th note grid)
+        let mut kicks = vec![0, 4, 8, 12]; // Standard 4-on-floor
+        let mut snares = vec![4, 12]; // Backbeat
+
+        // Add complexity based on density
+        if density > 0.3 {
+            kicks.push(2);
+            kicks.push(10);
+        }
+        if density > 0.6 {
+            snares.push(6);
+            snares.push(14);
+        }
+        if density > 0.8 {
+            kicks.push(1);
+            kicks.push(3);
+            kicks.push(9);
+            kicks.push(11);
+        }
+
+        kicks.sort();
+        snares.sort();
+        let kicks = self.grid.rescale(&kicks);
+        let mut snares = self.grid.rescale(&snares);
+
+        // Finer grids earn their steps at full density: a roll (or triplet
+        // fill) into the next bar across the last beat
+        if density > 0.9 && self.grid != GridResolution::Sixteenth {
+            let steps = self.grid.steps();
+            snares.extend(steps - steps / 4..steps);
+            snares.sort();
+            snares.dedup();
+        }
+
+        (kicks, snares)
+    }
+
+    fn generate_read_pattern(&self, density: f32) -> Vec<usize> {
+        // Idle disk: no toms at all
+        if density < 0.05 {
+            return vec![];
+        }
+
+        // Toms fill the gaps between kicks as reads pick up
+        let mut toms = vec![14];
+        if density > 0.3 {
+            toms.push(6);
+        }
+        if density > 0.6 {
+            toms.push(3);
+            toms.push(11);
+        }
+        if density > 0.8 {
+            toms.push(7);
+            toms.push(15);
+        }
+
+        toms.sort();
+        toms.dedup();
+        toms
+    }
+
+    fn generate_pluck_pattern(&self, density: f32) -> Vec<usize> {
+        // Stable connection count: no plucks
+        if density < 0.05 {
+            return vec![];
+        }
+
+        // Offbeat plucks, filling in as connections come and go faster
+        let mut plucks = vec![3, 11];
+        if density > 0.3 {
+            plucks.extend([7, 15]);
+        }
+        if density > 0.6 {
+            plucks.extend([1, 5, 9, 13]);
+        }
+
+        plucks.sort();
+        plucks
+    }
+
+    fn generate_write_pattern(&self, density: f32) -> Vec<usize> {
+        // Idle disk: no rimshots at all
+        if density < 0.05 {
+            return vec![];
+        }
+
+        // Rimshots sit on the off-beat sixteenths, away from the backbeat
+        let mut rims = vec![10];
+        if density > 0.3 {
+            rims.push(2);
+        }
+        if density > 0.6 {
+            rims.push(5);
+            rims.push(13);
+        }
+        if density > 0.8 {
+            rims.push(1);
+            rims.push(9);
+        }
+
+        rims.sort();
+        rims.dedup();
+        rims
+    }
+
+    // === NEW MAPPING METHODS ===
+
+    /// Temperature on a 0-1 scale, or its stand-in when there is no sensor
+    fn temperature_level(&self, metrics: &SystemMetrics) -> f32 {
+        let fallback = if metrics.temperature_available { TemperatureFallback::Fixed } else { self.temperature_fallback };
+        let level = match fallback {
+            TemperatureFallback::Fixed => (metrics.temperature - 30.0) / 40.0,
+            TemperatureFallback::Cpu => metrics.cpu_usage / 100.0,
+            TemperatureFallback::Memory => metrics.memory_usage / 100.0,
+            TemperatureFallback::Load => metrics.load_avg_1 / metrics.per_core_usage.len().max(1) as f32,
+        };
+        level.clamp(0.0, 1.0)
+    }
+
+    /// Move to the stress key past the enter thresholds, back home once both
+    /// metrics drop below the exit thresholds. Returns (current, previous) shift.
+    fn update_key_shift(&self, metrics: &SystemMetrics) -> (i32, i32) {
+        let previous = self.key_shift.load(Ordering::Relaxed);
+        let stressed = if previous == 0 {
+            metrics.cpu_usage > STRESS_CPU_ENTER || metrics.memory_usage > STRESS_MEMORY_ENTER
+        } else {
+            metrics.cpu_usage > STRESS_CPU_EXIT || metrics.memory_usage > STRESS_MEMORY_EXIT
+        };
+
+        let current = if stressed { STRESS_KEY_SHIFT } else { 0 };
+        self.key_shift.store(current, Ordering::Relaxed);
+        (current, previous)
+    }
+
+    /// The home context with its root moved up `key_shift` semitones
+    fn context_in_key(&self, key_shift: i32) -> MusicalContext {
+        let Some(root_index) = NOTE_NAMES.iter().position(|&n| n == self.context.root_note) else {
+            return self.context.clone();
+        };
+        let root = NOTE_NAMES[(root_index as i32 + key_shift).rem_euclid(12) as usize];
+
+        MusicalContext {
+            scale_name: self.context.scale_name.clone(),
+            root_note: root.to_string(),
+            key_signature: self.context.key_signature.replacen(&self.context.root_note, root, 1),
+        }
+    }
+
+    fn map_gpu_metrics(&self, metrics: &SystemMetrics) -> (Option<Vec<f32>>, f32, f32, f32, f32) {
+        // Check for NVIDIA GPU first, then AMD
+        let gpu_util = metrics.gpu_nvidia.as_ref().map(|g| g.utilization)
+            .or_else(|| metrics.gpu_amd.as_ref().map(|g| g.utilization))
+            .unwrap_or(0.0);
+
+        let gpu_temp = metrics.gpu_nvidia.as_ref().map(|g| g.temperature)
+            .or_else(|| metrics.gpu_amd.as_ref().map(|g| g.temperature))
+            .unwrap_or(45.0);
+
+        let gpu_mem_used = metrics.gpu_nvidia.as_ref().map(|g| g.memory_used)
+            .or_else(|| metrics.gpu_amd.as_ref().map(|g| g.memory_used))
+            .unwrap_or(0);
+
+        let gpu_mem_total = metrics.gpu_nvidia.as_ref().map(|g| g.memory_total)
+            .or_else(|| metrics.gpu_amd.as_ref().map(|g| g.memory_total))
+            .unwrap_or(1);
+
+        // If no GPU present, return None for notes
+        if gpu_util < 0.1 && gpu_mem_used == 0 {
+            return (None, 0.0, 0.0, 0.0, 0.0);
+        }
+
+        // GPU utilization → Dorian mode melody (for contrast with CPU's minor pentatonic)
+        let dorian_scale = vec![D4, E4, F4, G4, A4, B4, C5, D5, E5, F5];
+        let gpu_scale_index = scale_index(gpu_util / 100.0, dorian_scale.len());
+
+        let gpu_notes = vec![
+            dorian_scale[gpu_scale_index],
+            dorian_scale[(gpu_scale_index + 2).min(dorian_scale.len() - 1)],
+            dorian_scale[gpu_scale_index.saturating_sub(1)],
+            dorian_scale[gpu_scale_index],
+        ];
+
+        let gpu_intensity = (gpu_util / 100.0).clamp(0.0, 1.0);
+
+        // GPU temp → Chorus and flanger effects
+        let temp_norm = ((gpu_temp - 40.0) / 40.0).clamp(0.0, 1.0); // 40-80°C range
+        let gpu_chorus_depth = temp_norm * 0.3; // 0-30% chorus depth
+        let gpu_flanger_rate = 0.5 + (temp_norm * 2.5); // 0.5-3.0 Hz flanger
+
+        // GPU memory → Reverb room size
+        let vram_reverb_size = self.vram_curve.apply(gpu_mem_used as f32, gpu_mem_total as f32);
+
+        (Some(gpu_notes), gpu_intensity, gpu_chorus_depth, gpu_flanger_rate, vram_reverb_size)
+    }
+
+    fn map_gpu_clocks(&self, metrics: &SystemMetrics) -> (f32, f32) {
+        let clocks = metrics.gpu_nvidia.as_ref().and_then(|g| g.clocks)
+            .or_else(|| metrics.gpu_amd.as_ref().and_then(|g| g.clocks));
+
+        // No clocks reported → plain voice at the usual brightness
+        let Some(clocks) = clocks else {
+            return (0.0, 0.5);
+        };
+
+        // Core clock → Detune (boosted card sounds thicker, 0-15 cents)
+        let gpu_detune = clocks.core_ratio() * 15.0;
+
+        // Memory clock → Brightness (high-power memory state opens the filter)
+        let gpu_brightness = clocks.memory_ratio();
+
+        (gpu_detune, gpu_brightness)
+    }
+
+    fn map_gpu_power(&self, metrics: &SystemMetrics) -> f32 {
+        // NVIDIA reports 0.0 when power is unavailable, AMD reports None
+        let power_draw = metrics.gpu_nvidia.as_ref().map(|g| g.power_draw)
+            .or_else(|| metrics.gpu_amd.as_ref().and_then(|g| g.power_draw))
+            .unwrap_or(0.0);
+
+        // Absent GPU → no rumble
+        (power_draw / self.gpu_tdp_watts).clamp(0.0, 1.0)
+    }
+
+    fn map_load_average(&self, metrics: &SystemMetrics) -> (f32, usize) {
+        // Load average 1-min vs 15-min indicates load trend
+        // Higher 1-min relative to 15-min = increasing load = more polyrhythmic
+        let load_diff = metrics.load_avg_1 - metrics.load_avg_15;
+        let load_diff_norm = (load_diff / 4.0).clamp(0.0, 1.0); // Normalize to 0-1
+
+        let rhythm_polyrhythm_factor = load_diff_norm;
+
+        // Number of harmonic voices based on sustained load (5-min average)
+        let harmonic_voices = if metrics.load_avg_5 < 1.0 {
+            1 // Minimal load
+        } else if metrics.load_avg_5 < 3.0 {
+            2 // Moderate load
+        } else if metrics.load_avg_5 < 6.0 {
+            3 // High load
+        } else {
+            4 // Very high load
+        };
+
+        (rhythm_polyrhythm_factor, harmonic_voices)
+    }
+
+    fn map_swap_usage(&self, metrics: &SystemMetrics) -> f32 {
+        if metrics.swap_total == 0 {
+            return 0.0; // No swap configured
+        }
+
+        let swap_percent = (metrics.swap_used as f32 / metrics.swap_total as f32) * 100.0;
+
+        // Only a floor: swap that filled up hours ago shouldn't keep the bass
+        // crunchy, active paging (swap_activity) carries the real pressure
+        let swap_distortion = (swap_percent / 100.0) * 0.3; // 0-0.3
+
+        swap_distortion.clamp(0.0, 0.3)
+    }
+
+    fn map_battery(&self, metrics: &SystemMetrics) -> (f32, f32) {
+        let battery = match &metrics.battery {
+            Some(b) => b,
+            None => return (1.0, 0.0), // No battery = default volume, neutral tonality
+        };
+
+        // State of charge → Volume multiplier (0.5x at 0%, 1.0x at 100%)
+        let battery_volume_mult = 0.5 + (battery.state_of_charge / 100.0) * 0.5;
+
+        // Charging/discharging → Tonality bias
+        use crate::metrics::BatteryState;
+        let battery_tonality = match battery.state {
+            BatteryState::Charging => 0.3,      // Slightly major/brighter
+            BatteryState::Discharging => {
+                if battery.state_of_charge < 20.0 {
+                    -0.7 // Very minor/dark when low
+                } else {
+                    -0.3 // Slightly minor
+                }
+            }
+            BatteryState::Full => 0.5,           // Bright and happy
+            BatteryState::Empty => -1.0,         // Very dark
+            BatteryState::Unknown => 0.0,        // Neutral
+        };
+
+        (battery_volume_mult, battery_tonality)
+    }
+
+    fn map_battery_tremolo(&self, metrics: &SystemMetrics) -> f32 {
+        use crate::metrics::BatteryState;
+
+        // Only pulse while actually draining the battery
+        let battery = match &metrics.battery {
+            Some(b) if b.state == BatteryState::Discharging => b,
+            _ => return 0.0, // Charging, full, or on AC = tremolo off
+        };
+
+        // 0-30W discharge → 1-8 Hz tremolo (quickens under heavy draw)
+        let draw_norm = (battery.power_rate.abs() / 30.0).clamp(0.0, 1.0);
+        1.0 + draw_norm * 7.0
+    }
+
+    fn map_battery_warning(&self, metrics: &SystemMetrics) -> Option<f32> {
+        use crate::metrics::BatteryState;
+
+        // Never warn while charging or plugged in
+        let battery = metrics.battery.as_ref()
+            .filter(|b| b.state == BatteryState::Discharging)?;
+        let minutes_left = battery.time_to_empty?;
+
+        if self.battery_warning_minutes <= 0.0 || minutes_left >= self.battery_warning_minutes {
+            return None;
+        }
+
+        // 0.0 at the threshold, 1.0 when the battery is about to die
+        Some((1.0 - minutes_left / self.battery_warning_minutes).clamp(0.0, 1.0))
+    }
+
+    fn map_per_core_cpu(&self, per_core_usage: &[f32]) -> Vec<Vec<usize>> {
+        // Generate rhythmic pattern for each core voice
+        // Higher usage = denser pattern
+        let steps = self.grid.steps();
+        let beat = steps / 4;
+        group_core_usage(per_core_usage, self.core_voices, self.core_grouping).iter().enumerate().map(|(core_idx, &usage)| {
+            let usage_norm = usage / 100.0;
+
+            // Base pattern varies by core number (for variety)
+            let offset = core_idx % 4;
+
+            if usage_norm < 0.2 {
+                // Low usage: sparse hits
+                vec![offset * beat]
+            } else if usage_norm < 0.5 {
+                // Medium usage: quarter notes
+                vec![offset * beat, (offset * beat + 2 * beat) % steps]
+            } else if usage_norm < 0.8 {
+                // High usage: eighth notes
+                (0..4).map(|i| (offset * beat + i * beat) % steps).collect()
+            } else {
+                // Very high usage: every other grid step
+                (0..steps).filter(|i| i % 2 == offset % 2).collect()
+            }
+        }).collect()
+    }
+
+    fn apply_polyrhythm(&self, patterns: Vec<Vec<usize>>, factor: f32) -> Vec<Vec<usize>> {
+        // Low factor: keep the straightforward 4/4 patterns
+        if factor < 0.4 {
+            return patterns;
+        }
+
+        // Medium factor: odd cores switch to groups of 3 (3-against-4)
+        // High factor: every core takes a 3/5/7 grouping so the layers conflict
+        let groupings: &[usize] = if factor < 0.7 { &[3] } else { &[3, 5, 7] };
+
+        patterns.into_iter().enumerate().map(|(core_idx, pattern)| {
+            if pattern.is_empty() || (factor < 0.7 && core_idx % 2 == 0) {
+                return pattern;
+            }
+
+            let group = groupings[core_idx % groupings.len()];
+            let offset = pattern[0];
+            (offset..self.grid.steps()).step_by(group).collect()
+        }).collect()
+    }
+
+    fn map_process_count(&self, process_count: usize) -> f32 {
+        // Normalize process count to 0-1
+        // Assuming 50-300 processes is typical range
+        let count_norm = ((process_count as f32 - 50.0) / 250.0).clamp(0.0, 1.0);
+        count_norm
+    }
+
+    fn map_top_processes(&self, top_processes: &[crate::metrics::ProcessMetric]) -> Vec<(String, Vec<f32>)> {
+        // Generate mini-melody for each top process
+        let process_scale = vec![E5, G5, A5, B5, D6]; // Higher register for process melodies
+
+        top_processes.iter().map(|proc| {
+            // CPU % determines pitch
+            let scale_idx = scale_index(proc.cpu_usage / 100.0, process_scale.len());
+
+            // Memory determines octave: heavier processes sit lower and fuller,
+            // so a memory hog stays audible even when its CPU is near zero
+            let memory_gb = proc.memory_usage as f32 / 1_073_741_824.0;
+            let octave_mult = if memory_gb > 4.0 {
+                0.25 // Two octaves down
+            } else if memory_gb > 1.0 {
+                0.5 // One octave down
+            } else {
+                1.0
+            };
+
+            // Create simple 2-note melodic phrase
+            let melody = vec![
+                process_scale[scale_idx] * octave_mult,
+                process_scale[(scale_idx + 2).min(process_scale.len() - 1)] * octave_mult,
+            ];
+
+            (proc.name.clone(), melody)
+        }).collect()
+    }
+
+    fn map_fan_speeds(&self, metrics: &SystemMetrics) -> f32 {
+        // System fans via hwmon (Linux only)
+        let system_fan_norm = match &metrics.fan_speeds {
+            Some(fans) if !fans.is_empty() => {
+                // Average RPM across all fans
+                let avg_rpm = fans.iter().map(|f| f.rpm as f32).sum::<f32>() / fans.len() as f32;
+
+                // Typical fan range: 500-3000 RPM
+                // Map to 0-1
+                Some(((avg_rpm - 500.0) / 2500.0).clamp(0.0, 1.0))
+            }
+            _ => None,
+        };
+
+        // NVIDIA GPU fan via NVML (0-100%, available cross-platform)
+        let gpu_fan_norm = metrics.gpu_nvidia.as_ref()
+            .and_then(|g| g.fan_speed)
+            .map(|speed| (speed / 100.0).clamp(0.0, 1.0));
+
+        match (system_fan_norm, gpu_fan_norm) {
+            (Some(system), Some(gpu)) => (system + gpu) / 2.0,
+            (Some(system), None) => system,
+            (None, Some(gpu)) => gpu,
+            (None, None) => 0.0, // No fans detected
+        }
+    }
+
+    /// Each active mapping from `metrics` to the `params` mapped from them.
+    ///
+    /// The normalized values come from the same code path as `map`; the
+    /// resulting values are read back from `params`. GPU and battery entries
+    /// only appear when the machine reports them.
+    pub fn explain_mapping(&self, metrics: &SystemMetrics, params: &MusicalParams) -> Vec<MappingExplanation> {
+        let levels = self.levels(&with_usage_clamped(metrics));
+        let entry = |metric, raw, normalized, target, value| MappingExplanation { metric, raw, normalized, target, value };
+
+        let (temperature_metric, temperature_raw) = match self.temperature_fallback {
+            _ if metrics.temperature_available => ("temperature", metrics.temperature),
+            TemperatureFallback::Fixed => ("temperature", metrics.temperature),
+            TemperatureFallback::Cpu => ("cpu_usage", metrics.cpu_usage),
+            TemperatureFallback::Memory => ("memory_usage", metrics.memory_usage),
+            TemperatureFallback::Load => ("load_avg_1", metrics.load_avg_1),
+        };
+        let disk_total = (metrics.disk_read_bytes + metrics.disk_write_bytes) as f32;
+        let network_total = (metrics.network_rx_bytes + metrics.network_tx_bytes) as f32;
+
+        let mut explanation = vec![
+            entry("cpu_usage", metrics.cpu_usage, levels.cpu, "melody_notes", params.melody_notes.first().copied().unwrap_or(0.0)),
+            entry("memory_usage", metrics.memory_usage, (metrics.memory_usage / 100.0).clamp(0.0, 1.0), "bass_velocity", params.bass_velocity),
+            entry("disk_io", disk_total, levels.disk, "rhythm_density", params.rhythm_density),
+            entry("disk_io", disk_total, levels.disk, "melody_note_scale", params.melody_note_scale),
+            entry("disk_read", metrics.disk_read_bytes as f32, levels.disk_read, "read_density", params.read_density),
+            entry("disk_write", metrics.disk_write_bytes as f32, levels.disk_write, "write_density", params.write_density),
+            entry("network", network_total, levels.network, "tempo", params.tempo),
+            entry(temperature_metric, temperature_raw, levels.temperature, "filter_cutoff", params.filter_cutoff),
+            entry(temperature_metric, temperature_raw, levels.temperature, "reverb_mix", params.reverb_mix),
+            entry("connection_churn", metrics.connection_churn, levels.connections, "pluck_hits", params.pluck_hits.len() as f32),
+            entry("kernel_activity", (metrics.context_switches + metrics.interrupts) as f32, levels.kernel, "kernel_ticks", params.kernel_ticks),
+            entry("load_avg_1", metrics.load_avg_1, levels.load, "arp_rate", params.arp_rate),
+            entry("process_count", metrics.process_count as f32, params.hihat_density, "hihat_density", params.hihat_density),
+        ];
+
+        if params.gpu_notes.is_some() {
+            let gpu_util = metrics.gpu_nvidia.as_ref().map(|g| g.utilization)
+                .or_else(|| metrics.gpu_amd.as_ref().map(|g| g.utilization))
+                .unwrap_or(0.0);
+            explanation.push(entry("gpu_utilization", gpu_util, params.gpu_intensity, "gpu_intensity", params.gpu_intensity));
+        }
+        if params.gpu_power_rumble > 0.0 {
+            let power_draw = metrics.gpu_nvidia.as_ref().map(|g| g.power_draw)
+                .or_else(|| metrics.gpu_amd.as_ref().and_then(|g| g.power_draw))
+                .unwrap_or(0.0);
+            explanation.push(entry("gpu_power_draw", power_draw, params.gpu_power_rumble, "gpu_power_rumble", params.gpu_power_rumble));
+        }
+        if let Some(battery) = &metrics.battery {
+            let charge = (battery.state_of_charge / 100.0).clamp(0.0, 1.0);
+            explanation.push(entry("battery_charge", battery.state_of_charge, charge, "battery_volume_mult", params.battery_volume_mult));
+        }
+
+        explanation
+    }
+
+    /// Log the mapping table (at info level, so it is controlled by `RUST_LOG`)
+    pub fn print_mapping_info(&self, metrics: &SystemMetrics, params: &MusicalParams) {
+        log::info!("{}", self.format_mapping_info(metrics, params));
+    }
+
+    /// Render the mapping table as a human-readable string
+    pub fn format_mapping_info(&self, metrics: &SystemMetrics, params: &MusicalParams) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        let _ = writeln!(out, "\n=== System Metrics → Music Mapping ===");
+        let _ = writeln!(out, "Now playing:    {}", params.now_playing());
+        let _ = writeln!(out, "CPU Usage:      {:.1}% → Melody pitch (scale index)", metrics.cpu_usage);
+        let _ = writeln!(out, "Memory Usage:   {:.1}% → Bass intensity: {:.2}", metrics.memory_usage, params.bass_velocity);
+        let _ = writeln!(out, "Disk I/O:       {} KB/s → Rhythm density: {:.2}",
+            (metrics.disk_read_bytes + metrics.disk_write_bytes) / 1024,
+            params.rhythm_density);
+        let _ = writeln!(out, "                        → Melody note length: {:.2}x", params.melody_note_scale);
+        let _ = writeln!(out, "Disk R/W:       {} / {} KB/s → Toms: {:.2}, Rimshots: {:.2}",
+            metrics.disk_read_bytes / 1024,
+            metrics.disk_write_bytes / 1024,
+            params.read_density,
+            params.write_density);
+        let _ = writeln!(out, "Network:        {} KB/s → Tempo: {:.1} BPM",
+            (metrics.network_rx_bytes + metrics.network_tx_bytes) / 1024,
+            params.tempo);
+        let _ = writeln!(out, "Interfaces:     {}", metrics.network_interfaces.join(", "));
+        let _ = writeln!(out, "Kernel:         {} switches/s, {} irq/s → Ticks: {:.2}",
+            metrics.context_switches,
+            metrics.interrupts,
+            params.kernel_ticks);
+        let temperature = if metrics.temperature_available {
+            format!("{:.1}°C", metrics.temperature)
+        } else if self.temperature_fallback == TemperatureFallback::Fixed {
+            format!("no sensor (fixed {:.1}°C)", metrics.temperature)
+        } else {
+            format!("no sensor (using {})", self.temperature_fallback.name())
+        };
+        let _ = writeln!(out, "Temperature:    {} → Filter: {:.0}Hz, Reverb: {:.0}%",
+            temperature,
+            params.filter_cutoff,
+            params.reverb_mix * 100.0);
+        let _ = writeln!(out, "Kick hits:      {:?}", params.kick_hits);
+        let _ = writeln!(out, "Snare hits:     {:?}", params.snare_hits);
+        let _ = writeln!(out, "=====================================");
+        out
+    }
+}
+
+/// Nearest note of `scale` whose pitch class also belongs to the previous key
+/// (`scale` moved by `from_shift` semitones), so a key change pivots smoothly
+fn pivot_note(note: f32, scale: &[f32], from_shift: i32) -> f32 {
+    let previous_key: Vec<i32> = scale.iter()
+        .map(|&f| (pitch_class(f) + from_shift).rem_euclid(12))
+        .collect();
+
+    scale.iter()
+        .copied()
+        .filter(|&f| previous_key.contains(&pitch_class(f)))
+        .min_by(|a, b| (a / note).log2().abs().total_cmp(&(b / note).log2().abs()))
+        .unwrap_or(note)
+}
+
+/// Scale steps above the melody of each harmony line: a third, a fifth and
+/// a seventh in a seven-note scale
+const HARMONY_STEPS: [usize; 3] = [2, 4, 6];
+
+/// Up to three lines harmonizing the melody at `indices` of `scale`, each
+/// `HARMONY_STEPS` further up the scale. Steps past the top of the scale
+/// fall back an octave, so every harmony note stays in the scale.
+fn harmony_lines(scale: &[f32], indices: &[usize], voices: usize) -> Vec<Vec<f32>> {
+    let Some(&root) = scale.first() else {
+        return Vec::new();
+    };
+    // Notes per octave, e.g. 5 for a pentatonic scale
+    let octave = scale.iter().position(|&f| f >= root * 1.99).unwrap_or(scale.len()).max(1);
+
+    HARMONY_STEPS
+        .iter()
+        .take(voices)
+        .map(|&step| {
+            indices
+                .iter()
+                .map(|&index| {
+                    let (mut degree, mut ratio) = (index + step, 1.0);
+                    while degree >= scale.len() {
+                        degree -= octave;
+                        ratio *= 0.5;
+                    }
+                    scale[degree] * ratio
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Degrees (semitones above A) that `tonal_scale` moves, with how far the
+/// tonality must lean before each one flips: the third first, then the sixth,
+/// then the seventh
+const MAJOR_DEGREES: [(i32, i32, f32); 3] = [(3, 4, 0.2), (8, 9, 0.5), (10, 11, 0.8)];
+
+/// `scale` leaning major (`tonality` > 0) or minor (< 0), -1.0 to 1.0.
+///
+/// Each minor degree is raised to its major neighbour (or the reverse) once
+/// the tonality passes that degree's threshold, so the scale brightens one
+/// interval at a time rather than switching mode outright. Degrees whose
+/// neighbour is already in the scale stay put, keeping the notes distinct.
+fn tonal_scale(scale: &[f32], tonality: f32) -> Vec<f32> {
+    let classes: Vec<i32> = scale.iter().map(|&f| pitch_class(f)).collect();
+    let strength = tonality.clamp(-1.0, 1.0).abs();
+
+    scale
+        .iter()
+        .zip(&classes)
+        .map(|(&note, &class)| {
+            let moved = MAJOR_DEGREES
+                .iter()
+                .filter(|&&(.., threshold)| strength >= threshold)
+                .find_map(|&(minor, major, _)| {
+                    let (from, to) = if tonality > 0.0 { (minor, major) } else { (major, minor) };
+                    (class == from && !classes.contains(&to)).then_some(to - from)
+                });
+            match moved {
+                Some(semitones) => (note * 2f32.powf(semitones as f32 / 12.0) * 100.0).round() / 100.0,
+                None => note,
+            }
+        })
+        .collect()
+}
+
+/// Semitones above A of the chord `chord_tones` builds on: A minor seventh
+const CHORD_DEGREES: [i32; 4] = [0, 3, 7, 10];
+
+/// Root, third, fifth and seventh from A3, plus the octave, as `scale` has them.
+///
+/// A degree the scale lacks takes the scale's tone a semitone above or below,
+/// so the chord brightens and darkens with the melody (C# once the scale has
+/// leaned major). Degrees with neither neighbour in the scale keep the minor tone.
+fn chord_tones(scale: &[f32]) -> Vec<f32> {
+    let classes: Vec<i32> = scale.iter().map(|&f| pitch_class(f)).collect();
+    let mut tones: Vec<f32> = CHORD_DEGREES
+        .iter()
+        .map(|&degree| {
+            let semitones = [degree, degree + 1, degree - 1]
+                .into_iter()
+                .find(|semitones| classes.contains(&semitones.rem_euclid(12)))
+                .unwrap_or(degree);
+            (A3 * 2f32.powf(semitones as f32 / 12.0) * 100.0).round() / 100.0
+        })
+        .collect();
+    tones.push(A4);
+    tones
+}
+
+/// Semitones above A, 0-11, of the nearest equal-tempered note to `freq`
+fn pitch_class(freq: f32) -> i32 {
+    ((12.0 * (freq / A4).log2()).round() as i32).rem_euclid(12)
+}
+
+/// Scale indices of a 4-note melody shaped by `contour` around `base`.
+///
+/// Classic clamps each note into the scale, as it always has; the other
+/// contours shift as a whole so a run near the top or bottom keeps its shape.
+/// `rng` is xorshift state, advanced only by `RandomWalk`.
+fn contour_indices(contour: MelodyContour, base: usize, len: usize, rng: &mut u64) -> [usize; 4] {
+    let top = len as i32 - 1;
+    let offsets: [i32; 4] = match contour {
+        MelodyContour::Classic => {
+            return [0, -1, 2, 0].map(|offset| (base as i32 + offset).clamp(0, top) as usize);
+        }
+        MelodyContour::Ascending => [0, 1, 2, 3],
+        MelodyContour::Descending => [0, -1, -2, -3],
+        MelodyContour::Zigzag => [0, 2, -1, 1],
+        MelodyContour::RandomWalk => {
+            let mut offsets = [0; 4];
+            let mut position = 0;
+            for offset in offsets.iter_mut().skip(1) {
+                *rng ^= *rng << 13;
+                *rng ^= *rng >> 7;
+                *rng ^= *rng << 17;
+                position += [-2, -1, 1, 2][(*rng % 4) as usize];
+                *offset = position;
+            }
+            offsets
+        }
+    };
+
+    let lowest = base as i32 + offsets.iter().min().unwrap();
+    let highest = base as i32 + offsets.iter().max().unwrap();
+    let shift = if lowest < 0 { -lowest } else { (top - highest).min(0) };
+    offsets.map(|offset| (base as i32 + offset + shift).clamp(0, top) as usize)
+}
+
+/// Index into a `len`-note scale for `normalized` (0.0-1.0), in range for any input
+fn scale_index(normalized: f32, len: usize) -> usize {
+    let top = len.saturating_sub(1);
+    ((normalized.clamp(0.0, 1.0) * top as f32) as usize).min(top)
+}
+
+/// `metrics` with CPU and GPU usage percentages held to 0-100
+fn with_usage_clamped(metrics: &SystemMetrics) -> SystemMetrics {
+    let mut metrics = metrics.clone();
+    metrics.cpu_usage = metrics.cpu_usage.clamp(0.0, 100.0);
+    for core in &mut metrics.per_core_usage {
+        *core = core.clamp(0.0, 100.0);
+    }
+    if let Some(gpu) = metrics.gpu_nvidia.as_mut() {
+        gpu.utilization = gpu.utilization.clamp(0.0, 100.0);
+    }
+    if let Some(gpu) = metrics.gpu_amd.as_mut() {
+        gpu.utilization = gpu.utilization.clamp(0.0, 100.0);
+    }
+    metrics
+}
+
+/// `1.0 - x` when `inverted`
+fn invert(x: f32, inverted: bool) -> f32 {
+    if inverted { 1.0 - x } else { x }
+}
+
+/// Collapse per-core usage into at most `voices` values (see `with_core_voices`)
+fn group_core_usage(per_core_usage: &[f32], voices: usize, grouping: CoreGrouping) -> Vec<f32> {
+    if per_core_usage.len() <= voices {
+        return per_core_usage.to_vec();
+    }
+
+    match grouping {
+        CoreGrouping::Average => (0..voices)
+            .map(|group| {
+                // Contiguous, near-equal groups covering every core
+                let start = group * per_core_usage.len() / voices;
+                let end = (group + 1) * per_core_usage.len() / voices;
+                let cores = &per_core_usage[start..end];
+                cores.iter().sum::<f32>() / cores.len() as f32
+            })
+            .collect(),
+        CoreGrouping::Busiest => {
+            let mut usage = per_core_usage.to_vec();
+            usage.sort_by(|a, b| b.total_cmp(a));
+            usage.truncate(voices);
+            usage
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_curves_share_endpoints() {
+        for curve in [ResponseCurve::Linear, ResponseCurve::Logarithmic, ResponseCurve::Exponential] {
+            assert_eq!(curve.apply(0.0, 10.0), 0.0);
+            assert!((curve.apply(10.0, 10.0) - 1.0).abs() < 1e-5);
+            assert!((curve.apply(50.0, 10.0) - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_log_curve_lifts_low_activity() {
+        // 100 KB/s against a 10 MB/s ceiling
+        let linear = ResponseCurve::Linear.apply(100_000.0, 10_000_000.0);
+        let log = ResponseCurve::Logarithmic.apply(100_000.0, 10_000_000.0);
+        let exp = ResponseCurve::Exponential.apply(100_000.0, 10_000_000.0);
+
+        assert!(log > 0.3, "log curve should make 1% activity audible, got {}", log);
+        assert!(linear < 0.02);
+        assert!(exp < linear);
+    }
+
+    #[test]
+    fn test_slew_blends_continuous_params_only() {
+        let previous = MusicalParams { tempo: 90.0, reverb_mix: 0.0, kick_hits: vec![0], ..Default::default() };
+        let next = MusicalParams { tempo: 130.0, reverb_mix: 0.5, kick_hits: vec![0, 8], ..Default::default() };
+
+        let halfway = next.clone().slewed_from(&previous, 0.5);
+        assert_eq!(halfway.tempo, 110.0);
+        assert_eq!(halfway.reverb_mix, 0.25);
+        assert_eq!(halfway.kick_hits, vec![0, 8]);
+
+        assert_eq!(next.clone().slewed_from(&previous, 0.0).tempo, 90.0);
+        assert_eq!(next.slewed_from(&previous, 1.0).tempo, 130.0);
+    }
+
+    #[test]
+    fn test_gpu_clocks_drive_detune_and_brightness() {
+        use crate::metrics::{GpuClocks, NvidiaGpuMetrics};
+
+        let mapper = MetricsMapper::new();
+        let mut metrics = SystemMetrics::quiet();
+        metrics.gpu_nvidia = Some(NvidiaGpuMetrics {
+            name: None,
+            utilization: 50.0,
+            temperature: 60.0,
+            memory_used: 0,
+            memory_total: 1,
+            power_draw: 0.0,
+            fan_speed: None,
+            clocks: None,
+        });
+        assert_eq!(mapper.map_gpu_clocks(&metrics), (0.0, 0.5));
+
+        let clocks = GpuClocks { core_mhz: 1500.0, core_max_mhz: 2000.0, memory_mhz: 2500.0, memory_max_mhz: 10000.0 };
+        metrics.gpu_nvidia.as_mut().unwrap().clocks = Some(clocks);
+        assert_eq!(mapper.map_gpu_clocks(&metrics), (11.25, 0.25));
+    }
+
+    #[test]
+    fn test_disk_heavy_processes_get_the_io_timbre() {
+        use crate::metrics::ProcessMetric;
+
+        let process = |name: &str, disk_write: u64| ProcessMetric {
+            name: name.to_string(),
+            pid: 1,
+            cpu_usage: 5.0,
+            memory_usage: 0,
+            disk_read: 0,
+            disk_write,
+        };
+        let mut metrics = SystemMetrics::quiet();
+        metrics.top_processes = vec![process("rsync", 40_000_000), process("editor", 2_000)];
+
+        let params = MetricsMapper::new().map(&metrics);
+        assert_eq!(params.io_bound_processes, vec!["rsync".to_string()]);
+    }
+
+    #[test]
+    fn test_stress_modulates_with_hysteresis() {
+        let mapper = MetricsMapper::new();
+        let mut metrics = SystemMetrics::quiet();
+        metrics.memory_usage = 40.0;
+
+        metrics.cpu_usage = 50.0;
+        assert_eq!(mapper.map(&metrics).key_shift, 0);
+
+        // Crossing 80% lifts to E minor, pivoting on a tone A minor shares
+        metrics.cpu_usage = 90.0;
+        let stressed = mapper.map(&metrics);
+        assert_eq!(stressed.key_shift, 7);
+        assert_eq!(stressed.context.key_signature, "E minor");
+        let home_classes = [0, 3, 5, 7, 10]; // A C D E G
+        let class = pitch_class(stressed.melody_notes[0]);
+        assert!(home_classes.contains(&class));
+
+        // Stays up between the exit and enter thresholds, resolves below them
+        metrics.cpu_usage = 70.0;
+        assert_eq!(mapper.map(&metrics).key_shift, 7);
+        metrics.cpu_usage = 30.0;
+        assert_eq!(mapper.map(&metrics).key_shift, 0);
+    }
+
+    #[test]
+    fn test_swap_paging_outweighs_stale_usage() {
+        let mapper = MetricsMapper::new();
+        let mut metrics = SystemMetrics::quiet();
+        metrics.swap_total = 1_000;
+        metrics.swap_used = 1_000;
+
+        // Full but idle swap: only the subtle floor
+        let idle = mapper.map(&metrics);
+        assert_eq!(idle.swap_distortion, 0.3);
+        assert_eq!(idle.swap_activity, 0.0);
+
+        metrics.swap_out_bytes = 20_000_000;
+        assert_eq!(mapper.map(&metrics).swap_activity, 1.0);
+    }
+
+    #[test]
+    fn test_kernel_activity_drives_ticks() {
+        let mapper = MetricsMapper::new();
+        let mut metrics = SystemMetrics::quiet();
+        let calm = mapper.map(&metrics).kernel_ticks;
+        assert!(calm < 0.05);
+
+        metrics.context_switches = 150_000;
+        metrics.interrupts = 50_000;
+        assert_eq!(mapper.map(&metrics).kernel_ticks, 1.0);
+    }
+
+    #[test]
+    fn test_many_cores_reduce_to_core_voices() {
+        // 64 cores: one busy group of 16, the rest idle
+        let usage: Vec<f32> = (0..64).map(|i| if i < 16 { 100.0 } else { 0.0 }).collect();
+
+        let averaged = group_core_usage(&usage, 4, CoreGrouping::Average);
+        assert_eq!(averaged, vec![100.0, 0.0, 0.0, 0.0]);
+
+        let mut scattered = vec![0.0; 64];
+        scattered[40] = 90.0;
+        scattered[63] = 70.0;
+        let busiest = group_core_usage(&scattered, 3, CoreGrouping::Busiest);
+        assert_eq!(busiest, vec![90.0, 70.0, 0.0]);
+
+        // Fewer cores than voices: unchanged
+        assert_eq!(group_core_usage(&[10.0, 20.0], 4, CoreGrouping::Average), vec![10.0, 20.0]);
+
+        let mapper = MetricsMapper::new().with_core_voices(6, CoreGrouping::Busiest);
+        assert_eq!(mapper.map_per_core_cpu(&usage).len(), 6);
+    }
+
+    #[test]
+    fn test_disk_io_shortens_melody_notes() {
+        let mapper = MetricsMapper::new();
+        let mut metrics = SystemMetrics::quiet();
+        assert_eq!(mapper.map(&metrics).melody_note_scale, 1.5);
+
+        metrics.disk_read_bytes = 20_000_000;
+        assert_eq!(mapper.map(&metrics).melody_note_scale, 0.5);
+    }
+
+    #[test]
+    fn test_inverted_cpu_pitch_mirrors_scale_index() {
+        let normal = MetricsMapper::new();
+        let inverted = MetricsMapper::new()
+            .with_inversions(MappingInversions { cpu_pitch: true, ..Default::default() });
+        let mut metrics = SystemMetrics::quiet();
+        let len = Scale::default().notes().len();
+
+        // Inverted at the mirrored usage, the level and the scale index match the normal mapping
+        for (cpu, mirrored) in [(0.0, 100.0), (25.0, 75.0), (75.0, 25.0)] {
+            metrics.cpu_usage = cpu;
+            let level = normal.levels(&metrics).cpu;
+            metrics.cpu_usage = mirrored;
+            let mirrored_level = inverted.levels(&metrics).cpu;
+            assert!((mirrored_level - level).abs() < 1e-6);
+            assert_eq!(scale_index(mirrored_level, len), scale_index(level, len));
+        }
+        metrics.cpu_usage = 0.0;
+        assert_eq!(scale_index(inverted.levels(&metrics).cpu, len), len - 1);
+
+        // Inverted temperature: hot machine gets the cold filter
+        let cool = MetricsMapper::new().with_inversions(MappingInversions { temp_filter: true, ..Default::default() });
+        metrics.temperature = 70.0;
+        assert_eq!(cool.map(&metrics).filter_cutoff, 400.0);
+    }
+
+    #[test]
+    fn test_scales_select_melody_notes() {
+        for scale in Scale::ALL {
+            assert_eq!(Scale::from_name(scale.name()), Some(scale));
+            let notes = scale.notes();
+            assert_eq!((notes[0], *notes.last().unwrap()), (A3, D6), "{} keeps the range", scale.name());
+        }
+        assert_eq!(Scale::Major.notes().len(), 18);
+        assert_eq!(Scale::Chromatic.notes().len(), 30);
+        assert!(Scale::Minor.notes().contains(&C5) && !Scale::Major.notes().contains(&C5), "A major has C#");
+
+        let mut metrics = crate::metrics::SystemMetrics::quiet();
+        metrics.memory_usage = 50.0; // Stay out of the stress key change
+        let major = MetricsMapper::new().with_scale("major").map(&metrics);
+        assert_eq!(major.context.scale_name, "major");
+        assert_eq!(major.context.key_signature, "A major");
+        assert!(major.melody_notes.iter().all(|n| Scale::Major.notes().contains(n)));
+
+        let unknown = MetricsMapper::new().with_scale("lydian").map(&metrics);
+        assert_eq!(unknown.context.scale_name, "minor pentatonic");
+        assert_eq!(MetricsMapper::new().with_scale("blues").scale.len(), 15);
+    }
+
+    #[test]
+    fn test_harmony_stacks_in_scale() {
+        let scale = Scale::Major.notes();
+        let lines = harmony_lines(&scale, &[0, 2, 17], 3);
+        assert_eq!(lines.len(), 3);
+        // A3 harmonized: C#4 (third), E4 (fifth), G#4 (seventh)
+        assert_eq!([lines[0][0], lines[1][0], lines[2][0]], [scale[2], scale[4], scale[6]]);
+        // Past the top (D6), the harmony drops an octave but stays in A major
+        for line in &lines {
+            assert!(line.iter().all(|&n| Scale::Major.intervals().contains(&pitch_class(n))));
+        }
+        assert!(harmony_lines(&scale, &[0], 0).is_empty());
+
+        let mut metrics = crate::metrics::SystemMetrics::quiet();
+        metrics.memory_usage = 50.0; // Stay out of the stress key change
+        metrics.load_avg_5 = 7.0;
+        let params = MetricsMapper::new().map(&metrics);
+        assert_eq!(params.harmonic_voices, 4);
+        assert_eq!(params.harmony_notes.len(), 3);
+        assert!(params.harmony_notes.iter().all(|line| line.len() == params.melody_notes.len()));
+    }
+
+    #[test]
+    fn test_battery_tonality_shifts_the_scale() {
+        // Charging raises A minor pentatonic's C to C#; a low battery darkens A major's C# back to C
+        let has = |notes: Vec<f32>, freq: f32| notes.iter().any(|n| (n - freq).abs() < 0.05);
+        assert!(has(tonal_scale(&Scale::MinorPentatonic.notes(), 0.3), 277.18));
+        assert_eq!(tonal_scale(&Scale::MinorPentatonic.notes(), 0.1), Scale::MinorPentatonic.notes());
+        assert!(has(tonal_scale(&Scale::Major.notes(), -0.7), C5));
+        assert_eq!(tonal_scale(&Scale::Chromatic.notes(), 1.0), Scale::Chromatic.notes());
+
+        let melodies = |state: crate::metrics::BatteryState, charge: f32| -> Vec<f32> {
+            let mapper = MetricsMapper::new();
+            [10.0, 30.0, 50.0, 70.0]
+                .into_iter()
+                .flat_map(|cpu| {
+                    let mut metrics = crate::metrics::SystemMetrics::quiet();
+                    metrics.cpu_usage = cpu;
+                    metrics.memory_usage = 50.0; // Stay out of the stress key change
+                    metrics.battery = Some(crate::metrics::BatteryMetrics {
+                        state_of_charge: charge,
+                        state: state.clone(),
+                        power_rate: 0.0,
+                        temperature: None,
+                        time_to_full: None,
+                        time_to_empty: None,
+                    });
+                    mapper.map(&metrics).melody_notes
+                })
+                .collect()
+        };
+
+        let draining = melodies(crate::metrics::BatteryState::Discharging, 10.0);
+        let charging = melodies(crate::metrics::BatteryState::Charging, 60.0);
+        assert_ne!(draining, charging);
+    }
+
+    #[test]
+    fn test_chords_and_bass_follow_the_tonal_scale() {
+        assert_eq!(chord_tones(&Scale::MinorPentatonic.notes()), vec![A3, C4, E4, G4, A4]);
+        let near = |a: f32, b: f32| (a - b).abs() < 0.05;
+
+        // A major's C# and G# replace the minor third and seventh
+        let major = chord_tones(&Scale::Major.notes());
+        assert!(near(major[1], 277.18) && near(major[3], 415.30));
+        assert!(near(chord_tones(&tonal_scale(&Scale::MinorPentatonic.notes(), 0.3))[1], 277.18));
+
+        let mapper = MetricsMapper::new().with_scale("major");
+        let mut metrics = SystemMetrics::quiet();
+        let params = mapper.map(&metrics);
+        assert_eq!(params.chord_notes, major);
+        assert!(near(params.bass_note, E4 * 0.5));
+        metrics.memory_usage = 60.0;
+        assert!(near(mapper.map(&metrics).bass_note, 277.18 * 0.5), "mid bass is the major third");
+    }
+
+    #[test]
+    fn test_melody_contours_keep_their_shape_at_the_edges() {
+        let mut rng = 1;
+        assert_eq!(contour_indices(MelodyContour::Classic, 0, 13, &mut rng), [0, 0, 2, 0]);
+        assert_eq!(contour_indices(MelodyContour::Ascending, 5, 13, &mut rng), [5, 6, 7, 8]);
+        assert_eq!(contour_indices(MelodyContour::Ascending, 12, 13, &mut rng), [9, 10, 11, 12]);
+        assert_eq!(contour_indices(MelodyContour::Descending, 1, 13, &mut rng), [3, 2, 1, 0]);
+        assert_eq!(contour_indices(MelodyContour::Zigzag, 6, 13, &mut rng), [6, 8, 5, 7]);
+        assert_eq!(rng, 1, "only the random walk draws numbers");
+
+        // Same seed, same walks; every step moves one or two degrees
+        let (mut a, mut b) = (42, 42);
+        for _ in 0..20 {
+            let walk = contour_indices(MelodyContour::RandomWalk, 12, 13, &mut a);
+            assert_eq!(walk, contour_indices(MelodyContour::RandomWalk, 12, 13, &mut b));
+            assert!(walk.iter().all(|&i| i < 13));
+            assert!(walk.windows(2).all(|pair| (1..=2).contains(&pair[0].abs_diff(pair[1]))));
+        }
+
+        for contour in MelodyContour::ALL {
+            assert_eq!(MelodyContour::from_name(contour.name()), Some(contour));
+        }
+    }
+
+    #[test]
+    fn test_grid_resolution_keeps_kicks_on_the_beat() {
+        let quiet = SystemMetrics::quiet();
+        let expected: [(GridResolution, [usize; 4]); 4] = [
+            (GridResolution::Sixteenth, [0, 4, 8, 12]),
+            (GridResolution::ThirtySecond, [0, 8, 16, 24]),
+            (GridResolution::EighthTriplet, [0, 3, 6, 9]),
+            (GridResolution::SixteenthTriplet, [0, 6, 12, 18]),
+        ];
+        for (grid, kicks) in expected {
+            let params = MetricsMapper::new().with_grid_resolution(grid).map(&quiet);
+            assert_eq!(params.kick_hits, kicks);
+            assert_eq!(params.grid, grid);
+            assert!(params.core_patterns.iter().flatten().all(|&i| i < grid.steps()));
+            assert_eq!(GridResolution::from_name(grid.name()), Some(grid));
+        }
+
+        // Saturated disk on the 32-step grid: a snare roll across the last beat
+        let mut busy = quiet.clone();
+        busy.disk_read_bytes = 50_000_000;
+        busy.disk_write_bytes = 50_000_000;
+        let params = MetricsMapper::new().with_grid_resolution(GridResolution::ThirtySecond).map(&busy);
+        assert!((24..32).all(|i| params.snare_hits.contains(&i)));
+        let params = MetricsMapper::new().map(&busy);
+        assert_eq!(params.snare_hits, vec![4, 6, 12, 14]);
+    }
+
+    #[test]
+    fn test_overshooting_usage_maps_to_the_top_of_the_scale() {
+        use crate::metrics::{NvidiaGpuMetrics, ProcessMetric};
+
+        let busy = |usage: f32| {
+            let mut metrics = SystemMetrics::quiet();
+            metrics.cpu_usage = usage;
+            metrics.per_core_usage = vec![usage, -5.0, 100.0, 50.0];
+            metrics.gpu_nvidia = Some(NvidiaGpuMetrics {
+                name: None,
+                utilization: usage,
+                temperature: 60.0,
+                memory_used: 1,
+                memory_total: 1,
+                power_draw: 0.0,
+                fan_speed: None,
+                clocks: None,
+            });
+            metrics.top_processes = vec![ProcessMetric {
+                name: "spinner".to_string(),
+                pid: 1,
+                cpu_usage: usage * 4.0, // A process spread over four cores
+                memory_usage: 0,
+                disk_read: 0,
+                disk_write: 0,
+            }];
+            metrics
+        };
+
+        for contour in MelodyContour::ALL {
+            MetricsMapper::new().with_melody_contour(contour, 7).map(&busy(150.0));
+        }
+
+        // 150% plays exactly like 100%
+        let over = MetricsMapper::new().map(&busy(150.0));
+        let full = MetricsMapper::new().map(&busy(100.0));
+        assert_eq!(over.melody_notes, full.melody_notes);
+        assert_eq!(over.gpu_notes, full.gpu_notes);
+        assert_eq!(over.gpu_intensity, 1.0);
+        assert_eq!(over.core_patterns, full.core_patterns);
+        assert_eq!(over.process_melodies, full.process_melodies);
+
+        assert_eq!(scale_index(1.5, 13), 12);
+        assert_eq!(scale_index(-1.0, 13), 0);
+        assert_eq!(scale_index(f32::NAN, 13), 0);
+    }
+
+    #[test]
+    fn test_missing_temperature_sensor_can_follow_another_metric() {
+        let mut metrics = SystemMetrics::quiet();
+        metrics.cpu_usage = 70.0;
+        metrics.temperature = 45.0;
+
+        // With a sensor, the fallback choice doesn't matter
+        let cpu_fallback = MetricsMapper::new().with_temperature_fallback(TemperatureFallback::Cpu);
+        assert_eq!(cpu_fallback.map(&metrics).filter_cutoff, 1375.0);
+
+        // Without one, the fixed fallback pins the filter and CPU can stand in
+        metrics.temperature_available = false;
+        assert_eq!(MetricsMapper::new().map(&metrics).filter_cutoff, 1375.0);
+        assert!((cpu_fallback.map(&metrics).filter_cutoff - 2220.0).abs() < 0.1);
+        assert!(cpu_fallback.format_mapping_info(&metrics, &cpu_fallback.map(&metrics)).contains("no sensor"));
+
+        for fallback in TemperatureFallback::ALL {
+            assert_eq!(TemperatureFallback::from_name(fallback.name()), Some(fallback));
+        }
+    }
+
+    #[test]
+    fn test_explanation_matches_the_mapped_params() {
+        let mut metrics = SystemMetrics::quiet();
+        metrics.temperature = 62.0;
+        metrics.network_rx_bytes = 5_000_000;
+
+        let mapper = MetricsMapper::new();
+        let params = mapper.map(&metrics);
+        let explanation = mapper.explain_mapping(&metrics, &params);
+        let find = |target| explanation.iter().find(|e| e.target == target).unwrap();
+
+        let filter = find("filter_cutoff");
+        assert_eq!(filter.metric, "temperature");
+        assert_eq!(filter.raw, 62.0);
+        assert!((filter.normalized - 0.8).abs() < 1e-6);
+        assert_eq!(filter.value, params.filter_cutoff);
+
+        let tempo = find("tempo");
+        assert_eq!(tempo.normalized, 1.0);
+        assert_eq!(tempo.value, params.tempo);
+
+        // Quiet machine: no GPU or battery to explain
+        assert!(explanation.iter().all(|e| !e.metric.starts_with("gpu") && e.metric != "battery_charge"));
+    }
+}
@@ -2,28 +2,33 @@ use super::system::{BatteryMetrics, BatteryState};
 use battery::{Manager, State};
 use std::sync::OnceLock;
 
-// Global battery manager (initialized once)
-static BATTERY_MANAGER: OnceLock<Option<Manager>> = OnceLock::new();
+// Global battery manager (initialized once, or the reason batteries can't be read)
+static BATTERY_MANAGER: OnceLock<Result<Manager, String>> = OnceLock::new();
 
 /// Initialize battery manager (called once)
-fn init_battery_manager() -> Option<Manager> {
+fn init_battery_manager() -> Result<Manager, String> {
     match Manager::new() {
         Ok(manager) => {
-            println!("✅ Battery monitoring initialized");
-            Some(manager)
+            log::info!("Battery monitoring initialized");
+            Ok(manager)
         }
         Err(e) => {
-            eprintln!("ℹ️  Battery not available: {} (skipping battery metrics)", e);
-            None
+            log::info!("Battery not available: {} (skipping battery metrics)", e);
+            Err(e.to_string())
         }
     }
 }
 
+/// Why the battery manager failed to initialize, or `None` if it's up
+pub fn battery_init_error() -> Option<String> {
+    BATTERY_MANAGER.get_or_init(init_battery_manager).as_ref().err().cloned()
+}
+
 /// Collect battery metrics
 pub fn collect_battery_metrics() -> Option<BatteryMetrics> {
     // Initialize battery manager once
     let manager = BATTERY_MANAGER.get_or_init(init_battery_manager);
-    let manager = manager.as_ref()?;
+    let manager = manager.as_ref().ok()?;
 
     // Get first battery
     let batteries = manager.batteries().ok()?;
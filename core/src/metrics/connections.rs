@@ -0,0 +1,32 @@
+// Connection counting is Linux-only (reads /proc/net/tcp and tcp6)
+#[cfg(target_os = "linux")]
+pub fn collect_connection_count() -> Option<usize> {
+    // State column value for ESTABLISHED (see include/net/tcp_states.h)
+    const TCP_ESTABLISHED: &str = "01";
+
+    let mut count = 0;
+    let mut readable = false;
+
+    for path in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let Ok(table) = std::fs::read_to_string(path) else {
+            continue; // e.g. IPv6 disabled
+        };
+        readable = true;
+
+        // Skip the header; the connection state is the fourth column
+        count += table
+            .lines()
+            .skip(1)
+            .filter(|line| line.split_whitespace().nth(3) == Some(TCP_ESTABLISHED))
+            .count();
+    }
+
+    readable.then_some(count)
+}
+
+// Stub for non-Linux platforms
+#[cfg(not(target_os = "linux"))]
+pub fn collect_connection_count() -> Option<usize> {
+    // Connection counting not supported on this platform
+    None
+}
@@ -0,0 +1,135 @@
+use super::battery::{battery_init_error, collect_battery_metrics};
+use super::fans::collect_fan_metrics;
+use super::gpu_amd::{amd_init_error, collect_amd_metrics};
+use super::gpu_nvidia::{collect_nvidia_metrics, nvidia_init_error};
+use super::temperature::sensor_readings;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use sysinfo::Components;
+
+/// Optional hardware whose sensors may or may not be present
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Nvidia,
+    Amd,
+    Battery,
+    Fans,
+    Temperature,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 5] =
+        [Subsystem::Nvidia, Subsystem::Amd, Subsystem::Battery, Subsystem::Fans, Subsystem::Temperature];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "nvidia" => Some(Self::Nvidia),
+            "amd" => Some(Self::Amd),
+            "battery" => Some(Self::Battery),
+            "fan" | "fans" => Some(Self::Fans),
+            "temperature" | "temp" => Some(Self::Temperature),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Subsystem::Nvidia => "NVIDIA",
+            Subsystem::Amd => "AMD",
+            Subsystem::Battery => "Battery",
+            Subsystem::Fans => "Fans",
+            Subsystem::Temperature => "Temperature",
+        }
+    }
+}
+
+/// Outcome of probing one subsystem, e.g. "NVIDIA: OK, 23% util, 61°C"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsystemReport {
+    pub subsystem: String, // Display label ("NVIDIA", "Battery", ...)
+    pub available: bool,
+    pub detail: String,    // Raw readings when available, otherwise the reason
+}
+
+impl fmt::Display for SubsystemReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.available {
+            write!(f, "{}: OK, {}", self.subsystem, self.detail)
+        } else {
+            write!(f, "{}: not available ({})", self.subsystem, self.detail)
+        }
+    }
+}
+
+/// Initialize `subsystem` if needed and take one reading
+pub fn diagnose(subsystem: Subsystem) -> SubsystemReport {
+    let reading = match subsystem {
+        Subsystem::Nvidia => collect_nvidia_metrics()
+            .map(|gpu| {
+                format!(
+                    "{}, {:.0}% util, {:.0}°C, {:.0}W",
+                    gpu.name.as_deref().unwrap_or("unknown model"),
+                    gpu.utilization,
+                    gpu.temperature,
+                    gpu.power_draw
+                )
+            })
+            .ok_or_else(|| nvidia_init_error().unwrap_or_else(|| "no device at index 0".to_string())),
+        Subsystem::Amd => collect_amd_metrics()
+            .map(|gpu| {
+                format!(
+                    "{}, {:.0}% util, {:.0}°C",
+                    gpu.name.as_deref().unwrap_or("unknown model"),
+                    gpu.utilization,
+                    gpu.temperature
+                )
+            })
+            .ok_or_else(|| amd_init_error().unwrap_or_else(|| "device stopped reporting".to_string())),
+        Subsystem::Battery => collect_battery_metrics()
+            .map(|battery| format!("{:.0}% ({:?}, {:.1}W)", battery.state_of_charge, battery.state, battery.power_rate))
+            .ok_or_else(|| battery_init_error().unwrap_or_else(|| "no battery found".to_string())),
+        Subsystem::Fans => collect_fan_metrics()
+            .map(|fans| {
+                fans.iter()
+                    .map(|fan| format!("{} {} RPM", fan.label, fan.rpm))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .ok_or_else(|| "no readable fan sensors".to_string()),
+        // Labels are what `temperature_sensor` matches against
+        Subsystem::Temperature => {
+            let readings = sensor_readings(&Components::new_with_refreshed_list());
+            if readings.is_empty() {
+                Err("no readable temperature sensors".to_string())
+            } else {
+                Ok(readings
+                    .iter()
+                    .map(|(label, celsius)| format!("{} {:.0}°C", label, celsius))
+                    .collect::<Vec<_>>()
+                    .join(", "))
+            }
+        }
+    };
+
+    let (available, detail) = match reading {
+        Ok(detail) => (true, detail),
+        Err(reason) => (false, reason),
+    };
+    SubsystemReport { subsystem: subsystem.label().to_string(), available, detail }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_formats_both_outcomes() {
+        let ok = SubsystemReport { subsystem: "NVIDIA".to_string(), available: true, detail: "23% util".to_string() };
+        assert_eq!(ok.to_string(), "NVIDIA: OK, 23% util");
+
+        let missing = diagnose(Subsystem::from_name("Fans").unwrap());
+        if !missing.available {
+            assert!(missing.to_string().starts_with("Fans: not available"));
+        }
+    }
+}
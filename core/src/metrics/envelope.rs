@@ -0,0 +1,111 @@
+use super::system::{ema, SystemMetrics};
+use serde::{Deserialize, Serialize};
+
+/// How fast a metric follows rises (attack) and falls (release), in seconds.
+///
+/// Unlike averaging, the two directions differ: a slow attack eases into a
+/// spike, a slow release lets a burst decay gradually. 0 follows instantly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvelopeTimes {
+    pub attack_secs: f32,
+    pub release_secs: f32,
+}
+
+impl EnvelopeTimes {
+    /// Move `previous` toward `value` over `elapsed` seconds
+    fn follow(&self, previous: f32, value: f32, elapsed: f32) -> f32 {
+        let tau = if value > previous { self.attack_secs } else { self.release_secs };
+        ema(previous, value, elapsed, tau)
+    }
+
+    fn follow_rate(&self, previous: u64, value: u64, elapsed: f32) -> u64 {
+        self.follow(previous as f32, value as f32, elapsed).round() as u64
+    }
+}
+
+/// Per-metric envelopes applied before mapping (all instant by default)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvelopeSettings {
+    pub cpu: EnvelopeTimes,     // Overall and per-core usage
+    pub memory: EnvelopeTimes,
+    pub disk: EnvelopeTimes,    // Read and write rates
+    pub network: EnvelopeTimes, // Receive and transmit rates
+    pub gpu: EnvelopeTimes,     // Utilization
+}
+
+/// Envelope-follows successive samples so spikes lead in and decay out musically
+pub struct EnvelopeFollower {
+    settings: EnvelopeSettings,
+    previous: Option<SystemMetrics>, // Last followed output
+}
+
+impl EnvelopeFollower {
+    pub fn new(settings: EnvelopeSettings) -> Self {
+        Self { settings, previous: None }
+    }
+
+    /// `metrics` with each enveloped value moved from the last output toward it.
+    ///
+    /// Elapsed time comes from the sample timestamps, so the envelopes run in
+    /// real time however irregularly samples arrive.
+    pub fn follow(&mut self, metrics: &SystemMetrics) -> SystemMetrics {
+        let mut followed = metrics.clone();
+        if let Some(prev) = &self.previous {
+            let elapsed = metrics.timestamp.saturating_duration_since(prev.timestamp).as_secs_f32();
+            let s = &self.settings;
+
+            followed.cpu_usage = s.cpu.follow(prev.cpu_usage, metrics.cpu_usage, elapsed);
+            if prev.per_core_usage.len() == metrics.per_core_usage.len() {
+                for (core, &last) in followed.per_core_usage.iter_mut().zip(&prev.per_core_usage) {
+                    *core = s.cpu.follow(last, *core, elapsed);
+                }
+            }
+            followed.memory_usage = s.memory.follow(prev.memory_usage, metrics.memory_usage, elapsed);
+            followed.disk_read_bytes = s.disk.follow_rate(prev.disk_read_bytes, metrics.disk_read_bytes, elapsed);
+            followed.disk_write_bytes = s.disk.follow_rate(prev.disk_write_bytes, metrics.disk_write_bytes, elapsed);
+            followed.network_rx_bytes = s.network.follow_rate(prev.network_rx_bytes, metrics.network_rx_bytes, elapsed);
+            followed.network_tx_bytes = s.network.follow_rate(prev.network_tx_bytes, metrics.network_tx_bytes, elapsed);
+            if let (Some(gpu), Some(last)) = (followed.gpu_nvidia.as_mut(), prev.gpu_nvidia.as_ref()) {
+                gpu.utilization = s.gpu.follow(last.utilization, gpu.utilization, elapsed);
+            }
+            if let (Some(gpu), Some(last)) = (followed.gpu_amd.as_mut(), prev.gpu_amd.as_ref()) {
+                gpu.utilization = s.gpu.follow(last.utilization, gpu.utilization, elapsed);
+            }
+        }
+
+        self.previous = Some(followed.clone());
+        followed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_slow_attack_eases_into_spike_and_fast_release_drops() {
+        let cpu = EnvelopeTimes { attack_secs: 10.0, release_secs: 0.0 };
+        let mut follower = EnvelopeFollower::new(EnvelopeSettings { cpu, ..Default::default() });
+
+        let mut metrics = SystemMetrics::quiet();
+        metrics.cpu_usage = 10.0;
+        metrics.memory_usage = 40.0;
+        assert_eq!(follower.follow(&metrics).cpu_usage, 10.0);
+
+        // One time constant later a spike has covered ~63% of the jump
+        metrics.timestamp += Duration::from_secs(10);
+        metrics.cpu_usage = 100.0;
+        metrics.memory_usage = 90.0;
+        let spiked = follower.follow(&metrics);
+        assert!((spiked.cpu_usage - 66.9).abs() < 0.1);
+        assert_eq!(spiked.memory_usage, 90.0, "memory has no envelope");
+
+        // Instant release: back down as soon as the spike ends
+        metrics.timestamp += Duration::from_secs(1);
+        metrics.cpu_usage = 5.0;
+        assert_eq!(follower.follow(&metrics).cpu_usage, 5.0);
+    }
+}
@@ -0,0 +1,87 @@
+use super::system::FanMetric;
+
+// Fan monitoring is Linux-only (uses sysfs hwmon interface)
+#[cfg(target_os = "linux")]
+pub fn collect_fan_metrics() -> Option<Vec<FanMetric>> {
+    use libmedium::sensors::Input;
+
+    // Collect fan readings (empty when no fan sensors were found)
+    let sensors = FAN_SENSORS.get_or_init(init_fan_sensors);
+    let mut fan_metrics = Vec::new();
+
+    for sensor in sensors {
+        if let Input::FanInput(fan_input) = sensor.input() {
+            if let Ok(reading) = fan_input.read() {
+                fan_metrics.push(FanMetric {
+                    label: sensor.label().unwrap_or("Unknown").to_string(),
+                    rpm: reading as u32,
+                });
+            }
+        }
+    }
+
+    if fan_metrics.is_empty() {
+        None
+    } else {
+        Some(fan_metrics)
+    }
+}
+
+// Fan sensors, found once on first use and shared by every thread
+#[cfg(target_os = "linux")]
+static FAN_SENSORS: std::sync::OnceLock<Vec<libmedium::sensors::Sensor>> = std::sync::OnceLock::new();
+
+/// Find the fan sensors (called once)
+#[cfg(target_os = "linux")]
+fn init_fan_sensors() -> Vec<libmedium::sensors::Sensor> {
+    use libmedium::sensors::Input;
+
+    match libmedium::parse_hwmons() {
+        Ok(hwmons) => {
+            let mut fans = Vec::new();
+            for hwmon in hwmons {
+                for sensor in hwmon.sensors() {
+                    // Only collect fan sensors
+                    if matches!(sensor.input(), Input::FanInput(_)) {
+                        fans.push(sensor.clone());
+                    }
+                }
+            }
+
+            if fans.is_empty() {
+                log::info!("No fan sensors found (skipping fan metrics)");
+            } else {
+                log::info!("Fan monitoring initialized ({} fans found)", fans.len());
+            }
+            fans
+        }
+        Err(e) => {
+            log::info!("Fan monitoring not available: {} (skipping fan metrics)", e);
+            Vec::new()
+        }
+    }
+}
+
+// Stub for non-Linux platforms
+#[cfg(not(target_os = "linux"))]
+pub fn collect_fan_metrics() -> Option<Vec<FanMetric>> {
+    // Fan monitoring not supported on this platform
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_collection_is_consistent() {
+        // The second call reuses the sensors found by the first
+        let first = collect_fan_metrics();
+        let second = collect_fan_metrics();
+        assert_eq!(first.is_some(), second.is_some());
+        if let (Some(first), Some(second)) = (first, second) {
+            let labels = |fans: &[FanMetric]| fans.iter().map(|f| f.label.clone()).collect::<Vec<_>>();
+            assert_eq!(labels(&first), labels(&second));
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use super::system::{AmdGpuMetrics, GpuClocks};
+use libamdgpu_top::{AMDGPU, DevicePath};
+use std::sync::OnceLock;
+
+// Global AMD GPU device handle (initialized once, or the reason the AMD GPU is unavailable)
+static AMD_DEVICE: OnceLock<Result<AMDGPU, String>> = OnceLock::new();
+
+/// Initialize AMD GPU device (called once)
+fn init_amd_gpu() -> Result<AMDGPU, String> {
+    // Try to find first AMD GPU device
+    match DevicePath::init_amdgpu_top() {
+        Ok(device_paths) => {
+            if let Some(path) = device_paths.first() {
+                match AMDGPU::new(path.clone()) {
+                    Ok(device) => {
+                        log::info!("AMD GPU detected and initialized");
+                        Ok(device)
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to initialize AMD GPU: {} (skipping AMD metrics)", e);
+                        Err(e.to_string())
+                    }
+                }
+            } else {
+                log::info!("No AMD GPU devices found (skipping AMD metrics)");
+                Err("no AMD GPU devices found".to_string())
+            }
+        }
+        Err(e) => {
+            log::info!("AMD GPU not available: {} (skipping AMD metrics)", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Why the AMD GPU failed to initialize, or `None` if it's up
+pub fn amd_init_error() -> Option<String> {
+    AMD_DEVICE.get_or_init(init_amd_gpu).as_ref().err().cloned()
+}
+
+/// Collect AMD GPU metrics
+pub fn collect_amd_metrics() -> Option<AmdGpuMetrics> {
+    // Initialize AMD GPU once
+    let device = AMD_DEVICE.get_or_init(init_amd_gpu);
+    let mut device = device.as_ref().ok()?.clone();
+
+    // Update device stats
+    if let Err(_) = device.update() {
+        return None;
+    }
+
+    // Marketing name (e.g. "AMD Radeon RX 7800 XT")
+    let name = device.get_marketing_name();
+
+    // GPU utilization
+    let utilization = device.get_gfx_usage()
+        .map(|u| u as f32)
+        .unwrap_or(0.0);
+
+    // Temperature
+    let temperature = device.get_temp()
+        .map(|t| t as f32)
+        .unwrap_or(45.0);
+
+    // Memory usage
+    let vram_info = device.get_vram_usage();
+    let memory_used = vram_info.0.vram_usage;
+    let memory_total = vram_info.0.vram_size;
+
+    // Power draw (if available)
+    let power_draw = device.get_power_average()
+        .map(|p| p as f32);
+
+    // Shader (core) and memory clocks (if available)
+    let clocks = read_clocks(&device);
+
+    Some(AmdGpuMetrics {
+        name,
+        utilization,
+        temperature,
+        memory_used,
+        memory_total,
+        power_draw,
+        clocks,
+    })
+}
+
+/// Shader and memory clocks, or None if any of them is unavailable
+fn read_clocks(device: &AMDGPU) -> Option<GpuClocks> {
+    Some(GpuClocks {
+        core_mhz: device.get_gfx_clock()? as f32,
+        core_max_mhz: device.get_max_gfx_clock()? as f32,
+        memory_mhz: device.get_memory_clock()? as f32,
+        memory_max_mhz: device.get_max_memory_clock()? as f32,
+    })
+}
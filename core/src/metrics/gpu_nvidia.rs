@@ -1,31 +1,37 @@
-use super::system::NvidiaGpuMetrics;
-use nvml_wrapper::Nvml;
+use super::system::{GpuClocks, NvidiaGpuMetrics};
+use nvml_wrapper::enum_wrappers::device::Clock;
+use nvml_wrapper::{Device, Nvml};
 use std::sync::OnceLock;
 
-// Global NVML instance (initialized once, or None if NVIDIA GPU unavailable)
-static NVML_INSTANCE: OnceLock<Option<Nvml>> = OnceLock::new();
+// Global NVML instance (initialized once, or the reason the NVIDIA GPU is unavailable)
+static NVML_INSTANCE: OnceLock<Result<Nvml, String>> = OnceLock::new();
 
 /// Initialize NVML library (called once)
-fn init_nvml() -> Option<Nvml> {
+fn init_nvml() -> Result<Nvml, String> {
     match Nvml::init() {
         Ok(nvml) => {
-            println!("✅ NVIDIA GPU detected and initialized");
-            Some(nvml)
+            log::info!("NVIDIA GPU detected and initialized");
+            Ok(nvml)
         }
         Err(e) => {
             // Gracefully handle absence of NVIDIA GPU/drivers
-            eprintln!("ℹ️  NVIDIA GPU not available: {} (skipping NVIDIA metrics)", e);
-            None
+            log::info!("NVIDIA GPU not available: {} (skipping NVIDIA metrics)", e);
+            Err(e.to_string())
         }
     }
 }
 
+/// Why NVML failed to initialize, or `None` if it's up
+pub fn nvidia_init_error() -> Option<String> {
+    NVML_INSTANCE.get_or_init(init_nvml).as_ref().err().cloned()
+}
+
 /// Collect NVIDIA GPU metrics
 pub fn collect_nvidia_metrics() -> Option<NvidiaGpuMetrics> {
     // Initialize NVML once
     let nvml = NVML_INSTANCE.get_or_init(init_nvml);
 
-    let nvml = nvml.as_ref()?;
+    let nvml = nvml.as_ref().ok()?;
 
     // Get first device (device 0)
     // TODO: Support multiple GPUs in the future
@@ -35,6 +41,8 @@ pub fn collect_nvidia_metrics() -> Option<NvidiaGpuMetrics> {
     };
 
     // Collect metrics (handle errors gracefully)
+    let name = device.name().ok();
+
     let utilization = device.utilization_rates()
         .ok()
         .map(|u| u.gpu as f32)
@@ -58,12 +66,27 @@ pub fn collect_nvidia_metrics() -> Option<NvidiaGpuMetrics> {
         .ok()
         .map(|f| f as f32);
 
+    // Clocks are optional: some boards/drivers don't report them
+    let clocks = read_clocks(&device);
+
     Some(NvidiaGpuMetrics {
+        name,
         utilization,
         temperature,
         memory_used,
         memory_total,
         power_draw,
         fan_speed,
+        clocks,
+    })
+}
+
+/// Core and memory clocks, or None if any of them is unavailable
+fn read_clocks(device: &Device) -> Option<GpuClocks> {
+    Some(GpuClocks {
+        core_mhz: device.clock_info(Clock::Graphics).ok()? as f32,
+        core_max_mhz: device.max_clock_info(Clock::Graphics).ok()? as f32,
+        memory_mhz: device.clock_info(Clock::Memory).ok()? as f32,
+        memory_max_mhz: device.max_clock_info(Clock::Memory).ok()? as f32,
     })
 }
@@ -0,0 +1,58 @@
+/// Interface name prefixes excluded from network rates by default:
+/// loopback, container bridges/veths, VPN tunnels and VM host adapters
+pub const DEFAULT_DENIED_INTERFACES: [&str; 9] =
+    ["lo", "docker", "veth", "br-", "virbr", "tun", "tap", "vmnet", "vboxnet"];
+
+/// Which network interfaces count toward the network rates, by name prefix.
+///
+/// An empty allow list means every interface not denied. Deny wins over allow,
+/// so `allow = ["en"]` with the defaults still skips loopback.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceFilter {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl Default for InterfaceFilter {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: DEFAULT_DENIED_INTERFACES.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+impl InterfaceFilter {
+    pub fn includes(&self, interface: &str) -> bool {
+        let matches = |prefixes: &[String]| prefixes.iter().any(|p| interface.starts_with(p.as_str()));
+
+        !matches(&self.deny) && (self.allow.is_empty() || matches(&self.allow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_filter_skips_virtual_interfaces() {
+        let filter = InterfaceFilter::default();
+        assert!(filter.includes("eth0"));
+        assert!(filter.includes("wlp3s0"));
+        assert!(!filter.includes("lo"));
+        assert!(!filter.includes("docker0"));
+        assert!(!filter.includes("veth12ab"));
+        assert!(!filter.includes("tun0"));
+    }
+
+    #[test]
+    fn test_allow_list_narrows_and_deny_wins() {
+        let filter = InterfaceFilter {
+            allow: vec!["en".to_string(), "lo".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.includes("enp5s0"));
+        assert!(!filter.includes("wlan0"));
+        assert!(!filter.includes("lo"));
+    }
+}
@@ -0,0 +1,209 @@
+use super::battery::battery_init_error;
+use super::connections::collect_connection_count;
+use super::gpu_amd::amd_init_error;
+use super::gpu_nvidia::nvidia_init_error;
+use super::kernel::collect_kernel_counters;
+use super::swap::collect_swap_pages;
+use super::system::SystemMetrics;
+use super::temperature::sensor_readings;
+use sysinfo::Components;
+
+/// Where a reported value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueSource {
+    Measured,    // Read from a real counter or sensor
+    Fallback,    // Stand-in value used because the sensor is missing
+    Unavailable, // Not collected on this machine (the mapping stays silent)
+}
+
+/// One collected field, e.g. `cpu_usage = "12.5%"`
+#[derive(Debug, Clone)]
+pub struct MetricValue {
+    pub field: String,
+    pub value: String,
+    pub source: ValueSource,
+}
+
+/// The fields one subsystem contributes
+#[derive(Debug, Clone)]
+pub struct MetricGroup {
+    pub subsystem: &'static str,
+    pub values: Vec<MetricValue>,
+}
+
+impl MetricGroup {
+    fn new(subsystem: &'static str) -> Self {
+        Self { subsystem, values: Vec::new() }
+    }
+
+    fn add(&mut self, field: impl Into<String>, value: impl Into<String>, source: ValueSource) {
+        self.values.push(MetricValue { field: field.into(), value: value.into(), source });
+    }
+
+    fn measured(&mut self, field: impl Into<String>, value: impl Into<String>) {
+        self.add(field, value, ValueSource::Measured);
+    }
+
+    fn unavailable(&mut self, field: impl Into<String>, reason: impl Into<String>) {
+        self.add(field, reason, ValueSource::Unavailable);
+    }
+}
+
+fn per_sec(bytes: u64) -> String {
+    format!("{:.1} KB/s", bytes as f64 / 1024.0)
+}
+
+fn megabytes(bytes: u64) -> String {
+    format!("{:.0} MB", bytes as f64 / 1_048_576.0)
+}
+
+/// Every field of `metrics`, grouped by subsystem and marked with where it came from.
+///
+/// The platform-only counters (swap paging, scheduler, connections) and the
+/// temperature sensor list are probed again here, since a zero rate alone
+/// can't tell an idle counter from a missing one.
+pub fn inventory(metrics: &SystemMetrics) -> Vec<MetricGroup> {
+    let mut cpu = MetricGroup::new("CPU");
+    cpu.measured("cpu_usage", format!("{:.1}%", metrics.cpu_usage));
+    let cores: Vec<String> = metrics.per_core_usage.iter().map(|usage| format!("{:.0}", usage)).collect();
+    cpu.measured("per_core_usage", format!("{} cores: {}%", cores.len(), cores.join(", ")));
+    cpu.measured("load_avg", format!("{:.2} / {:.2} / {:.2}", metrics.load_avg_1, metrics.load_avg_5, metrics.load_avg_15));
+    cpu.measured("process_count", metrics.process_count.to_string());
+
+    let mut memory = MetricGroup::new("Memory");
+    memory.measured("memory_usage", format!("{:.1}%", metrics.memory_usage));
+    memory.measured("swap_used", format!("{} of {}", megabytes(metrics.swap_used), megabytes(metrics.swap_total)));
+    if collect_swap_pages().is_some() {
+        memory.measured("swap_in_bytes", per_sec(metrics.swap_in_bytes));
+        memory.measured("swap_out_bytes", per_sec(metrics.swap_out_bytes));
+    } else {
+        memory.unavailable("swap_in_bytes / swap_out_bytes", "no paging counters (Linux only)");
+    }
+
+    let mut disk = MetricGroup::new("Disk");
+    disk.measured("disk_read_bytes", per_sec(metrics.disk_read_bytes));
+    disk.measured("disk_write_bytes", per_sec(metrics.disk_write_bytes));
+
+    let mut network = MetricGroup::new("Network");
+    network.measured("network_rx_bytes", per_sec(metrics.network_rx_bytes));
+    network.measured("network_tx_bytes", per_sec(metrics.network_tx_bytes));
+    if metrics.network_interfaces.is_empty() {
+        network.unavailable("network_interfaces", "none counted (check the allow/deny lists)");
+    } else {
+        network.measured("network_interfaces", metrics.network_interfaces.join(", "));
+    }
+    match metrics.connection_count.or_else(collect_connection_count) {
+        Some(count) => {
+            network.measured("connection_count", count.to_string());
+            network.measured("connection_churn", format!("{:.1}/s", metrics.connection_churn));
+        }
+        None => network.unavailable("connection_count / connection_churn", "no connection table (Linux only)"),
+    }
+
+    let mut temperature = MetricGroup::new("Temperature");
+    let source = if metrics.temperature_available { ValueSource::Measured } else { ValueSource::Fallback };
+    temperature.add("temperature", format!("{:.1}°C", metrics.temperature), source);
+    temperature.add("temperature_raw", format!("{:.1}°C", metrics.temperature_raw), source);
+    for (label, celsius) in sensor_readings(&Components::new_with_refreshed_list()) {
+        temperature.measured(format!("sensor \"{}\"", label), format!("{:.1}°C", celsius));
+    }
+
+    let mut kernel = MetricGroup::new("Kernel");
+    if collect_kernel_counters().is_some() {
+        kernel.measured("context_switches", format!("{}/s", metrics.context_switches));
+        kernel.measured("interrupts", format!("{}/s", metrics.interrupts));
+    } else {
+        kernel.unavailable("context_switches / interrupts", "no scheduler counters (Linux only)");
+    }
+
+    let mut gpu = MetricGroup::new("GPU");
+    match &metrics.gpu_nvidia {
+        Some(nvidia) => {
+            gpu.measured("nvidia.name", nvidia.name.as_deref().unwrap_or("unknown model"));
+            gpu.measured("nvidia.utilization", format!("{:.0}%", nvidia.utilization));
+            gpu.measured("nvidia.temperature", format!("{:.0}°C", nvidia.temperature));
+            gpu.measured("nvidia.memory", format!("{} of {}", megabytes(nvidia.memory_used), megabytes(nvidia.memory_total)));
+            gpu.measured("nvidia.power_draw", format!("{:.0}W", nvidia.power_draw));
+            match nvidia.fan_speed {
+                Some(fan) => gpu.measured("nvidia.fan_speed", format!("{:.0}%", fan)),
+                None => gpu.unavailable("nvidia.fan_speed", "not reported"),
+            }
+        }
+        None => gpu.unavailable("nvidia", nvidia_init_error().unwrap_or_else(|| "no device".to_string())),
+    }
+    match &metrics.gpu_amd {
+        Some(amd) => {
+            gpu.measured("amd.name", amd.name.as_deref().unwrap_or("unknown model"));
+            gpu.measured("amd.utilization", format!("{:.0}%", amd.utilization));
+            gpu.measured("amd.temperature", format!("{:.0}°C", amd.temperature));
+            gpu.measured("amd.memory", format!("{} of {}", megabytes(amd.memory_used), megabytes(amd.memory_total)));
+            match amd.power_draw {
+                Some(watts) => gpu.measured("amd.power_draw", format!("{:.0}W", watts)),
+                None => gpu.unavailable("amd.power_draw", "not reported"),
+            }
+        }
+        None => gpu.unavailable("amd", amd_init_error().unwrap_or_else(|| "no device".to_string())),
+    }
+
+    let mut battery = MetricGroup::new("Battery");
+    match &metrics.battery {
+        Some(charge) => {
+            battery.measured("state_of_charge", format!("{:.0}% ({:?})", charge.state_of_charge, charge.state));
+            battery.measured("power_rate", format!("{:.1}W", charge.power_rate));
+            if let Some(minutes) = charge.time_to_empty {
+                battery.measured("time_to_empty", format!("{:.0} min", minutes));
+            }
+            if let Some(minutes) = charge.time_to_full {
+                battery.measured("time_to_full", format!("{:.0} min", minutes));
+            }
+        }
+        None => battery.unavailable("battery", battery_init_error().unwrap_or_else(|| "no battery found".to_string())),
+    }
+
+    let mut fans = MetricGroup::new("Fans");
+    match metrics.fan_speeds.as_deref() {
+        Some(speeds) if !speeds.is_empty() => {
+            for fan in speeds {
+                fans.measured(format!("fan \"{}\"", fan.label), format!("{} RPM", fan.rpm));
+            }
+        }
+        _ => fans.unavailable("fan_speeds", "no readable fan sensors"),
+    }
+
+    let mut processes = MetricGroup::new("Processes");
+    if metrics.top_processes.is_empty() {
+        processes.unavailable("top_processes", "none collected");
+    }
+    for process in &metrics.top_processes {
+        processes.measured(
+            format!("{} ({})", process.name, process.pid),
+            format!("{:.1}% CPU, {}", process.cpu_usage, megabytes(process.memory_usage)),
+        );
+    }
+
+    vec![cpu, memory, disk, network, temperature, kernel, gpu, battery, fans, processes]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group<'a>(groups: &'a [MetricGroup], subsystem: &str) -> &'a MetricGroup {
+        groups.iter().find(|g| g.subsystem == subsystem).unwrap()
+    }
+
+    #[test]
+    fn test_inventory_marks_fallbacks_and_missing_hardware() {
+        let mut metrics = SystemMetrics::quiet();
+        metrics.temperature_available = false;
+        let groups = inventory(&metrics);
+        assert_eq!(groups.len(), 10);
+
+        let temperature = &group(&groups, "Temperature").values[0];
+        assert_eq!((temperature.field.as_str(), temperature.source), ("temperature", ValueSource::Fallback));
+        assert_eq!(group(&groups, "CPU").values[0].source, ValueSource::Measured);
+        if metrics.battery.is_none() {
+            assert_eq!(group(&groups, "Battery").values[0].source, ValueSource::Unavailable);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// Scheduler counters are Linux-only (reads /proc/stat)
+#[cfg(target_os = "linux")]
+pub fn collect_kernel_counters() -> Option<(u64, u64)> {
+    parse_proc_stat(&std::fs::read_to_string("/proc/stat").ok()?)
+}
+
+/// Cumulative (context switches, interrupts) since boot from /proc/stat
+#[cfg(target_os = "linux")]
+fn parse_proc_stat(stat: &str) -> Option<(u64, u64)> {
+    // `intr` is the total followed by one count per interrupt source
+    let counter = |key: &str| {
+        stat.lines()
+            .find_map(|line| line.strip_prefix(key)?.split_whitespace().next()?.parse::<u64>().ok())
+    };
+
+    Some((counter("ctxt ")?, counter("intr ")?))
+}
+
+// Stub for non-Linux platforms
+#[cfg(not(target_os = "linux"))]
+pub fn collect_kernel_counters() -> Option<(u64, u64)> {
+    // Context switch and interrupt counters not supported on this platform
+    None
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let stat = "cpu  10 0 5 100 0 0 0 0 0 0\nintr 52000 10 0 3\nctxt 81000\nbtime 1700000000\n";
+        assert_eq!(parse_proc_stat(stat), Some((81000, 52000)));
+        assert_eq!(parse_proc_stat("cpu  10 0 5 100\n"), None);
+    }
+}
@@ -0,0 +1,37 @@
+// Core system metrics
+mod system;
+pub use system::{
+    SystemMetrics, MetricsCollector,
+    Aggregation, Smoothing,
+    NvidiaGpuMetrics, AmdGpuMetrics, GpuClocks, GpuVendor,
+    BatteryMetrics, BatteryState,
+    FanMetric, ProcessMetric,
+};
+
+// Metric collection modules
+mod gpu_nvidia;
+mod gpu_amd;
+mod battery;
+mod fans;
+mod connections;
+mod swap;
+mod kernel;
+mod temperature;
+mod interfaces;
+pub use interfaces::{InterfaceFilter, DEFAULT_DENIED_INTERFACES};
+mod processes;
+pub use processes::ProcessSort;
+
+// Attack/release smoothing ahead of the mapper
+mod envelope;
+pub use envelope::{EnvelopeFollower, EnvelopeSettings, EnvelopeTimes};
+
+// Capability checks for optional hardware
+mod diagnose;
+pub use diagnose::{diagnose, Subsystem, SubsystemReport};
+mod inventory;
+pub use inventory::{inventory, MetricGroup, MetricValue, ValueSource};
+
+// Live or recorded metrics
+mod source;
+pub use source::{MetricsSource, ReplaySource, record_metrics};
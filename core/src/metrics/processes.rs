@@ -0,0 +1,79 @@
+use sysinfo::{System, ProcessStatus};
+use super::system::ProcessMetric;
+
+/// Ranking used to pick which processes drive the process melodies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessSort {
+    Cpu,      // CPU % only (default)
+    Memory,   // Resident memory only
+    Combined, // CPU % + share of total memory %
+    Io,       // Disk reads + writes per second
+}
+
+impl ProcessSort {
+    /// Parse a config/CLI value ("cpu", "memory", "combined", "io")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "cpu" => Some(ProcessSort::Cpu),
+            "memory" | "mem" => Some(ProcessSort::Memory),
+            "combined" => Some(ProcessSort::Combined),
+            "io" | "disk" => Some(ProcessSort::Io),
+            _ => None,
+        }
+    }
+}
+
+/// Collect the top `top_n` processes ranked by `sort`.
+///
+/// `elapsed_secs` is the time since the last process refresh, used to turn
+/// per-process disk usage into rates. Platforms that don't report per-process
+/// I/O (or processes we lack permission to inspect) read as zero.
+pub fn collect_top_processes(system: &System, top_n: usize, sort: ProcessSort, elapsed_secs: f32) -> Vec<ProcessMetric> {
+    let total_memory = system.total_memory().max(1) as f32;
+    let memory_percent = |bytes: u64| (bytes as f32 / total_memory) * 100.0;
+    let per_second = |bytes: u64| (bytes as f32 / elapsed_secs.max(0.001)) as u64;
+
+    let mut processes: Vec<_> = system.processes()
+        .iter()
+        .filter(|(_, proc)| {
+            // Filter out idle processes; memory rankings keep low-CPU memory hogs
+            match sort {
+                ProcessSort::Cpu => proc.cpu_usage() > 0.1,
+                ProcessSort::Memory | ProcessSort::Combined => {
+                    proc.cpu_usage() > 0.1 || memory_percent(proc.memory()) > 1.0
+                }
+                ProcessSort::Io => {
+                    let io = proc.disk_usage();
+                    io.read_bytes + io.written_bytes > 0
+                }
+            }
+        })
+        .map(|(pid, proc)| {
+            let io = proc.disk_usage();
+            ProcessMetric {
+                name: proc.name().to_string(),
+                pid: pid.as_u32(),
+                cpu_usage: proc.cpu_usage(),
+                memory_usage: proc.memory(),
+                disk_read: per_second(io.read_bytes),
+                disk_write: per_second(io.written_bytes),
+            }
+        })
+        .collect();
+
+    // Sort by the chosen score (descending)
+    let score = |p: &ProcessMetric| match sort {
+        ProcessSort::Cpu => p.cpu_usage,
+        ProcessSort::Memory => memory_percent(p.memory_usage),
+        ProcessSort::Combined => p.cpu_usage + memory_percent(p.memory_usage),
+        ProcessSort::Io => (p.disk_read + p.disk_write) as f32,
+    };
+    processes.sort_by(|a, b| {
+        score(b).partial_cmp(&score(a)).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // Take top N
+    processes.truncate(top_n);
+
+    processes
+}
@@ -0,0 +1,97 @@
+use super::system::{MetricsCollector, SystemMetrics};
+use anyhow::Result;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::path::Path;
+
+/// Where compositions get their metrics from: the live system or a recording
+pub trait MetricsSource {
+    /// Metrics for the next composition, or `None` once the source is exhausted.
+    ///
+    /// Live sources combine `samples` readings `interval_ms` apart (or fold
+    /// one into their running average); recorded sources return one line.
+    fn next_metrics(&mut self, samples: usize, interval_ms: u64) -> Option<SystemMetrics>;
+}
+
+impl MetricsSource for MetricsCollector {
+    fn next_metrics(&mut self, samples: usize, interval_ms: u64) -> Option<SystemMetrics> {
+        Some(self.collect_averaged(samples, interval_ms))
+    }
+}
+
+/// Replays a recorded session: one JSON-serialized `SystemMetrics` per line
+pub struct ReplaySource {
+    lines: Lines<BufReader<File>>,
+    line_number: usize,
+}
+
+impl ReplaySource {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        Ok(Self { lines: BufReader::new(file).lines(), line_number: 0 })
+    }
+}
+
+impl MetricsSource for ReplaySource {
+    fn next_metrics(&mut self, _samples: usize, _interval_ms: u64) -> Option<SystemMetrics> {
+        loop {
+            let line = self.lines.next()?.ok()?;
+            self.line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // One bad line shouldn't end a long recording
+            match serde_json::from_str(&line) {
+                Ok(metrics) => return Some(metrics),
+                Err(e) => log::warn!("Skipping replay line {}: {}", self.line_number, e),
+            }
+        }
+    }
+}
+
+/// Append `metrics` to a session recording that `ReplaySource` can play back
+pub fn record_metrics(writer: &mut impl Write, metrics: &SystemMetrics) -> Result<()> {
+    writeln!(writer, "{}", serde_json::to_string(metrics)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_reads_recorded_session() {
+        let recorded: Vec<SystemMetrics> = [(5.0, 0, 150), (60.0, 4_000_000, 180), (95.0, 250_000, 210)]
+            .into_iter()
+            .map(|(cpu, disk_read, processes)| {
+                let mut metrics = SystemMetrics::quiet();
+                metrics.cpu_usage = cpu;
+                metrics.disk_read_bytes = disk_read;
+                metrics.process_count = processes;
+                metrics
+            })
+            .collect();
+
+        // Per process and test, so parallel runs don't share the file
+        let path = std::env::temp_dir()
+            .join(format!("syssonic_{}_test_replay_reads_recorded_session.jsonl", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        record_metrics(&mut file, &recorded[0]).unwrap();
+        writeln!(file, "not json").unwrap();
+        for metrics in &recorded[1..] {
+            record_metrics(&mut file, metrics).unwrap();
+        }
+        drop(file);
+
+        // Everything but the (unrecorded) timestamp comes back as it was
+        let values = |metrics: &SystemMetrics| serde_json::to_value(metrics).unwrap();
+        let mut replay = ReplaySource::open(&path).unwrap();
+        for metrics in &recorded {
+            assert_eq!(replay.next_metrics(3, 200).map(|m| values(&m)), Some(values(metrics)));
+        }
+        assert!(replay.next_metrics(3, 200).is_none());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
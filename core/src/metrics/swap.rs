@@ -0,0 +1,21 @@
+// Swap paging counters are Linux-only (reads /proc/vmstat)
+#[cfg(target_os = "linux")]
+pub fn collect_swap_pages() -> Option<(u64, u64)> {
+    let vmstat = std::fs::read_to_string("/proc/vmstat").ok()?;
+
+    // Cumulative pages swapped in and out since boot
+    let counter = |key: &str| {
+        vmstat
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.trim().parse::<u64>().ok())
+    };
+
+    Some((counter("pswpin ")?, counter("pswpout ")?))
+}
+
+// Stub for non-Linux platforms
+#[cfg(not(target_os = "linux"))]
+pub fn collect_swap_pages() -> Option<(u64, u64)> {
+    // Swap paging counters not supported on this platform
+    None
+}
@@ -0,0 +1,915 @@
+use sysinfo::{System, Networks, Disks, Components, ProcessesToUpdate};
+use super::interfaces::InterfaceFilter;
+use super::processes::ProcessSort;
+use super::temperature::{select_temperature, sensor_readings};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemMetrics {
+    // Original metrics
+    pub cpu_usage: f32,          // 0-100%
+    pub memory_usage: f32,       // 0-100%
+    pub disk_read_bytes: u64,    // bytes/sec
+    pub disk_write_bytes: u64,   // bytes/sec
+    pub network_rx_bytes: u64,   // bytes/sec
+    pub network_tx_bytes: u64,   // bytes/sec
+    #[serde(default)]
+    pub network_interfaces: Vec<String>, // Interfaces counted in the rates above
+    pub temperature: f32,        // °C (chosen sensor, smoothed over time)
+    #[serde(default)]
+    pub temperature_raw: f32,    // °C (chosen sensor, latest reading)
+    #[serde(default = "sensors_reported")]
+    pub temperature_available: bool, // False when no sensor was found and temperature is the fallback
+    #[serde(skip, default = "Instant::now")]
+    pub timestamp: Instant,
+    #[serde(default)]
+    pub sample_secs: f32,        // Real time the rates were measured over (0 = unknown)
+
+    // Easy wins (from sysinfo)
+    pub load_avg_1: f32,         // 1-minute load average
+    pub load_avg_5: f32,         // 5-minute load average
+    pub load_avg_15: f32,        // 15-minute load average
+    pub swap_total: u64,         // Total swap in bytes
+    pub swap_used: u64,          // Used swap in bytes
+    #[serde(default)]
+    pub swap_in_bytes: u64,      // bytes/sec paged in from swap (Linux only)
+    #[serde(default)]
+    pub swap_out_bytes: u64,     // bytes/sec paged out to swap (Linux only)
+    #[serde(default)]
+    pub context_switches: u64,   // Context switches/sec (Linux only)
+    #[serde(default)]
+    pub interrupts: u64,         // Interrupts/sec (Linux only)
+    pub per_core_usage: Vec<f32>, // Per-core CPU usage (0-100%)
+    pub process_count: usize,    // Number of running processes
+
+    // GPU metrics (optional - runtime detection)
+    pub gpu_nvidia: Option<NvidiaGpuMetrics>,
+    pub gpu_amd: Option<AmdGpuMetrics>,
+    #[serde(default)]
+    pub gpu_vendor: Option<String>, // Vendor of the GPU being sonified ("NVIDIA" or "AMD")
+    #[serde(default)]
+    pub gpu_name: Option<String>,   // Its model name, if the driver reports one
+
+    // Battery metrics (optional - laptops only)
+    pub battery: Option<BatteryMetrics>,
+
+    // Fan metrics (optional - Linux/supported hardware)
+    pub fan_speeds: Option<Vec<FanMetric>>,
+
+    // Per-process metrics (top N by CPU)
+    pub top_processes: Vec<ProcessMetric>,
+
+    // Connection activity (optional - Linux only)
+    pub connection_count: Option<usize>, // Established TCP connections
+    #[serde(default)]
+    pub connection_churn: f32,           // Change in connection count per second
+}
+
+/// Recordings from before `temperature_available` came from real sensors
+fn sensors_reported() -> bool {
+    true
+}
+
+impl SystemMetrics {
+    /// A calm, synthetic machine: 4 cores near idle, no GPU, battery or fan
+    /// sensors. A baseline for demos and tests to modify field by field.
+    pub fn quiet() -> Self {
+        Self {
+            cpu_usage: 5.0,
+            memory_usage: 35.0,
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+            network_rx_bytes: 2_000,
+            network_tx_bytes: 1_000,
+            network_interfaces: Vec::new(),
+            temperature: 40.0,
+            temperature_raw: 40.0,
+            temperature_available: true,
+            timestamp: Instant::now(),
+            sample_secs: 1.0,
+            load_avg_1: 0.2,
+            load_avg_5: 0.2,
+            load_avg_15: 0.2,
+            swap_total: 0,
+            swap_used: 0,
+            swap_in_bytes: 0,
+            swap_out_bytes: 0,
+            context_switches: 3_000,
+            interrupts: 1_500,
+            per_core_usage: vec![5.0; 4],
+            process_count: 150,
+            gpu_nvidia: None,
+            gpu_amd: None,
+            gpu_vendor: None,
+            gpu_name: None,
+            battery: None,
+            fan_speeds: None,
+            top_processes: Vec::new(),
+            connection_count: None,
+            connection_churn: 0.0,
+        }
+    }
+}
+
+// GPU metric structs (will be implemented in separate modules)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NvidiaGpuMetrics {
+    pub name: Option<String>,    // e.g. "NVIDIA GeForce RTX 4070"
+    pub utilization: f32,        // 0-100%
+    pub temperature: f32,         // °C
+    pub memory_used: u64,         // bytes
+    pub memory_total: u64,        // bytes
+    pub power_draw: f32,          // watts
+    pub fan_speed: Option<f32>,   // 0-100%
+    pub clocks: Option<GpuClocks>, // None if the driver doesn't report clocks
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmdGpuMetrics {
+    pub name: Option<String>,    // Marketing name, e.g. "AMD Radeon RX 7800 XT"
+    pub utilization: f32,        // 0-100%
+    pub temperature: f32,         // °C
+    pub memory_used: u64,         // bytes
+    pub memory_total: u64,        // bytes
+    pub power_draw: Option<f32>,  // watts (if available)
+    pub clocks: Option<GpuClocks>, // None if the driver doesn't report clocks
+}
+
+/// GPU vendor to sonify when more than one is present
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+}
+
+impl GpuVendor {
+    /// Parse a config value ("nvidia", "amd"); "auto" and unknown names give None
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "nvidia" => Some(GpuVendor::Nvidia),
+            "amd" => Some(GpuVendor::Amd),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GpuVendor::Nvidia => "NVIDIA",
+            GpuVendor::Amd => "AMD",
+        }
+    }
+}
+
+/// Current and maximum GPU clocks, to tell a boosted card from an idle one
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GpuClocks {
+    pub core_mhz: f32,
+    pub core_max_mhz: f32,
+    pub memory_mhz: f32,
+    pub memory_max_mhz: f32,
+}
+
+impl GpuClocks {
+    /// Core clock as a fraction of its maximum (0.0-1.0)
+    pub fn core_ratio(&self) -> f32 {
+        (self.core_mhz / self.core_max_mhz.max(1.0)).clamp(0.0, 1.0)
+    }
+
+    /// Memory clock as a fraction of its maximum (0.0-1.0)
+    pub fn memory_ratio(&self) -> f32 {
+        (self.memory_mhz / self.memory_max_mhz.max(1.0)).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryMetrics {
+    pub state_of_charge: f32,    // 0-100%
+    pub state: BatteryState,      // Charging/Discharging/Full/Empty
+    pub power_rate: f32,          // watts (positive=charging, negative=discharging)
+    pub temperature: Option<f32>, // °C
+    pub time_to_full: Option<f32>, // minutes
+    pub time_to_empty: Option<f32>, // minutes
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BatteryState {
+    Charging,
+    Discharging,
+    Full,
+    Empty,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanMetric {
+    pub label: String,            // Fan sensor label
+    pub rpm: u32,                 // RPM reading
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessMetric {
+    pub name: String,             // Process name
+    pub pid: u32,                 // Process ID
+    pub cpu_usage: f32,           // CPU % (0-100)
+    pub memory_usage: u64,        // Memory in bytes
+    #[serde(default)]
+    pub disk_read: u64,           // bytes/sec (0 if unavailable)
+    #[serde(default)]
+    pub disk_write: u64,          // bytes/sec (0 if unavailable)
+}
+
+/// How `collect_smoothed` combines samples of one metric
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Mean,
+    Max,  // Catch spikes
+    Min,
+    Last, // Most recent sample only
+}
+
+impl Aggregation {
+    /// Parse a config/CLI value ("mean", "max", "min", "last")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "mean" | "avg" => Some(Aggregation::Mean),
+            "max" => Some(Aggregation::Max),
+            "min" => Some(Aggregation::Min),
+            "last" => Some(Aggregation::Last),
+            _ => None,
+        }
+    }
+
+    fn apply(self, values: &[f64]) -> f64 {
+        if values.is_empty() {
+            return 0.0;
+        }
+        match self {
+            Aggregation::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregation::Max => values.iter().copied().fold(f64::MIN, f64::max),
+            Aggregation::Min => values.iter().copied().fold(f64::MAX, f64::min),
+            Aggregation::Last => values[values.len() - 1],
+        }
+    }
+
+    /// Like `apply`, but the mean weights each rate by the seconds it covers.
+    ///
+    /// Samples on a busy machine are taken at uneven intervals; averaging their
+    /// rates directly would overweight the short ones. Falls back to `apply`
+    /// when the durations are unknown.
+    fn apply_weighted(self, values: &[f64], secs: &[f64]) -> f64 {
+        let total_secs: f64 = secs.iter().sum();
+        match self {
+            Aggregation::Mean if total_secs > 0.0 && secs.len() == values.len() => {
+                values.iter().zip(secs).map(|(v, s)| v * s).sum::<f64>() / total_secs
+            }
+            _ => self.apply(values),
+        }
+    }
+}
+
+/// Per-metric aggregation used by `collect_smoothed`.
+///
+/// Defaults average CPU/memory/temperature/load and take the max for I/O.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Smoothing {
+    pub cpu: Aggregation,    // Also per-core usage
+    pub memory: Aggregation, // Also swap
+    pub temperature: Aggregation,
+    pub disk: Aggregation,
+    pub network: Aggregation,
+    pub load: Aggregation,
+}
+
+impl Default for Smoothing {
+    fn default() -> Self {
+        Self {
+            cpu: Aggregation::Mean,
+            memory: Aggregation::Mean,
+            temperature: Aggregation::Mean,
+            disk: Aggregation::Max, // Bursty I/O is more interesting musically
+            network: Aggregation::Max,
+            load: Aggregation::Mean,
+        }
+    }
+}
+
+pub struct MetricsCollector {
+    system: System,
+    networks: Networks,
+    disks: Disks,
+    components: Components,
+    last_metrics: Option<SystemMetrics>,
+    last_update: Instant,
+
+    // Process melody selection
+    process_voices: usize,
+    process_sort: ProcessSort,
+
+    // How collect_smoothed combines samples
+    smoothing: Smoothing,
+
+    // Forced GPU vendor (None = NVIDIA if present, otherwise AMD)
+    gpu_vendor: Option<GpuVendor>,
+
+    // Temperature moving-average time constant (seconds, 0 = off)
+    temperature_time_constant: f32,
+
+    // Reported temperature when no sensor is found (°C)
+    fallback_temperature: f32,
+
+    // Label fragment of the sensor to follow (None = CPU package if found)
+    temperature_sensor: Option<String>,
+
+    // Cumulative disk bytes (read, written) at the last sample, for rates
+    last_disk_totals: Option<(u64, u64)>,
+
+    // Cumulative network bytes (received, transmitted) at the last sample, for rates
+    last_network_totals: Option<(u64, u64)>,
+
+    // Cumulative swap pages (in, out) at the last sample, for rates
+    last_swap_pages: Option<(u64, u64)>,
+
+    // Cumulative (context switches, interrupts) at the last sample, for rates
+    last_kernel_counters: Option<(u64, u64)>,
+
+    // Interfaces summed into the network rates
+    interface_filter: InterfaceFilter,
+
+    // Running average kept by collect_ema (None until its first call)
+    ema_state: Option<SystemMetrics>,
+
+    // New-sample weight collect_averaged passes to collect_ema (None = collect_smoothed)
+    ema_alpha: Option<f32>,
+}
+
+impl MetricsCollector {
+    pub fn new() -> Self {
+        Self {
+            system: System::new_all(),
+            networks: Networks::new_with_refreshed_list(),
+            disks: Disks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
+            last_metrics: None,
+            last_update: Instant::now(),
+            process_voices: 5,
+            process_sort: ProcessSort::Cpu,
+            smoothing: Smoothing::default(),
+            gpu_vendor: None,
+            temperature_time_constant: 10.0,
+            fallback_temperature: 45.0,
+            temperature_sensor: None,
+            last_disk_totals: None,
+            last_network_totals: None,
+            last_swap_pages: None,
+            last_kernel_counters: None,
+            interface_filter: InterfaceFilter::default(),
+            ema_state: None,
+            ema_alpha: None,
+        }
+    }
+
+    /// Choose how many top processes are collected and how they are ranked
+    pub fn with_process_selection(mut self, voices: usize, sort: ProcessSort) -> Self {
+        self.process_voices = voices;
+        self.process_sort = sort;
+        self
+    }
+
+    /// Choose how `collect_smoothed` combines samples per metric
+    pub fn with_smoothing(mut self, smoothing: Smoothing) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Only collect (and sonify) one GPU vendor, e.g. the dGPU on a hybrid laptop
+    pub fn with_gpu_vendor(mut self, vendor: Option<GpuVendor>) -> Self {
+        self.gpu_vendor = vendor;
+        self
+    }
+
+    /// Smooth temperature over roughly `secs` seconds (0 disables).
+    ///
+    /// Sensors jitter by a few degrees between reads while real temperature
+    /// changes slowly, so this runs on every `collect`, not just `collect_smoothed`.
+    pub fn with_temperature_time_constant(mut self, secs: f32) -> Self {
+        self.temperature_time_constant = secs.max(0.0);
+        self
+    }
+
+    /// Temperature (°C) reported when no sensor is found; such samples have
+    /// `temperature_available` cleared so the mapper and UI can tell
+    pub fn with_fallback_temperature(mut self, celsius: f32) -> Self {
+        self.fallback_temperature = celsius;
+        self
+    }
+
+    /// Follow the sensors whose label contains `sensor` instead of the CPU
+    /// package; the average of all sensors when none match
+    pub fn with_temperature_sensor(mut self, sensor: Option<String>) -> Self {
+        self.temperature_sensor = sensor;
+        self
+    }
+
+    /// Choose which network interfaces count toward the network rates
+    pub fn with_interface_filter(mut self, filter: InterfaceFilter) -> Self {
+        self.interface_filter = filter;
+        self
+    }
+
+    /// Have `collect_averaged` follow `collect_ema` with this `alpha` (held
+    /// to 1.0) instead of a fresh `collect_smoothed` window; None or 0 = off
+    pub fn with_ema(mut self, alpha: Option<f32>) -> Self {
+        self.ema_alpha = alpha.filter(|&alpha| alpha > 0.0).map(|alpha| alpha.min(1.0));
+        self
+    }
+
+    pub fn collect(&mut self) -> SystemMetrics {
+        // Refresh the I/O counters first and time them right away, so a slow
+        // process or sensor refresh doesn't stretch the interval the rates cover
+        self.networks.refresh();
+        self.disks.refresh();
+        let now = Instant::now();
+        let delta = now.duration_since(self.last_update).as_secs_f32();
+        self.last_update = now;
+
+        // Refresh everything else
+        self.system.refresh_cpu_all();
+        self.system.refresh_memory();
+        self.system.refresh_processes(ProcessesToUpdate::All);
+        self.components.refresh();
+
+        // CPU Usage (average across all cores)
+        let cpu_usage = self.system.global_cpu_usage();
+
+        // Memory Usage
+        let memory_usage = (self.system.used_memory() as f32 / self.system.total_memory() as f32) * 100.0;
+
+        // Disk I/O: rates from the cumulative counters (the previous sample
+        // only holds rates, so the totals are kept apart)
+        let disk_totals = (
+            self.disks.iter().map(|d| d.usage().total_read_bytes).sum::<u64>(),
+            self.disks.iter().map(|d| d.usage().total_written_bytes).sum::<u64>(),
+        );
+        let (disk_read_bytes, disk_write_bytes) = totals_rate(disk_totals, self.last_disk_totals, delta);
+        self.last_disk_totals = Some(disk_totals);
+
+        // Network I/O (calculate rates), skipping loopback and virtual interfaces
+        let filter = &self.interface_filter;
+        let counted = || self.networks.iter().filter(move |(name, _)| filter.includes(name));
+        let network_interfaces: Vec<String> = counted().map(|(name, _)| name.clone()).collect();
+        let network_totals = (
+            counted().map(|(_, data)| data.total_received()).sum::<u64>(),
+            counted().map(|(_, data)| data.total_transmitted()).sum::<u64>(),
+        );
+        let (network_rx_bytes, network_tx_bytes) = totals_rate(network_totals, self.last_network_totals, delta);
+        self.last_network_totals = Some(network_totals);
+
+        // Temperature (the chosen sensor, or the average across all of them)
+        let selected = select_temperature(&sensor_readings(&self.components), self.temperature_sensor.as_deref());
+        let temperature_available = selected.is_some();
+        let temperature_raw = selected.unwrap_or(self.fallback_temperature);
+        let temperature = match &self.last_metrics {
+            Some(prev) => ema(prev.temperature, temperature_raw, delta, self.temperature_time_constant),
+            None => temperature_raw,
+        };
+
+        // Easy wins: Load average
+        let load_avg = self.system.load_average();
+        let load_avg_1 = load_avg.one as f32;
+        let load_avg_5 = load_avg.five as f32;
+        let load_avg_15 = load_avg.fifteen as f32;
+
+        // Easy wins: Swap usage
+        let swap_total = self.system.total_swap();
+        let swap_used = self.system.used_swap();
+
+        // Active swapping: paging rates from the cumulative counters
+        let swap_pages = super::swap::collect_swap_pages();
+        let (swap_in_bytes, swap_out_bytes) = match (swap_pages, self.last_swap_pages) {
+            (Some((pages_in, pages_out)), Some((prev_in, prev_out))) if delta > 0.0 => {
+                let rate = |pages: u64| (pages as f32 * SWAP_PAGE_BYTES / delta) as u64;
+                (rate(pages_in.saturating_sub(prev_in)), rate(pages_out.saturating_sub(prev_out)))
+            }
+            _ => (0, 0),
+        };
+        self.last_swap_pages = swap_pages;
+
+        // Scheduler activity: context switch and interrupt rates
+        let kernel_counters = super::kernel::collect_kernel_counters();
+        let (context_switches, interrupts) = match (kernel_counters, self.last_kernel_counters) {
+            (Some((switches, irqs)), Some((prev_switches, prev_irqs))) if delta > 0.0 => {
+                let rate = |count: u64| (count as f32 / delta) as u64;
+                (rate(switches.saturating_sub(prev_switches)), rate(irqs.saturating_sub(prev_irqs)))
+            }
+            _ => (0, 0),
+        };
+        self.last_kernel_counters = kernel_counters;
+
+        // Easy wins: Per-core CPU usage
+        let per_core_usage: Vec<f32> = self.system.cpus()
+            .iter()
+            .map(|cpu| cpu.cpu_usage())
+            .collect();
+
+        // Easy wins: Process count
+        let process_count = self.system.processes().len();
+
+        // GPU metrics (a forced vendor skips probing the other one)
+        let gpu_nvidia = match self.gpu_vendor {
+            Some(GpuVendor::Amd) => None,
+            _ => super::gpu_nvidia::collect_nvidia_metrics(),
+        };
+        let gpu_amd = match self.gpu_vendor {
+            Some(GpuVendor::Nvidia) => None,
+            _ => super::gpu_amd::collect_amd_metrics(),
+        };
+        let (gpu_vendor, gpu_name) = active_gpu(&gpu_nvidia, &gpu_amd);
+
+        // Battery metrics (will be populated by battery module)
+        let battery = super::battery::collect_battery_metrics();
+
+        // Fan metrics (will be populated by fans module)
+        let fan_speeds = super::fans::collect_fan_metrics();
+
+        // Connection count, and how fast it changed since the last sample
+        let connection_count = super::connections::collect_connection_count();
+        let connection_churn = match (connection_count, self.last_metrics.as_ref().and_then(|m| m.connection_count)) {
+            (Some(count), Some(prev)) if delta > 0.0 => count.abs_diff(prev) as f32 / delta,
+            _ => 0.0,
+        };
+
+        // Top processes (by CPU unless configured otherwise)
+        let top_processes = super::processes::collect_top_processes(
+            &self.system,
+            self.process_voices,
+            self.process_sort,
+            delta,
+        );
+
+        let metrics = SystemMetrics {
+            cpu_usage,
+            memory_usage,
+            disk_read_bytes,
+            disk_write_bytes,
+            network_rx_bytes,
+            network_tx_bytes,
+            network_interfaces,
+            temperature,
+            temperature_raw,
+            temperature_available,
+            timestamp: now,
+            sample_secs: delta,
+            load_avg_1,
+            load_avg_5,
+            load_avg_15,
+            swap_total,
+            swap_used,
+            swap_in_bytes,
+            swap_out_bytes,
+            context_switches,
+            interrupts,
+            per_core_usage,
+            process_count,
+            gpu_nvidia,
+            gpu_amd,
+            gpu_vendor,
+            gpu_name,
+            battery,
+            fan_speeds,
+            top_processes,
+            connection_count,
+            connection_churn,
+        };
+
+        self.last_metrics = Some(metrics.clone());
+        metrics
+    }
+
+    /// One sample blended into an exponential moving average kept across calls.
+    ///
+    /// `alpha` (0.0-1.0) is the weight of the new sample: 1.0 follows each
+    /// sample as-is, 0.5 moves halfway toward it per call, and 0.1 takes about
+    /// 22 calls to cover 90% of a step. Lower is smoother but slower to react;
+    /// unlike `collect_smoothed`, a burst fades out over the following calls
+    /// instead of ending with its window.
+    pub fn collect_ema(&mut self, alpha: f32) -> SystemMetrics {
+        let sample = self.collect();
+        let averaged = match self.ema_state.take() {
+            Some(previous) => ema_blend(&previous, &sample, alpha),
+            None => sample,
+        };
+        self.ema_state = Some(averaged.clone());
+        averaged
+    }
+
+    /// Metrics for the next composition: one reading into the running average
+    /// when `with_ema` set one, otherwise `samples` readings `interval_ms` apart
+    pub fn collect_averaged(&mut self, samples: usize, interval_ms: u64) -> SystemMetrics {
+        match self.ema_alpha {
+            Some(alpha) => self.collect_ema(alpha),
+            None => self.collect_smoothed(samples, interval_ms),
+        }
+    }
+
+    pub fn collect_smoothed(&mut self, samples: usize, interval_ms: u64) -> SystemMetrics {
+        let mut accumulated = vec![];
+
+        for _ in 0..samples {
+            accumulated.push(self.collect());
+            std::thread::sleep(Duration::from_millis(interval_ms));
+        }
+
+        combine_samples(&accumulated, self.smoothing)
+    }
+}
+
+/// One reading from `accumulated` samples, each metric combined as `smoothing` says
+fn combine_samples(accumulated: &[SystemMetrics], smoothing: Smoothing) -> SystemMetrics {
+    let series = |value: fn(&SystemMetrics) -> f64| -> Vec<f64> {
+        accumulated.iter().map(value).collect()
+    };
+
+    // Combine the samples for smoother transitions
+    let cpu_avg = smoothing.cpu.apply(&series(|m| m.cpu_usage as f64)) as f32;
+    let mem_avg = smoothing.memory.apply(&series(|m| m.memory_usage as f64)) as f32;
+    let temp_avg = smoothing.temperature.apply(&series(|m| m.temperature as f64)) as f32;
+    let temp_raw = accumulated.last().map(|m| m.temperature_raw).unwrap_or(temp_avg);
+    let temp_available = accumulated.iter().all(|m| m.temperature_available);
+
+    // I/O metrics (max by default, more interesting musically). Rates are
+    // weighted by how long each sample actually covered
+    let secs = series(|m| m.sample_secs as f64);
+    let sample_secs = secs.iter().sum::<f64>() as f32;
+    let disk_read = smoothing.disk.apply_weighted(&series(|m| m.disk_read_bytes as f64), &secs) as u64;
+    let disk_write = smoothing.disk.apply_weighted(&series(|m| m.disk_write_bytes as f64), &secs) as u64;
+    let net_rx = smoothing.network.apply_weighted(&series(|m| m.network_rx_bytes as f64), &secs) as u64;
+    let net_tx = smoothing.network.apply_weighted(&series(|m| m.network_tx_bytes as f64), &secs) as u64;
+
+    // Load averages
+    let load_avg_1 = smoothing.load.apply(&series(|m| m.load_avg_1 as f64)) as f32;
+    let load_avg_5 = smoothing.load.apply(&series(|m| m.load_avg_5 as f64)) as f32;
+    let load_avg_15 = smoothing.load.apply(&series(|m| m.load_avg_15 as f64)) as f32;
+
+    // Swap usage (follows memory)
+    let swap_total = accumulated.last().map(|m| m.swap_total).unwrap_or(0);
+    let swap_used = smoothing.memory.apply(&series(|m| m.swap_used as f64)) as u64;
+
+    // Paging rates (follow disk, bursty)
+    let swap_in_bytes = smoothing.disk.apply_weighted(&series(|m| m.swap_in_bytes as f64), &secs) as u64;
+    let swap_out_bytes = smoothing.disk.apply_weighted(&series(|m| m.swap_out_bytes as f64), &secs) as u64;
+
+    // Scheduler rates (follow CPU)
+    let context_switches = smoothing.cpu.apply_weighted(&series(|m| m.context_switches as f64), &secs) as u64;
+    let interrupts = smoothing.cpu.apply_weighted(&series(|m| m.interrupts as f64), &secs) as u64;
+
+    // Per-core usage (each core combined across samples, follows CPU)
+    let core_count = accumulated.first().map(|m| m.per_core_usage.len()).unwrap_or(0);
+    let per_core_usage = (0..core_count)
+        .map(|i| {
+            let core: Vec<f64> = accumulated.iter()
+                .filter_map(|m| m.per_core_usage.get(i).map(|&u| u as f64))
+                .collect();
+            smoothing.cpu.apply(&core) as f32
+        })
+        .collect();
+
+    // Counted interfaces: latest sample
+    let network_interfaces = accumulated.last().map(|m| m.network_interfaces.clone()).unwrap_or_default();
+
+    // Process count (average)
+    let process_count = Aggregation::Mean.apply(&series(|m| m.process_count as f64)) as usize;
+
+    // GPU/Battery/Fans: Take last sample (no averaging needed for these)
+    let gpu_nvidia = accumulated.last().and_then(|m| m.gpu_nvidia.clone());
+    let gpu_amd = accumulated.last().and_then(|m| m.gpu_amd.clone());
+    let gpu_vendor = accumulated.last().and_then(|m| m.gpu_vendor.clone());
+    let gpu_name = accumulated.last().and_then(|m| m.gpu_name.clone());
+    let battery = accumulated.last().and_then(|m| m.battery.clone());
+    let fan_speeds = accumulated.last().and_then(|m| m.fan_speeds.clone());
+
+    // Top processes: Take last sample
+    let top_processes = accumulated.last().map(|m| m.top_processes.clone()).unwrap_or_default();
+
+    // Connections: latest count, churn follows network (bursty)
+    let connection_count = accumulated.last().and_then(|m| m.connection_count);
+    let connection_churn = smoothing.network.apply_weighted(&series(|m| m.connection_churn as f64), &secs) as f32;
+
+    SystemMetrics {
+        cpu_usage: cpu_avg,
+        memory_usage: mem_avg,
+        disk_read_bytes: disk_read,
+        disk_write_bytes: disk_write,
+        network_rx_bytes: net_rx,
+        network_tx_bytes: net_tx,
+        network_interfaces,
+        temperature: temp_avg,
+        temperature_raw: temp_raw,
+        temperature_available: temp_available,
+        timestamp: Instant::now(),
+        sample_secs,
+        load_avg_1,
+        load_avg_5,
+        load_avg_15,
+        swap_total,
+        swap_used,
+        swap_in_bytes,
+        swap_out_bytes,
+        context_switches,
+        interrupts,
+        per_core_usage,
+        process_count,
+        gpu_nvidia,
+        gpu_amd,
+        gpu_vendor,
+        gpu_name,
+        battery,
+        fan_speeds,
+        top_processes,
+        connection_count,
+        connection_churn,
+    }
+}
+
+/// Bytes per page in /proc/vmstat's pswpin/pswpout (4 KiB on common Linux platforms)
+const SWAP_PAGE_BYTES: f32 = 4096.0;
+
+/// Per-second rates of a pair of cumulative counters since `last` (zero on
+/// the first reading). A counter that went backwards (device removed,
+/// counter reset) reads as zero rather than wrapping.
+fn totals_rate(totals: (u64, u64), last: Option<(u64, u64)>, elapsed: f32) -> (u64, u64) {
+    match last {
+        Some((last_a, last_b)) if elapsed > 0.0 => {
+            let rate = |total: u64, last: u64| (total.saturating_sub(last) as f32 / elapsed) as u64;
+            (rate(totals.0, last_a), rate(totals.1, last_b))
+        }
+        _ => (0, 0),
+    }
+}
+
+/// `sample` with its levels and rates weighted `alpha` against `previous`
+/// (the rest, such as processes and sensors, is the latest as collected)
+fn ema_blend(previous: &SystemMetrics, sample: &SystemMetrics, alpha: f32) -> SystemMetrics {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let level = |last: f32, value: f32| last * (1.0 - alpha) + value * alpha;
+    let rate = |last: u64, value: u64| (last as f64 * (1.0 - alpha as f64) + value as f64 * alpha as f64).round() as u64;
+
+    let per_core_usage = if previous.per_core_usage.len() == sample.per_core_usage.len() {
+        previous.per_core_usage.iter().zip(&sample.per_core_usage).map(|(&last, &value)| level(last, value)).collect()
+    } else {
+        sample.per_core_usage.clone()
+    };
+
+    SystemMetrics {
+        cpu_usage: level(previous.cpu_usage, sample.cpu_usage),
+        memory_usage: level(previous.memory_usage, sample.memory_usage),
+        disk_read_bytes: rate(previous.disk_read_bytes, sample.disk_read_bytes),
+        disk_write_bytes: rate(previous.disk_write_bytes, sample.disk_write_bytes),
+        network_rx_bytes: rate(previous.network_rx_bytes, sample.network_rx_bytes),
+        network_tx_bytes: rate(previous.network_tx_bytes, sample.network_tx_bytes),
+        temperature: level(previous.temperature, sample.temperature),
+        load_avg_1: level(previous.load_avg_1, sample.load_avg_1),
+        load_avg_5: level(previous.load_avg_5, sample.load_avg_5),
+        load_avg_15: level(previous.load_avg_15, sample.load_avg_15),
+        swap_used: rate(previous.swap_used, sample.swap_used),
+        swap_in_bytes: rate(previous.swap_in_bytes, sample.swap_in_bytes),
+        swap_out_bytes: rate(previous.swap_out_bytes, sample.swap_out_bytes),
+        context_switches: rate(previous.context_switches, sample.context_switches),
+        interrupts: rate(previous.interrupts, sample.interrupts),
+        connection_churn: level(previous.connection_churn, sample.connection_churn),
+        per_core_usage,
+        ..sample.clone()
+    }
+}
+
+/// Exponential moving average step: move `previous` toward `value` by the
+/// share of time constant `tau` that `elapsed` covers (both in seconds)
+pub(super) fn ema(previous: f32, value: f32, elapsed: f32, tau: f32) -> f32 {
+    if tau <= 0.0 {
+        return value;
+    }
+    let alpha = 1.0 - (-elapsed / tau).exp();
+    previous + (value - previous) * alpha
+}
+
+/// Vendor and name of the GPU the mapper sonifies: NVIDIA first, then AMD
+fn active_gpu(
+    nvidia: &Option<NvidiaGpuMetrics>,
+    amd: &Option<AmdGpuMetrics>,
+) -> (Option<String>, Option<String>) {
+    match (nvidia, amd) {
+        (Some(gpu), _) => (Some(GpuVendor::Nvidia.label().to_string()), gpu.name.clone()),
+        (None, Some(gpu)) => (Some(GpuVendor::Amd.label().to_string()), gpu.name.clone()),
+        (None, None) => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregations() {
+        let values = [2.0, 8.0, 5.0];
+        assert_eq!(Aggregation::Mean.apply(&values), 5.0);
+        assert_eq!(Aggregation::Max.apply(&values), 8.0);
+        assert_eq!(Aggregation::Min.apply(&values), 2.0);
+        assert_eq!(Aggregation::Last.apply(&values), 5.0);
+        assert_eq!(Aggregation::Max.apply(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_io_rates_come_from_cumulative_totals() {
+        // 5 MB read and 1 MB written over 2 seconds
+        let first = (100_000_000, 40_000_000);
+        let second = (105_000_000, 41_000_000);
+        assert_eq!(totals_rate(first, None, 2.0), (0, 0));
+        assert_eq!(totals_rate(second, Some(first), 2.0), (2_500_000, 500_000));
+
+        // The next rate is against the last totals, not the last rate:
+        // a steady 2.5 MB/s stays 2.5 MB/s
+        let third = (110_000_000, 42_000_000);
+        assert_eq!(totals_rate(third, Some(second), 2.0), (2_500_000, 500_000));
+        assert_eq!(totals_rate(first, Some(second), 2.0), (0, 0), "reset counters read as idle");
+        assert_eq!(totals_rate(second, Some(first), 0.0), (0, 0));
+    }
+
+    #[test]
+    fn test_collect_reports_cores_load_and_swap() {
+        // The CLI, tray and daemon share this collector, so `live` maps these too
+        let metrics = MetricsCollector::new().collect();
+        assert_eq!(metrics.per_core_usage.len(), System::new_all().cpus().len());
+        assert!(metrics.per_core_usage.iter().all(|usage| (0.0..=100.0).contains(usage)));
+        assert!(metrics.load_avg_1 >= 0.0 && metrics.load_avg_5 >= 0.0 && metrics.load_avg_15 >= 0.0);
+        assert!(metrics.swap_used <= metrics.swap_total);
+    }
+
+    #[test]
+    fn test_collect_ema_weights_the_new_sample() {
+        let previous = SystemMetrics::quiet();
+        let mut sample = SystemMetrics::quiet();
+        sample.cpu_usage = 85.0;
+        sample.disk_read_bytes = 4_000_000;
+        sample.per_core_usage = vec![90.0; 4];
+
+        // alpha = 1.0 is the raw sample
+        let raw = ema_blend(&previous, &sample, 1.0);
+        assert_eq!(raw.cpu_usage, sample.cpu_usage);
+        assert_eq!(raw.disk_read_bytes, sample.disk_read_bytes);
+        assert_eq!(raw.per_core_usage, sample.per_core_usage);
+        assert_eq!(raw.memory_usage, sample.memory_usage);
+
+        let half = ema_blend(&previous, &sample, 0.5);
+        assert_eq!(half.cpu_usage, 45.0);
+        assert_eq!(half.disk_read_bytes, 2_000_000);
+
+        // The running average carries over: the next call moves alpha of the way to the new sample
+        let mut collector = MetricsCollector::new();
+        collector.ema_state = Some(previous.clone());
+        let averaged = collector.collect_ema(0.2);
+        let collected = collector.last_metrics.clone().unwrap();
+        assert!((averaged.cpu_usage - (previous.cpu_usage * 0.8 + collected.cpu_usage * 0.2)).abs() < 1e-3);
+        assert_eq!(collector.ema_state.map(|m| m.cpu_usage), Some(averaged.cpu_usage));
+    }
+
+    #[test]
+    fn test_temperature_ema_glides() {
+        // One time constant covers ~63% of a step
+        let step = ema(40.0, 50.0, 10.0, 10.0);
+        assert!((step - 46.32).abs() < 0.01);
+
+        // A short refresh barely moves it; no time constant jumps straight there
+        assert!(ema(40.0, 50.0, 0.2, 10.0) < 40.5);
+        assert_eq!(ema(40.0, 50.0, 0.2, 0.0), 50.0);
+    }
+
+    #[test]
+    fn test_rates_weighted_by_real_interval() {
+        // A 1s sample at 10 MB/s, then a slow refresh stretching the next to 3s at 2 MB/s:
+        // 16 MB over 4s is 4 MB/s, not the naive 6 MB/s
+        let rates = [10_000_000.0, 2_000_000.0];
+        let secs = [1.0, 3.0];
+        assert_eq!(Aggregation::Mean.apply_weighted(&rates, &secs), 4_000_000.0);
+        assert_eq!(Aggregation::Max.apply_weighted(&rates, &secs), 10_000_000.0);
+        assert_eq!(Aggregation::Mean.apply_weighted(&rates, &[0.0, 0.0]), 6_000_000.0);
+
+        // Two real refreshes, the second after a much longer gap: combining them
+        // weights the burst by the short interval it covered
+        let mut collector = MetricsCollector::new();
+        collector.collect();
+        std::thread::sleep(Duration::from_millis(20));
+        let mut quick = collector.collect();
+        std::thread::sleep(Duration::from_millis(600));
+        let mut slow = collector.collect();
+        assert!(slow.sample_secs > quick.sample_secs);
+
+        quick.disk_read_bytes = 10_000_000;
+        slow.disk_read_bytes = 2_000_000;
+        let (quick_secs, slow_secs) = (quick.sample_secs as f64, slow.sample_secs as f64);
+        let smoothing = Smoothing { disk: Aggregation::Mean, ..Smoothing::default() };
+        let combined = combine_samples(&[quick, slow], smoothing).disk_read_bytes as f64;
+        let weighted = (10_000_000.0 * quick_secs + 2_000_000.0 * slow_secs) / (quick_secs + slow_secs);
+        assert!((combined - weighted).abs() < 1.0, "{} vs {}", combined, weighted);
+        assert!(combined < 5_500_000.0, "the unweighted mean would be 6 MB/s");
+    }
+}
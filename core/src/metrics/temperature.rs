@@ -0,0 +1,66 @@
+use sysinfo::Components;
+
+/// Label fragments of CPU package sensors, most specific first
+const CPU_SENSOR_HINTS: [&str; 5] = ["package", "tctl", "tdie", "cpu", "coretemp"];
+
+/// Every sensor that reports a temperature, as (label, °C)
+pub fn sensor_readings(components: &Components) -> Vec<(String, f32)> {
+    components
+        .iter()
+        .filter_map(|c| c.temperature().map(|t| (c.label().to_string(), t as f32)))
+        .collect()
+}
+
+/// The temperature the mapping follows, or None when there are no readings.
+///
+/// `sensor` picks every label containing it (case-insensitive). Without one,
+/// the CPU package sensor is preferred, so a hot NVMe drive or VRM doesn't
+/// drag the reading. Either way, no match falls back to the average of all.
+pub fn select_temperature(readings: &[(String, f32)], sensor: Option<&str>) -> Option<f32> {
+    let average = |temps: Vec<f32>| (!temps.is_empty()).then(|| temps.iter().sum::<f32>() / temps.len() as f32);
+    let matching = |fragment: &str| {
+        let fragment = fragment.to_lowercase();
+        average(
+            readings
+                .iter()
+                .filter(|(label, _)| label.to_lowercase().contains(&fragment))
+                .map(|&(_, t)| t)
+                .collect(),
+        )
+    };
+
+    let chosen = match sensor {
+        Some(fragment) => matching(fragment),
+        None => CPU_SENSOR_HINTS.iter().find_map(|hint| matching(hint)),
+    };
+    chosen.or_else(|| average(readings.iter().map(|&(_, t)| t).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn readings() -> Vec<(String, f32)> {
+        vec![
+            ("nvme Composite".to_string(), 70.0),
+            ("coretemp Package id 0".to_string(), 50.0),
+            ("coretemp Core 0".to_string(), 48.0),
+            ("coretemp Core 1".to_string(), 52.0),
+        ]
+    }
+
+    #[test]
+    fn test_prefers_cpu_package_sensor() {
+        assert_eq!(select_temperature(&readings(), None), Some(50.0));
+        assert_eq!(select_temperature(&readings(), Some("NVME")), Some(70.0));
+        assert_eq!(select_temperature(&readings(), Some("core ")), Some(50.0));
+    }
+
+    #[test]
+    fn test_falls_back_to_average() {
+        assert_eq!(select_temperature(&readings(), Some("gpu")), Some(55.0));
+        let drives = vec![("nvme Composite".to_string(), 60.0), ("sda".to_string(), 40.0)];
+        assert_eq!(select_temperature(&drives, None), Some(50.0));
+        assert_eq!(select_temperature(&[], None), None);
+    }
+}
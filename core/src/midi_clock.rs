@@ -0,0 +1,145 @@
+use anyhow::{anyhow, bail, Result};
+use midir::{MidiOutput, MidiOutputConnection};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// MIDI clock resolution: pulses per quarter note
+pub const CLOCK_PPQN: u32 = 24;
+
+// System real-time messages
+const TIMING_CLOCK: u8 = 0xF8;
+const START: u8 = 0xFA;
+const STOP: u8 = 0xFC;
+
+/// Names of the MIDI output ports on this machine
+pub fn output_ports() -> Result<Vec<String>> {
+    let output = MidiOutput::new("SysSonic")?;
+    Ok(output.ports().iter().filter_map(|p| output.port_name(p).ok()).collect())
+}
+
+/// Time between clock pulses at `tempo_bpm`
+pub fn tick_interval(tempo_bpm: f32) -> Duration {
+    Duration::from_secs_f32(60.0 / (tempo_bpm.max(1.0) * CLOCK_PPQN as f32))
+}
+
+/// MIDI clock and transport output, so a DAW follows SysSonic's tempo.
+///
+/// Pulses run on their own thread from `connect` until drop, so the DAW
+/// stays locked to the tempo between compositions; `start` and `stop` drive
+/// its transport. Each pulse is timed from the tempo at that moment, so a
+/// new tempo takes effect on the next pulse.
+pub struct MidiClock {
+    connection: Arc<Mutex<MidiOutputConnection>>,
+    tempo: Arc<AtomicU32>, // f32 bits (std has no AtomicF32)
+    shutdown: Arc<AtomicBool>,
+    thread_handle: Option<JoinHandle<()>>,
+    running: bool, // Transport started
+}
+
+impl MidiClock {
+    /// Open the first output port whose name contains `port` (case-insensitive)
+    pub fn connect(port: &str, tempo_bpm: f32) -> Result<Self> {
+        let output = MidiOutput::new("SysSonic")?;
+        let ports = output.ports();
+        let wanted = port.to_lowercase();
+        let Some(found) = ports.iter().find(|p| {
+            output.port_name(p).is_ok_and(|name| name.to_lowercase().contains(&wanted))
+        }) else {
+            let names: Vec<String> = ports.iter().filter_map(|p| output.port_name(p).ok()).collect();
+            let available = if names.is_empty() { "none".to_string() } else { names.join(", ") };
+            bail!("No MIDI output port matching '{}' (available: {})", port, available);
+        };
+
+        let connection = output
+            .connect(found, "syssonic-clock")
+            .map_err(|e| anyhow!("Failed to open MIDI port '{}': {}", port, e))?;
+        let connection = Arc::new(Mutex::new(connection));
+        let tempo = Arc::new(AtomicU32::new(tempo_bpm.to_bits()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let connection_clone = connection.clone();
+        let tempo_clone = tempo.clone();
+        let shutdown_clone = shutdown.clone();
+        let thread_handle = thread::spawn(move || {
+            // Schedule from the previous pulse, not from when the send
+            // returned, so the clock doesn't drift slow
+            let mut next_tick = Instant::now();
+            while !shutdown_clone.load(Ordering::SeqCst) {
+                next_tick += tick_interval(f32::from_bits(tempo_clone.load(Ordering::SeqCst)));
+                match next_tick.checked_duration_since(Instant::now()) {
+                    Some(wait) => thread::sleep(wait),
+                    // Fell behind (suspended, overloaded): resync instead of bursting pulses
+                    None => next_tick = Instant::now(),
+                }
+                if let Err(e) = connection_clone.lock().unwrap().send(&[TIMING_CLOCK]) {
+                    log::warn!("MIDI clock send failed: {}", e);
+                }
+            }
+        });
+
+        Ok(Self {
+            connection,
+            tempo,
+            shutdown,
+            thread_handle: Some(thread_handle),
+            running: false,
+        })
+    }
+
+    /// Pulse at `tempo_bpm` from the next clock tick on
+    pub fn set_tempo(&self, tempo_bpm: f32) {
+        self.tempo.store(tempo_bpm.to_bits(), Ordering::SeqCst);
+    }
+
+    /// Follow `tempo_bpm` and start the transport if it is stopped (call as a composition begins)
+    pub fn start(&mut self, tempo_bpm: f32) -> Result<()> {
+        self.set_tempo(tempo_bpm);
+        if !self.running {
+            self.send(START)?;
+            self.running = true;
+        }
+        Ok(())
+    }
+
+    /// Stop the transport if it is running (call when playback pauses or ends)
+    pub fn stop(&mut self) -> Result<()> {
+        if self.running {
+            self.send(STOP)?;
+            self.running = false;
+        }
+        Ok(())
+    }
+
+    fn send(&self, message: u8) -> Result<()> {
+        self.connection
+            .lock()
+            .unwrap()
+            .send(&[message])
+            .map_err(|e| anyhow!("MIDI send failed: {}", e))
+    }
+}
+
+impl Drop for MidiClock {
+    fn drop(&mut self) {
+        let _ = self.stop();
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_interval_follows_tempo() {
+        // 120 BPM: 48 pulses a second
+        assert!((tick_interval(120.0).as_secs_f64() - 1.0 / 48.0).abs() < 1e-6);
+        assert!(tick_interval(60.0) > tick_interval(130.0));
+        assert!(tick_interval(0.0) <= Duration::from_secs_f32(60.0 / CLOCK_PPQN as f32));
+    }
+}
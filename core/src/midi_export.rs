@@ -0,0 +1,202 @@
+use anyhow::Result;
+use midly::{
+    Format, Header, MetaMessage, MidiMessage, Smf, Timing, TrackEvent, TrackEventKind,
+    num::{u4, u7, u15, u24, u28},
+};
+use serde::{Deserialize, Serialize};
+use tunes::prelude::*;
+use tunes::track::{AudioEvent, Track};
+
+/// MIDI ticks per quarter note
+const PPQ: u16 = 480;
+
+/// General MIDI percussion channel (channel 10, 0-indexed)
+const DRUM_CHANNEL: u8 = 9;
+
+/// MIDI note for each percussion role, for drum samplers that don't follow
+/// General MIDI. Other drum types keep their GM notes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DrumMap {
+    pub kick: u8,    // GM 36 (Bass Drum 1)
+    pub snare: u8,   // GM 38 (Acoustic Snare)
+    pub hihat: u8,   // GM 42 (Closed Hi-Hat)
+    pub shaker: u8,  // GM 70 (Maracas)
+    pub tom: u8,     // GM 47 (Low-Mid Tom)
+    pub rimshot: u8, // GM 37 (Side Stick)
+}
+
+impl Default for DrumMap {
+    fn default() -> Self {
+        Self { kick: 36, snare: 38, hihat: 42, shaker: 70, tom: 47, rimshot: 37 }
+    }
+}
+
+impl DrumMap {
+    /// Note for `drum_type`, kept within MIDI's 0-127
+    fn note(&self, drum_type: DrumType) -> u8 {
+        let note = match drum_type {
+            DrumType::Kick => self.kick,
+            DrumType::Snare => self.snare,
+            DrumType::HiHatClosed => self.hihat,
+            DrumType::Shaker => self.shaker,
+            DrumType::Tom => self.tom,
+            DrumType::Rimshot => self.rimshot,
+            other => drum_type_to_midi_note(other),
+        };
+        note.min(127)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NoteMessage {
+    On { key: u8, velocity: u8 },
+    Off { key: u8 },
+}
+
+/// Export a mixer to a Standard MIDI File with one channel per voice.
+///
+/// `Mixer::export_midi` puts every melodic track on channel 1, which collapses
+/// the arrangement into a single instrument on DAW import. Here each SysSonic
+/// voice gets its own channel and a General MIDI program, and every percussion
+/// track lands on channel 10, with drum notes from `drum_map`.
+pub fn export_midi(mixer: &Mixer, path: &str, drum_map: &DrumMap) -> Result<()> {
+    let bpm = mixer.tempo.bpm;
+    let mut tracks = Vec::new();
+
+    // Track 0: tempo and time signature
+    let us_per_quarter_note = (60_000_000.0 / bpm) as u32;
+    tracks.push(vec![
+        TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::Tempo(u24::new(us_per_quarter_note))),
+        },
+        TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::TimeSignature(4, 2, 24, 8)), // 4/4
+        },
+        TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        },
+    ]);
+
+    let mut next_channel = 0u8;
+    for track in &mixer.tracks {
+        let name = track.name.as_deref().unwrap_or("Track");
+        let is_percussion = track.events.iter().any(|e| matches!(e, AudioEvent::Drum(_)));
+
+        // Melodic voices take channels in order, skipping the drum channel
+        let channel = if is_percussion {
+            DRUM_CHANNEL
+        } else {
+            let channel = next_channel;
+            next_channel = (next_channel + 1) % 16;
+            if next_channel == DRUM_CHANNEL {
+                next_channel += 1;
+            }
+            channel
+        };
+
+        let mut midi_track = vec![TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::TrackName(name.as_bytes())),
+        }];
+
+        if !is_percussion {
+            midi_track.push(TrackEvent {
+                delta: u28::new(0),
+                kind: TrackEventKind::Midi {
+                    channel: u4::new(channel),
+                    message: MidiMessage::ProgramChange { program: u7::new(gm_program(name)) },
+                },
+            });
+        }
+
+        let mut last_tick = 0u32;
+        for (tick, note) in note_messages(track, bpm, drum_map) {
+            let delta = tick.saturating_sub(last_tick);
+            last_tick = tick;
+
+            let message = match note {
+                NoteMessage::On { key, velocity } => MidiMessage::NoteOn { key: u7::new(key), vel: u7::new(velocity) },
+                NoteMessage::Off { key } => MidiMessage::NoteOff { key: u7::new(key), vel: u7::new(0) },
+            };
+            midi_track.push(TrackEvent {
+                delta: u28::new(delta),
+                kind: TrackEventKind::Midi { channel: u4::new(channel), message },
+            });
+        }
+
+        midi_track.push(TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        });
+        tracks.push(midi_track);
+    }
+
+    let smf = Smf {
+        header: Header {
+            format: Format::Parallel,
+            timing: Timing::Metrical(u15::new(PPQ)),
+        },
+        tracks,
+    };
+    smf.save(path)?;
+
+    Ok(())
+}
+
+/// General MIDI program (0-indexed) for each SysSonic voice
+fn gm_program(track_name: &str) -> u8 {
+    match track_name {
+        "melody" => 81,                          // Lead 2 (sawtooth)
+        "bass" => 38,                            // Synth Bass 1
+        "gpu_rumble" => 39,                      // Synth Bass 2
+        "swap_burst" => 30,                      // Distortion Guitar
+        "pad" => 89,                             // Pad 2 (warm)
+        "gpu" => 90,                             // Pad 3 (polysynth)
+        "arp" => 45,                             // Pizzicato Strings
+        "connections" => 46,                     // Orchestral Harp
+        "battery_warning" => 9,                  // Glockenspiel
+        "fans" => 122,                           // Seashore
+        name if name.starts_with("proc_") => 10, // Music Box
+        _ => 80,                                 // Lead 1 (square)
+    }
+}
+
+/// Flatten a track's notes and drum hits into time-sorted note on/off messages
+fn note_messages(track: &Track, bpm: f32, drum_map: &DrumMap) -> Vec<(u32, NoteMessage)> {
+    let mut messages = Vec::new();
+
+    for event in &track.events {
+        match event {
+            AudioEvent::Note(note) => {
+                let start = seconds_to_ticks(note.start_time, bpm);
+                let end = seconds_to_ticks(note.start_time + note.duration, bpm).max(start + 1);
+                let velocity = ((note.velocity * 127.0).round() as u8).clamp(1, 127);
+
+                for &freq in &note.frequencies[..note.num_freqs] {
+                    let key = frequency_to_midi_note(freq);
+                    messages.push((start, NoteMessage::On { key, velocity }));
+                    messages.push((end, NoteMessage::Off { key }));
+                }
+            }
+            AudioEvent::Drum(drum) => {
+                let key = drum_map.note(drum.drum_type);
+                let start = seconds_to_ticks(drum.start_time, bpm);
+                messages.push((start, NoteMessage::On { key, velocity: 100 }));
+                messages.push((start + (PPQ / 8) as u32, NoteMessage::Off { key }));
+            }
+            _ => {}
+        }
+    }
+
+    // Note-offs sort before note-ons on the same tick so repeated notes retrigger
+    messages.sort_by_key(|(tick, message)| (*tick, matches!(message, NoteMessage::On { .. })));
+    messages
+}
+
+fn seconds_to_ticks(time: f32, bpm: f32) -> u32 {
+    (time * (bpm / 60.0) * PPQ as f32).round() as u32
+}
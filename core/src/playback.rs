@@ -0,0 +1,265 @@
+use crate::audio_export;
+use crate::composer::ShapedMix;
+use anyhow::{anyhow, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Frames rendered between halt checks (~0.1s at 44.1kHz)
+const RENDER_CHUNK_FRAMES: usize = 4096;
+
+/// Audio rendered ahead before the stream starts, to avoid underruns
+const PREBUFFER_SECS: f32 = 0.5;
+
+/// How often a playing composition checks its halt flag
+const HALT_POLL: Duration = Duration::from_millis(10);
+
+/// Output samples kept for waveform views (~0.37s at 44.1kHz)
+pub const WAVEFORM_CAPACITY: usize = 16384;
+
+/// Most recent output samples, for an oscilloscope view.
+///
+/// Written from the stream callback, which must never block on a reader, so
+/// it is a ring of f32 bits plus a running write count rather than a locked
+/// buffer. One stream writes at a time; a read racing the writer may pick up
+/// a few samples from a moment later, which no display can show.
+#[derive(Clone)]
+pub struct WaveformTap {
+    samples: Arc<[AtomicU32]>,
+    written: Arc<AtomicUsize>, // Samples pushed so far; the next goes at `written % capacity`
+}
+
+impl Default for WaveformTap {
+    fn default() -> Self {
+        Self {
+            samples: (0..WAVEFORM_CAPACITY).map(|_| AtomicU32::new(0)).collect(),
+            written: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl WaveformTap {
+    /// Append one output sample (from the stream callback)
+    fn push(&self, sample: f32) {
+        let written = self.written.load(Ordering::Relaxed);
+        self.samples[written % WAVEFORM_CAPACITY].store(sample.to_bits(), Ordering::Relaxed);
+        self.written.store(written + 1, Ordering::Release);
+    }
+
+    /// The last `num_samples` samples played (at most `WAVEFORM_CAPACITY`),
+    /// oldest first, in -1..1.
+    ///
+    /// Samples are the mono mix of what the device played, at its rate;
+    /// silence (not yet played, halted, underrun) reads as 0.0.
+    pub fn recent(&self, num_samples: usize) -> Vec<f32> {
+        let written = self.written.load(Ordering::Acquire);
+        let count = num_samples.min(WAVEFORM_CAPACITY);
+        // Slots not yet written still hold their initial 0.0
+        let start = written + WAVEFORM_CAPACITY - count;
+        (start..start + count)
+            .map(|i| f32::from_bits(self.samples[i % WAVEFORM_CAPACITY].load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+/// There is no output device to play on (headless servers, containers).
+///
+/// Kept distinct from other playback errors so callers can fall back to
+/// export-only operation instead of surfacing a raw error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoAudioDevice;
+
+impl fmt::Display for NoAudioDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "No audio output device available")
+    }
+}
+
+impl std::error::Error for NoAudioDevice {}
+
+/// Output device that streams a mix and can be silenced mid-composition.
+///
+/// tunes' `AudioEngine::play_mixer` sleeps for the whole composition with no way
+/// to interrupt it, so playback drives cpal directly and polls a halt flag instead.
+pub struct AudioOutput {
+    device: cpal::Device,
+}
+
+impl AudioOutput {
+    /// Open the default output device
+    pub fn open() -> Result<Self> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or(NoAudioDevice)?;
+        Ok(Self { device })
+    }
+
+    /// Play `mix` to completion, or until `halt` is set.
+    ///
+    /// Renders at `sample_rate` when the device supports it, otherwise at the
+    /// device default; likewise for `buffer_size` frames (None = device default).
+    /// Once halted the stream outputs silence immediately and is dropped within
+    /// a few milliseconds, releasing the device. Everything played is also
+    /// pushed to `waveform`. Returns the RMS level of the audio rendered so far.
+    pub fn play(&self, mix: &ShapedMix, sample_rate: u32, buffer_size: Option<u32>, halt: &Arc<AtomicBool>, waveform: &WaveformTap) -> Result<f32> {
+        let supported = self.stream_config(sample_rate)?;
+        let mut config: cpal::StreamConfig = supported.config();
+        if let Some(frames) = buffer_size {
+            match supported.buffer_size() {
+                cpal::SupportedBufferSize::Range { min, max } if frames < *min || frames > *max => {
+                    log::warn!("Output device doesn't support a {}-frame buffer ({}-{}), using its default", frames, min, max);
+                }
+                _ => config.buffer_size = cpal::BufferSize::Fixed(frames),
+            }
+        }
+
+        match supported.sample_format() {
+            cpal::SampleFormat::F32 => self.stream::<f32>(&config, mix, halt, waveform),
+            cpal::SampleFormat::I16 => self.stream::<i16>(&config, mix, halt, waveform),
+            cpal::SampleFormat::U16 => self.stream::<u16>(&config, mix, halt, waveform),
+            format => Err(anyhow!("Unsupported sample format: {:?}", format)),
+        }
+    }
+
+    /// Default device config, moved to `sample_rate` if the device allows it
+    fn stream_config(&self, sample_rate: u32) -> Result<cpal::SupportedStreamConfig> {
+        let default = self.device.default_output_config()?;
+        if default.sample_rate().0 == sample_rate {
+            return Ok(default);
+        }
+
+        let matching = self
+            .device
+            .supported_output_configs()?
+            .filter(|c| c.channels() == default.channels() && c.sample_format() == default.sample_format())
+            .find(|c| c.min_sample_rate().0 <= sample_rate && sample_rate <= c.max_sample_rate().0);
+
+        match matching {
+            Some(range) => Ok(range.with_sample_rate(cpal::SampleRate(sample_rate))),
+            None => {
+                log::warn!(
+                    "Output device doesn't support {} Hz, playing at {} Hz",
+                    sample_rate,
+                    default.sample_rate().0
+                );
+                Ok(default)
+            }
+        }
+    }
+
+    fn stream<T>(&self, config: &cpal::StreamConfig, mix: &ShapedMix, halt: &Arc<AtomicBool>, waveform: &WaveformTap) -> Result<f32>
+    where
+        T: cpal::SizedSample + cpal::FromSample<f32>,
+    {
+        let sample_rate = config.sample_rate.0 as f32;
+        let channels = config.channels as usize;
+        let duration = mix.total_duration();
+        let total_frames = (duration * sample_rate).ceil() as usize;
+
+        // Interleaved stereo, filled by the render thread while the stream drains it
+        let rendered = Arc::new(Mutex::new(Vec::<f32>::with_capacity(total_frames * 2)));
+
+        let renderer = {
+            let mut mix = mix.clone();
+            let rendered = rendered.clone();
+            let halt = halt.clone();
+            thread::spawn(move || {
+                let mut chunk = Vec::with_capacity(RENDER_CHUNK_FRAMES * 2);
+                let mut sample_clock = 0.0;
+                for frame in 0..total_frames {
+                    let (left, right) = mix.sample_at(frame as f32 / sample_rate, sample_rate, sample_clock);
+                    chunk.push(left.clamp(-1.0, 1.0));
+                    chunk.push(right.clamp(-1.0, 1.0));
+                    sample_clock = (sample_clock + 1.0) % sample_rate;
+
+                    if chunk.len() >= RENDER_CHUNK_FRAMES * 2 {
+                        if halt.load(Ordering::SeqCst) {
+                            return;
+                        }
+                        rendered.lock().unwrap().append(&mut chunk);
+                    }
+                }
+                rendered.lock().unwrap().append(&mut chunk);
+            })
+        };
+
+        let prebuffer = ((PREBUFFER_SECS * sample_rate) as usize).min(total_frames) * 2;
+        while rendered.lock().unwrap().len() < prebuffer
+            && !halt.load(Ordering::SeqCst)
+            && !renderer.is_finished()
+        {
+            thread::sleep(HALT_POLL);
+        }
+
+        let playback_buffer = rendered.clone();
+        let playback_halt = halt.clone();
+        let playback_waveform = waveform.clone();
+        let mut frame_index = 0;
+        let stream = self.device.build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                let halted = playback_halt.load(Ordering::Relaxed);
+                let buffer = playback_buffer.lock().unwrap();
+
+                for frame in data.chunks_mut(channels) {
+                    let index = frame_index * 2;
+                    // Silence once halted, or if rendering fell behind
+                    let (left, right) = if halted || index + 1 >= buffer.len() {
+                        (0.0, 0.0)
+                    } else {
+                        frame_index += 1;
+                        (buffer[index], buffer[index + 1])
+                    };
+                    playback_waveform.push((left + right) * 0.5);
+
+                    if channels == 1 {
+                        frame[0] = T::from_sample((left + right) * 0.5);
+                    } else {
+                        frame[0] = T::from_sample(left);
+                        frame[1] = T::from_sample(right);
+                        for sample in frame.iter_mut().skip(2) {
+                            *sample = T::from_sample(0.0f32);
+                        }
+                    }
+                }
+            },
+            |err| log::warn!("Audio stream error: {}", err),
+            None,
+        )?;
+        stream.play()?;
+
+        let deadline = Instant::now() + Duration::from_secs_f32(duration);
+        while Instant::now() < deadline && !halt.load(Ordering::SeqCst) {
+            thread::sleep(HALT_POLL);
+        }
+
+        // Dropping the stream releases the device
+        drop(stream);
+        let _ = renderer.join();
+
+        let level = audio_export::rms(&rendered.lock().unwrap());
+        Ok(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waveform_tap_keeps_the_latest_samples() {
+        let tap = WaveformTap::default();
+        assert_eq!(tap.recent(4), vec![0.0; 4], "silence before anything plays");
+
+        for i in 0..WAVEFORM_CAPACITY + 10 {
+            tap.push(i as f32 / WAVEFORM_CAPACITY as f32);
+        }
+        let recent = tap.recent(3);
+        let last = |back: usize| (WAVEFORM_CAPACITY + 10 - back) as f32 / WAVEFORM_CAPACITY as f32;
+        assert_eq!(recent, vec![last(3), last(2), last(1)]);
+        assert_eq!(tap.recent(usize::MAX).len(), WAVEFORM_CAPACITY);
+    }
+}
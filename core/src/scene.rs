@@ -0,0 +1,95 @@
+use crate::config::AppConfig;
+use crate::mapper::MusicalParams;
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// Named `MusicalParams` saved to disk and recalled later, whatever the
+/// machine is doing by then. One JSON file per scene.
+pub struct SceneLibrary {
+    dir: PathBuf,
+}
+
+impl SceneLibrary {
+    /// Scenes in the `scenes` folder of the config directory
+    pub fn open() -> Result<Self> {
+        Ok(Self::in_dir(AppConfig::config_dir()?.join("scenes")))
+    }
+
+    /// Scenes in `dir` (created on the first save)
+    pub fn in_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Save `params` as `name`, replacing any scene of that name
+    pub fn save(&self, name: &str, params: &MusicalParams) -> Result<()> {
+        let path = self.path(name)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(path, serde_json::to_string_pretty(params)?)?;
+        Ok(())
+    }
+
+    pub fn load(&self, name: &str) -> Result<MusicalParams> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            bail!("No scene named '{}'", name);
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Names of the saved scenes, sorted
+    pub fn list(&self) -> Result<Vec<String>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut names: Vec<String> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// File for `name`, which must be a plain file name (no separators)
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let name = name.trim();
+        let valid = !name.is_empty()
+            && name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'))
+            && !name.starts_with('.');
+        if !valid {
+            bail!("Invalid scene name '{}' (use letters, digits, spaces, '-', '_' or '.')", name);
+        }
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenes_round_trip() {
+        let dir = std::env::temp_dir().join("syssonic_scene_test");
+        let _ = fs::remove_dir_all(&dir);
+        let scenes = SceneLibrary::in_dir(dir.clone());
+        assert!(scenes.list().unwrap().is_empty());
+
+        let params = MusicalParams { tempo: 117.0, kick_hits: vec![0, 8], ..Default::default() };
+        scenes.save("build storm", &params).unwrap();
+        scenes.save("idle", &MusicalParams::default()).unwrap();
+
+        assert_eq!(scenes.list().unwrap(), vec!["build storm", "idle"]);
+        let loaded = scenes.load("build storm").unwrap();
+        assert_eq!(loaded.tempo, 117.0);
+        assert_eq!(loaded.kick_hits, vec![0, 8]);
+
+        assert!(scenes.load("missing").is_err());
+        assert!(scenes.save("../escape", &params).is_err());
+        assert!(scenes.save("", &params).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
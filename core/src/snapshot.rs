@@ -0,0 +1,166 @@
+use crate::config::AppConfig;
+use crate::mapper::{MetricsMapper, MusicalParams};
+use crate::metrics::{MetricsCollector, SystemMetrics};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Platform details included in bug reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemInfo {
+    pub platform: String,
+    pub arch: String,
+    pub version: String, // SysSonic version
+}
+
+impl SystemInfo {
+    pub fn current() -> Self {
+        Self {
+            platform: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Everything needed to reproduce "the music sounds wrong on my machine":
+/// the raw metrics, the params derived from them, and the settings in effect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugSnapshot {
+    pub system_info: SystemInfo,
+    pub config: AppConfig,
+    pub metrics: SystemMetrics,
+    pub params: MusicalParams,
+}
+
+impl DebugSnapshot {
+    /// Collect metrics the same way playback does and map them
+    pub fn capture(collector: &mut MetricsCollector, mapper: &MetricsMapper, config: &AppConfig) -> Self {
+        let (samples, interval_ms) = config.sampling();
+        let metrics = collector.collect_averaged(samples, interval_ms);
+        let params = mapper.map(&metrics);
+
+        Self {
+            system_info: SystemInfo::current(),
+            config: config.clone(),
+            metrics,
+            params,
+        }
+    }
+
+    /// Write the snapshot as pretty-printed JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Load a snapshot from a bug report for replay
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+/// Sidecar JSON written next to an export (`foo.wav` → `foo.wav.json`)
+/// recording exactly how it was made, so the file documents itself and its
+/// params can be rendered again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub system_info: SystemInfo,
+    pub format: String,
+    pub bars: usize,         // Per segment, not counting the intro
+    pub intro_bars: usize,
+    pub preset: String,      // Focus mix ("none" = balanced)
+    pub seed: Option<u64>,   // Random-walk melody seed (None = fixed contour)
+    pub segments: Vec<ManifestSegment>, // One per snapshot, in order
+}
+
+/// One composition of an export: the metrics it was mapped from and the result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSegment {
+    pub metrics: Option<SystemMetrics>, // None when exported from saved params
+    pub params: MusicalParams,
+    pub key: String,   // e.g. "A minor"
+    pub scale: String, // e.g. "minor pentatonic"
+    pub tempo: f32,
+}
+
+impl ManifestSegment {
+    pub fn new(metrics: Option<SystemMetrics>, params: MusicalParams) -> Self {
+        Self {
+            key: params.context.key_signature.clone(),
+            scale: params.context.scale_name.clone(),
+            tempo: params.tempo,
+            metrics,
+            params,
+        }
+    }
+}
+
+impl ExportManifest {
+    /// Sidecar path for the export at `export_path`
+    pub fn path_for(export_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.json", export_path))
+    }
+
+    /// Write the manifest as pretty-printed JSON next to `export_path`
+    pub fn save_beside(&self, export_path: &str) -> Result<()> {
+        fs::write(Self::path_for(export_path), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let config = AppConfig { sample_count: 1, ..AppConfig::default() };
+        let mut collector = config.build_collector();
+        let snapshot = DebugSnapshot::capture(&mut collector, &config.build_mapper(), &config);
+
+        let path = std::env::temp_dir().join("syssonic_snapshot_test.json");
+        snapshot.save(&path).unwrap();
+        let loaded = DebugSnapshot::load(&path).unwrap();
+
+        assert_eq!(loaded.params.tempo, snapshot.params.tempo);
+        assert_eq!(loaded.metrics.per_core_usage.len(), snapshot.metrics.per_core_usage.len());
+        assert_eq!(loaded.config.sample_count, 1);
+        assert_eq!(loaded.system_info.platform, std::env::consts::OS);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_manifest_sits_beside_the_export() {
+        let export = std::env::temp_dir().join("syssonic_manifest_test.wav");
+        let export = export.to_string_lossy();
+        let params = MusicalParams { tempo: 104.0, ..Default::default() };
+        let manifest = ExportManifest {
+            system_info: SystemInfo::current(),
+            format: "wav".to_string(),
+            bars: 8,
+            intro_bars: 0,
+            preset: "none".to_string(),
+            seed: None,
+            segments: vec![ManifestSegment::new(None, params)],
+        };
+
+        manifest.save_beside(&export).unwrap();
+        let path = ExportManifest::path_for(&export);
+        assert!(path.to_string_lossy().ends_with("syssonic_manifest_test.wav.json"));
+        let loaded = ExportManifest::load(&path).unwrap();
+        assert_eq!(loaded.segments.len(), 1);
+        assert_eq!(loaded.segments[0].tempo, 104.0);
+        assert_eq!(loaded.segments[0].params.tempo, 104.0);
+
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use tunes::prelude::*;
+use tunes::track::AudioEvent;
+
+/// One scheduled note or drum hit, for piano-roll style visualizers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteEvent {
+    pub instrument: String,   // Track name, e.g. "melody", "drums", "proc_firefox"
+    pub pitch: Option<f32>,   // Hz (None for drum hits)
+    pub drum: Option<String>, // Drum type for hits, e.g. "kick"
+    pub start: f32,           // seconds from the start of the composition
+    pub duration: f32,        // seconds (0.0 for drum hits)
+    pub velocity: f32,        // 0.0-1.0
+}
+
+/// Flatten a mixed composition into time-sorted note events.
+///
+/// Chords become one event per pitch, so each row of a piano roll is one key.
+pub fn note_events(mixer: &Mixer) -> Vec<NoteEvent> {
+    let mut events = Vec::new();
+
+    for track in &mixer.tracks {
+        let instrument = track.name.clone().unwrap_or_else(|| "track".to_string());
+
+        for event in &track.events {
+            match event {
+                AudioEvent::Note(note) => {
+                    for &freq in &note.frequencies[..note.num_freqs] {
+                        events.push(NoteEvent {
+                            instrument: instrument.clone(),
+                            pitch: Some(freq),
+                            drum: None,
+                            start: note.start_time,
+                            duration: note.duration,
+                            velocity: note.velocity,
+                        });
+                    }
+                }
+                AudioEvent::Drum(drum) => events.push(NoteEvent {
+                    instrument: instrument.clone(),
+                    pitch: None,
+                    drum: Some(format!("{:?}", drum.drum_type).to_lowercase()),
+                    start: drum.start_time,
+                    duration: 0.0,
+                    velocity: 1.0,
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    events.sort_by(|a, b| a.start.total_cmp(&b.start));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::composer::SystemComposer;
+    use crate::mapper::MusicalParams;
+
+    #[test]
+    fn test_timeline_lists_notes_and_hits_in_order() {
+        let params = MusicalParams {
+            tempo: 120.0,
+            melody_notes: vec![A4, C5],
+            bass_note: A2,
+            bass_velocity: 0.5,
+            kick_hits: vec![0, 8],
+            ..Default::default()
+        };
+        let events = SystemComposer::new_silent().arrange(&params, 1);
+
+        assert!(events.windows(2).all(|pair| pair[0].start <= pair[1].start));
+        assert!(events.iter().any(|e| e.instrument == "melody" && e.pitch == Some(A4)));
+        assert!(events.iter().any(|e| e.instrument == "drums" && e.drum.is_some() && e.pitch.is_none()));
+    }
+}
@@ -1,18 +1,112 @@
-mod metrics;
-mod mapper;
-mod composer;
-
-use metrics::MetricsCollector;
-use mapper::MetricsMapper;
-use composer::{SystemComposer, ExportFormat};
+use syssonic_core::metrics::{self, Aggregation, EnvelopeFollower, EnvelopeSettings, MetricsCollector, MetricsSource, ProcessSort, ReplaySource, Smoothing};
+use syssonic_core::mapper::{MetricsMapper, MusicalParams, Scale};
+use syssonic_core::midi_clock::MidiClock;
+use syssonic_core::composer::{self, BarClock, EffectSettings, ExportFormat, Focus, NoAudioDevice, SystemComposer, VoiceLimit};
+use syssonic_core::auto_gain::{AutoGain, AutoGainSettings};
+use syssonic_core::config::AppConfig;
+use syssonic_core::idle::{IdleDetector, IdleThreshold};
+use syssonic_core::scene::SceneLibrary;
+use syssonic_core::snapshot::{DebugSnapshot, ExportManifest, ManifestSegment, SystemInfo};
 use clap::{Parser, Subcommand};
-use anyhow::Result;
-use std::time::Duration;
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// How much status output the CLI prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Verbosity {
+    Quiet,   // Errors only
+    Normal,  // Status lines
+    Verbose, // Status lines + info-level logs (mapping table, sensor init)
+}
+
+/// Print a status line unless running with --quiet
+macro_rules! status {
+    ($verbosity:expr, $($arg:tt)*) => {
+        if $verbosity >= Verbosity::Normal {
+            println!($($arg)*);
+        }
+    };
+}
 
 #[derive(Parser)]
 #[command(name = "syssonic")]
 #[command(about = "Turn your system metrics into music", long_about = None)]
 struct Cli {
+    /// Number of top processes collected for the process melodies
+    #[arg(long, global = true, default_value_t = 5)]
+    process_voices: usize,
+
+    /// Rank processes by: cpu, memory, combined, or io (disk reads + writes)
+    #[arg(long, global = true, default_value = "cpu")]
+    process_sort: String,
+
+    /// How CPU samples are combined: mean, max (catch spikes), min, or last
+    #[arg(long, global = true, default_value = "mean")]
+    cpu_smoothing: String,
+
+    /// How disk/network samples are combined: max (bursty), mean, min, or last
+    #[arg(long, global = true, default_value = "max")]
+    io_smoothing: String,
+
+    /// Milliseconds between the readings combined into each sample
+    #[arg(long, global = true, default_value_t = 200)]
+    sample_interval: u64,
+
+    /// Weight (0-1) of each new reading in a running average across compositions and
+    /// snapshots, instead of a fresh window of --samples readings (lower = smoother, slower)
+    #[arg(long, global = true)]
+    ema: Option<f32>,
+
+    /// Seconds over which temperature readings are averaged to hide sensor jitter (0 = off)
+    #[arg(long, global = true, default_value_t = 10.0)]
+    temp_smoothing: f32,
+
+    /// Follow the temperature sensors whose label contains this (default: the CPU package).
+    /// `syssonic doctor` lists the labels
+    #[arg(long, global = true)]
+    temp_sensor: Option<String>,
+
+    /// Output sample rate for playback and exports: 44100, 48000, or 96000
+    #[arg(long, global = true, default_value_t = composer::DEFAULT_SAMPLE_RATE)]
+    sample_rate: u32,
+
+    /// Playback buffer in frames, a power of two from 64 to 8192 (default: device default).
+    /// Raise it if playback stutters; lower it for quicker starts and stops
+    #[arg(long, global = true)]
+    buffer_size: Option<u32>,
+
+    /// Export bit depth: 16 or 24 (default: 16 for WAV, 24 for FLAC)
+    #[arg(long, global = true)]
+    bit_depth: Option<u16>,
+
+    /// Scale every reverb/delay/chorus mix: 0 = dry, 1 = as composed
+    #[arg(long, global = true, default_value_t = 1.0)]
+    wet_mix: f32,
+
+    /// Most layers playing at once; the least important drop first (0 = unlimited)
+    #[arg(long, global = true, default_value_t = 12)]
+    max_voices: usize,
+
+    /// Melody scale: minor_pentatonic, major, minor, blues, dorian, or chromatic
+    /// (defaults to the config file's scale_type)
+    #[arg(long, global = true)]
+    scale: Option<String>,
+
+    /// Feature one subsystem and fade the others: cpu, memory, disk, network, or gpu
+    #[arg(long, global = true)]
+    focus: Option<String>,
+
+    /// Suppress all status output
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Also log the mapping table and sensor initialization (same as RUST_LOG=info)
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -25,13 +119,49 @@ enum Commands {
         #[arg(short, long, default_value_t = 4)]
         bars: usize,
 
-        /// Seconds between updates (default: 16)
-        #[arg(short, long, default_value_t = 16.0)]
+        /// Number of samples to average per composition (default: 3)
+        #[arg(short, long, default_value_t = 3)]
+        samples: usize,
+
+        /// Seconds to wait after each composition (default: 0, back to back)
+        #[arg(short, long, default_value_t = 0.0)]
         interval: f32,
 
         /// Number of iterations (0 = infinite)
         #[arg(short, long, default_value_t = 0)]
         count: usize,
+
+        /// Stop after this many seconds, finishing the current composition (0 = no limit)
+        #[arg(long, default_value_t = 0.0)]
+        max_duration: f32,
+
+        /// Time constant in seconds for tempo/filter/reverb to glide toward new values (0 = jump)
+        #[arg(long, default_value_t = 20.0)]
+        slew: f32,
+
+        /// Start each composition on the next downbeat, keeping the bar grid across waits
+        #[arg(long)]
+        quantize: bool,
+
+        /// Send MIDI clock and start/stop to the output port whose name contains this, so a DAW follows the tempo
+        #[arg(long, value_name = "PORT")]
+        midi_clock: Option<String>,
+
+        /// Sonify a recorded session (one JSON metrics sample per line) instead of this system
+        #[arg(long)]
+        replay: Option<std::path::PathBuf>,
+
+        /// Skip compositions once CPU, disk, network and GPU have been quiet this many seconds (0 = always play)
+        #[arg(long, default_value_t = 0.0)]
+        idle_after: f32,
+
+        /// Hold the params of this composition (1 = the first) and keep replaying them (0 = never)
+        #[arg(long, default_value_t = 0)]
+        freeze_after: usize,
+
+        /// Play a saved scene (see `syssonic scene`) instead of this system's metrics
+        #[arg(long, conflicts_with = "replay")]
+        scene: Option<String>,
     },
 
     /// Capture a snapshot and export to file
@@ -40,10 +170,14 @@ enum Commands {
         #[arg(short, long)]
         output: String,
 
-        /// Export format: wav, flac, or midi
+        /// Export format: wav, flac, midi, mp3, or ogg
         #[arg(short, long, default_value = "wav")]
         format: String,
 
+        /// Bitrate in kbps for mp3 and ogg exports
+        #[arg(long, default_value_t = composer::DEFAULT_BITRATE_KBPS)]
+        bitrate: u32,
+
         /// Number of bars to generate (default: 8)
         #[arg(short, long, default_value_t = 8)]
         bars: usize,
@@ -51,6 +185,48 @@ enum Commands {
         /// Number of samples to average (default: 5)
         #[arg(short, long, default_value_t = 5)]
         samples: usize,
+
+        /// Stereo width: 0 = mono, 1 = normal, up to 2 = extra wide
+        #[arg(long, default_value_t = 1.0)]
+        stereo_width: f32,
+
+        /// Sum the export to a single channel
+        #[arg(long)]
+        mono: bool,
+
+        /// Fold everything below this crossover (Hz, e.g. 120) to mono, keeping highs stereo
+        #[arg(long)]
+        bass_mono: Option<f32>,
+
+        /// Bars of intro (0-2) where voices enter one at a time before the full pattern
+        #[arg(long, default_value_t = 0)]
+        intro: usize,
+
+        /// Take this many snapshots and join their compositions into one evolving track
+        #[arg(long, default_value_t = 1)]
+        snapshots: usize,
+
+        /// Seconds between snapshots
+        #[arg(long, default_value_t = 60.0)]
+        snapshot_interval: f32,
+
+        /// Export a saved scene (see `syssonic scene`) instead of sampling this system
+        #[arg(long, conflicts_with = "snapshots")]
+        scene: Option<String>,
+
+        /// Write a sidecar `<output>.json` with the metrics, params and settings behind the export
+        #[arg(long)]
+        manifest: bool,
+
+        /// Render the params recorded in an export manifest again instead of sampling this system
+        #[arg(long, value_name = "MANIFEST", conflicts_with_all = ["snapshots", "scene"])]
+        from_manifest: Option<std::path::PathBuf>,
+    },
+
+    /// Save the current machine state as a named scene, or list saved scenes
+    Scene {
+        #[command(subcommand)]
+        action: SceneAction,
     },
 
     /// Show current system metrics (no audio)
@@ -62,134 +238,769 @@ enum Commands {
         /// Number of iterations (0 = infinite)
         #[arg(short, long, default_value_t = 0)]
         count: usize,
+
+        /// Append each sample as a JSON line, for `live --replay`
+        #[arg(long)]
+        record: Option<std::path::PathBuf>,
+    },
+
+    /// Capture metrics, musical params and settings as JSON for bug reports
+    Snapshot {
+        /// Output file path
+        #[arg(short, long, default_value = "syssonic-debug.json")]
+        output: String,
+
+        /// Number of samples to average (default: 3)
+        #[arg(short, long, default_value_t = 3)]
+        samples: usize,
+    },
+
+    /// Time collect/map/render stages (no playback)
+    Bench {
+        /// Number of timed iterations
+        #[arg(short, long, default_value_t = 50)]
+        iterations: usize,
+
+        /// Bars rendered per iteration
+        #[arg(short, long, default_value_t = 1)]
+        bars: usize,
+    },
+
+    /// Play a narrated tour of the mappings on scripted metrics
+    /// (idle, CPU ramp, disk burst, GPU load, low battery, thermal alarm)
+    Demo {
+        /// Bars played per scene
+        #[arg(short, long, default_value_t = 4)]
+        bars: usize,
+
+        /// Render every scene without an audio device (smoke test)
+        #[arg(long)]
+        silent: bool,
     },
 
     /// Test audio setup with a simple composition
     Test,
+
+    /// Report which optional sensors (GPU, battery, fans, temperature) and the audio device work here
+    Doctor,
+
+    /// Collect every metric once and print each value, grouped by subsystem,
+    /// marked as measured, fallback or unavailable on this machine
+    ListMetrics,
+
+    /// Run the sonification engine headless, controlled over a Unix socket
+    /// (commands: play, stop, panic, freeze, unfreeze, volume <0.0-1.0>, status)
+    #[cfg(unix)]
+    Daemon {
+        /// Socket path (default: $XDG_RUNTIME_DIR/syssonic.sock)
+        #[arg(long)]
+        socket: Option<std::path::PathBuf>,
+
+        /// Render compositions without an audio device
+        #[arg(long)]
+        silent: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SceneAction {
+    /// Map the current metrics and save the params under NAME
+    Save {
+        name: String,
+
+        /// Number of samples to average (default: 3)
+        #[arg(short, long, default_value_t = 3)]
+        samples: usize,
+    },
+
+    /// List saved scenes
+    List,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    let verbosity = if cli.quiet {
+        Verbosity::Quiet
+    } else if cli.verbose {
+        Verbosity::Verbose
+    } else {
+        Verbosity::Normal
+    };
+
+    // Diagnostics go to stderr via the logger; RUST_LOG overrides the default level
+    let default_log_level = match verbosity {
+        Verbosity::Quiet => "error",
+        Verbosity::Normal => "warn",
+        Verbosity::Verbose => "info",
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_log_level)).init();
+
+    let process_sort = ProcessSort::from_name(&cli.process_sort).unwrap_or_else(|| {
+        eprintln!("❌ Unknown process sort '{}'. Using cpu.", cli.process_sort);
+        ProcessSort::Cpu
+    });
+    let aggregation = |name: &str, fallback: Aggregation| {
+        Aggregation::from_name(name).unwrap_or_else(|| {
+            eprintln!("❌ Unknown smoothing '{}'. Using {:?}.", name, fallback);
+            fallback
+        })
+    };
+    let io = aggregation(&cli.io_smoothing, Aggregation::Max);
+    let smoothing = Smoothing {
+        cpu: aggregation(&cli.cpu_smoothing, Aggregation::Mean),
+        disk: io,
+        network: io,
+        ..Smoothing::default()
+    };
+
+    composer::validate_output_format(cli.sample_rate, cli.bit_depth)?;
+    if let Some(frames) = cli.buffer_size {
+        composer::validate_buffer_size(frames)?;
+    }
+    let focus = cli.focus.as_deref().and_then(|name| {
+        let focus = Focus::from_name(name);
+        if focus.is_none() {
+            eprintln!("❌ Unknown focus '{}'. Using a balanced mix.", name);
+        }
+        focus
+    });
+    // Persistent settings (layers, scale, ...) shared with the tray and daemon
+    let config = AppConfig::load().unwrap_or_default();
+    let scale = cli.scale.clone().unwrap_or_else(|| config.scale_type.clone());
+    if Scale::from_name(&scale).is_none() {
+        eprintln!("❌ Unknown scale '{}'. Using minor pentatonic.", scale);
+    }
+    let mapper = config.build_mapper().with_scale(&scale);
+    let effects = EffectSettings { wet_mix: cli.wet_mix, ..EffectSettings::default() };
+    let voice_limit = VoiceLimit { max_voices: cli.max_voices, ..VoiceLimit::default() };
+
+    let collector = MetricsCollector::new()
+        .with_process_selection(cli.process_voices, process_sort)
+        .with_smoothing(smoothing)
+        .with_temperature_time_constant(cli.temp_smoothing)
+        .with_temperature_sensor(cli.temp_sensor.clone())
+        .with_ema(cli.ema);
+
     match cli.command {
-        Commands::Live { bars, interval, count } => {
-            live_sonification(bars, interval, count)?;
+        Commands::Live { bars, samples, interval, count, max_duration, slew, quantize, midi_clock, replay, idle_after, freeze_after, scene } => {
+            let source: Box<dyn MetricsSource> = match replay {
+                Some(path) => Box::new(ReplaySource::open(&path)?),
+                None => Box::new(collector),
+            };
+            let composer = SystemComposer::new()
+                .map_err(|e| match e.downcast_ref::<NoAudioDevice>() {
+                    Some(_) => anyhow!("{}. `syssonic export` works without one.", e),
+                    None => e,
+                })?
+                .with_sample_rate(cli.sample_rate)
+                .with_buffer_size(cli.buffer_size)
+                .with_focus(focus)
+                .with_effects(effects)
+                .with_voice_limit(Some(voice_limit))
+                .with_layers(config.layers());
+            let options = LiveOptions {
+                bars,
+                samples,
+                sample_interval_ms: cli.sample_interval,
+                interval_secs: interval,
+                count,
+                max_duration_secs: max_duration,
+                slew_secs: slew,
+                envelope: config.envelope,
+                auto_gain: config.auto_gain,
+                quantize,
+                midi_clock,
+                idle: IdleThreshold { after_secs: idle_after, ..IdleThreshold::default() },
+                freeze_after,
+                scene: scene.map(|name| SceneLibrary::open()?.load(&name)).transpose()?,
+            };
+            live_sonification(source, composer, mapper, options, verbosity)?;
+        }
+        Commands::Export { output, format, bitrate, bars, samples, stereo_width, mono, bass_mono, intro, snapshots, snapshot_interval, scene, manifest, from_manifest } => {
+            // Drum samplers are set up once, so the kit mapping comes from the config file
+            let composer = SystemComposer::new_silent()
+                .with_sample_rate(cli.sample_rate)
+                .with_bit_depth(cli.bit_depth)
+                .with_bitrate(bitrate)
+                .with_focus(focus)
+                .with_effects(effects)
+                .with_voice_limit(Some(voice_limit))
+                .with_layers(config.layers())
+                .with_drum_map(config.drum_map)
+                .with_stereo_width(stereo_width)
+                .with_mono(mono)
+                .with_bass_mono(bass_mono)
+                .with_intro(intro);
+            let options = ExportOptions {
+                bars,
+                samples,
+                sample_interval_ms: cli.sample_interval,
+                snapshots,
+                snapshot_interval_secs: snapshot_interval,
+                saved: saved_params(scene, from_manifest)?,
+                manifest: manifest.then(|| ManifestSettings {
+                    intro_bars: intro.min(2),
+                    preset: cli.focus.clone().unwrap_or_else(|| "none".to_string()),
+                }),
+            };
+            export_snapshot(collector, composer, mapper, &output, &format, options, verbosity)?;
+        }
+        Commands::Scene { action } => {
+            scene_command(collector, mapper, action, cli.sample_interval, verbosity)?;
+        }
+        Commands::Monitor { interval, count, record } => {
+            monitor_metrics(collector, mapper, interval, count, record.as_deref(), verbosity)?;
         }
-        Commands::Export { output, format, bars, samples } => {
-            export_snapshot(&output, &format, bars, samples)?;
+        Commands::Snapshot { output, samples } => {
+            // Settings in effect for this run, recorded alongside the metrics
+            let config = AppConfig {
+                sample_count: samples,
+                sample_interval_ms: cli.sample_interval,
+                process_voices: cli.process_voices,
+                process_sort: cli.process_sort.clone(),
+                cpu_smoothing: cli.cpu_smoothing.clone(),
+                io_smoothing: cli.io_smoothing.clone(),
+                temperature_smoothing_secs: cli.temp_smoothing,
+                temperature_sensor: cli.temp_sensor.clone().unwrap_or_default(),
+                sample_rate: cli.sample_rate,
+                bit_depth: cli.bit_depth,
+                focus: cli.focus.clone().unwrap_or_else(|| "none".to_string()),
+                scale_type: scale,
+                ..config
+            };
+            debug_snapshot(collector, mapper, &config, &output, verbosity)?;
         }
-        Commands::Monitor { interval, count } => {
-            monitor_metrics(interval, count)?;
+        Commands::Bench { iterations, bars } => {
+            // Render with the mix settings live playback uses
+            let composer = SystemComposer::new_silent()
+                .with_sample_rate(cli.sample_rate)
+                .with_focus(focus)
+                .with_effects(effects)
+                .with_voice_limit(Some(voice_limit))
+                .with_layers(config.layers());
+            benchmark(collector, mapper, composer, cli.sample_rate, iterations, bars, verbosity)?;
+        }
+        Commands::Demo { bars, silent } => {
+            let composer = if silent {
+                SystemComposer::new_silent()
+            } else {
+                SystemComposer::new().map_err(|e| match e.downcast_ref::<NoAudioDevice>() {
+                    Some(_) => anyhow!("{}. Run `syssonic demo --silent` to render the tour without one.", e),
+                    None => e,
+                })?
+            };
+            let composer = composer
+                .with_sample_rate(cli.sample_rate)
+                .with_buffer_size(cli.buffer_size)
+                .with_effects(effects)
+                .with_layers(config.layers());
+            demo_tour(composer, bars, verbosity)?;
         }
         Commands::Test => {
             test_audio()?;
         }
+        Commands::Doctor => {
+            doctor(verbosity);
+        }
+        Commands::ListMetrics => {
+            list_metrics(collector, verbosity);
+        }
+        #[cfg(unix)]
+        Commands::Daemon { socket, silent } => {
+            run_daemon(socket, silent, verbosity)?;
+        }
     }
 
     Ok(())
 }
 
-fn live_sonification(bars: usize, interval_secs: f32, count: usize) -> Result<()> {
-    println!("🎵 SysSonic - Live System Sonification");
-    println!("Press Ctrl+C to stop\n");
+/// Pacing of the `live` loop
+struct LiveOptions {
+    bars: usize,
+    samples: usize,          // Readings combined per composition
+    sample_interval_ms: u64, // Gap between those readings
+    interval_secs: f32,
+    count: usize,
+    max_duration_secs: f32,
+    slew_secs: f32,
+    envelope: EnvelopeSettings, // Attack/release per metric, ahead of the mapper
+    auto_gain: AutoGainSettings, // Loudness leveling across compositions
+    quantize: bool, // Hold new compositions until the next bar line
+    midi_clock: Option<String>, // MIDI output port for clock and transport
+    idle: IdleThreshold,
+    freeze_after: usize, // Composition whose params are held from then on (0 = never)
+    scene: Option<MusicalParams>, // Saved params held from the start
+}
+
+fn live_sonification(mut source: Box<dyn MetricsSource>, mut composer: SystemComposer, mapper: MetricsMapper, options: LiveOptions, verbosity: Verbosity) -> Result<()> {
+    status!(verbosity, "🎵 SysSonic - Live System Sonification");
+    status!(verbosity, "Press Ctrl+C to stop\n");
 
-    let mut collector = MetricsCollector::new();
-    let mapper = MetricsMapper::new();
-    let composer = SystemComposer::new()?;
+    let LiveOptions { bars, samples, sample_interval_ms, interval_secs, count, max_duration_secs, slew_secs, envelope, auto_gain, quantize, midi_clock, idle, freeze_after, scene } = options;
 
+    let mut idle = IdleDetector::new(idle);
+    let mut envelope = EnvelopeFollower::new(envelope);
+    let mut auto_gain = AutoGain::new(auto_gain);
+    let running = install_ctrlc_handler()?;
+
+    let started = Instant::now();
+    let mut previous: Option<(MusicalParams, Instant)> = None;
+    let mut frozen: Option<MusicalParams> = scene;
+    let mut bar_clock = BarClock::default();
+    let mut midi_clock = match midi_clock {
+        Some(port) => {
+            let clock = MidiClock::connect(&port, 90.0)?; // Base tempo until the first composition
+            status!(verbosity, "🎛  Sending MIDI clock to '{}'", port);
+            Some(clock)
+        }
+        None => None,
+    };
+    let mut played = 0;
     let mut iteration = 0;
     loop {
         if count > 0 && iteration >= count {
             break;
         }
+        if !running.load(Ordering::SeqCst) {
+            break;
+        }
+        if max_duration_secs > 0.0 && started.elapsed().as_secs_f32() >= max_duration_secs {
+            status!(verbosity, "⏱  Reached max duration of {:.1}s", max_duration_secs);
+            break;
+        }
 
-        println!("🔄 Collecting metrics...");
-        let metrics = collector.collect_smoothed(3, 200);
-        let params = mapper.map(&metrics);
-        
-        mapper.print_mapping_info(&metrics, &params);
+        // Frozen: replay the held params without sampling (compositions are deterministic)
+        if let Some(params) = &frozen {
+            let length_secs = composer::bars_to_seconds(params.tempo, composer::BEATS_PER_BAR, bars);
+            status!(verbosity, "🧊 Replaying frozen composition ({} bars, ≈{:.1}s of audio)...", bars, length_secs);
+            wait_for_downbeat(&mut bar_clock, quantize, params.tempo, &running);
+            if let Some(clock) = &mut midi_clock {
+                clock.start(params.tempo)?;
+            }
+            composer = composer.with_gain(auto_gain.gain());
+            auto_gain.update(composer.compose_and_play(params, bars)?);
+            if count > 0 {
+                iteration += 1;
+            }
+            if interval_secs > 0.0 && (count == 0 || iteration < count) {
+                if let Some(clock) = &mut midi_clock {
+                    clock.stop()?;
+                }
+                sleep_unless_interrupted(interval_secs, &running);
+            }
+            continue;
+        }
 
-        println!("🎹 Playing composition ({} bars)...", bars);
-        composer.compose_and_play(&params, bars)?;
+        status!(verbosity, "🔄 Collecting metrics...");
+        let Some(metrics) = source.next_metrics(samples, sample_interval_ms) else {
+            status!(verbosity, "⏹  Replay finished");
+            break;
+        };
+        let followed = envelope.follow(&metrics);
+        let params = mapper.map(&followed);
+
+        // Glide from the last composition instead of jumping between bars.
+        // After one time constant the params have covered ~63% of the change.
+        let params = match &previous {
+            Some((last, at)) if slew_secs > 0.0 => {
+                let amount = 1.0 - (-at.elapsed().as_secs_f32() / slew_secs).exp();
+                params.slewed_from(last, amount)
+            }
+            _ => params,
+        };
+        previous = Some((params.clone(), Instant::now()));
+
+        mapper.print_mapping_info(&followed, &params);
+
+        let length_secs = composer::bars_to_seconds(params.tempo, composer::BEATS_PER_BAR, bars);
+        if idle.update(&metrics) {
+            // Keep the usual cadence while skipping, instead of resampling nonstop
+            status!(verbosity, "💤 System idle, skipping composition");
+            if let Some(clock) = &mut midi_clock {
+                clock.stop()?;
+            }
+            sleep_unless_interrupted(length_secs, &running);
+        } else {
+            status!(verbosity, "🎹 Playing composition ({} bars, ≈{:.1}s of audio, then {:.0}s wait)...", bars, length_secs, interval_secs);
+            wait_for_downbeat(&mut bar_clock, quantize, params.tempo, &running);
+            if let Some(clock) = &mut midi_clock {
+                clock.start(params.tempo)?;
+            }
+            // Level each composition toward the target loudness, from the ones before it
+            composer = composer.with_gain(auto_gain.gain());
+            auto_gain.update(composer.compose_and_play(&params, bars)?);
+
+            played += 1;
+            if played == freeze_after {
+                status!(verbosity, "🧊 Freezing these params; later compositions replay them");
+                frozen = Some(params);
+            }
+        }
 
         if count > 0 {
             iteration += 1;
-            if iteration < count {
-                println!("\n⏸  Waiting {:.1}s before next sample...\n", interval_secs);
-                std::thread::sleep(Duration::from_secs_f32(interval_secs));
+        }
+        if interval_secs > 0.0 && (count == 0 || iteration < count) {
+            status!(verbosity, "\n⏸  Waiting {:.1}s before next sample...\n", interval_secs);
+            if let Some(clock) = &mut midi_clock {
+                clock.stop()?;
             }
-        } else {
-            println!("\n⏸  Waiting {:.1}s before next sample...\n", interval_secs);
-            std::thread::sleep(Duration::from_secs_f32(interval_secs));
+            sleep_unless_interrupted(interval_secs, &running);
         }
     }
 
-    println!("\n✅ Live sonification complete!");
+    status!(verbosity, "\n✅ Live sonification complete!");
     Ok(())
 }
 
-fn export_snapshot(output: &str, format_str: &str, bars: usize, samples: usize) -> Result<()> {
-    println!("🎵 SysSonic - Export Snapshot");
-    println!("📊 Collecting {} samples...", samples);
+/// Overlap between consecutive snapshots in a sequence export
+const SNAPSHOT_CROSSFADE_SECS: f32 = 1.0;
 
-    let mut collector = MetricsCollector::new();
-    let mapper = MetricsMapper::new();
-    let composer = SystemComposer::new()?;
+/// What the `export` command captures
+struct ExportOptions {
+    bars: usize,                 // Per snapshot
+    samples: usize,              // Averaged into each snapshot
+    sample_interval_ms: u64,     // Between those samples
+    snapshots: usize,            // More than 1 = an evolving track of crossfaded segments
+    snapshot_interval_secs: f32, // From the start of one snapshot to the next
+    saved: Option<Vec<MusicalParams>>, // Scene or manifest params exported instead of sampling
+    manifest: Option<ManifestSettings>, // Write a sidecar manifest with these settings
+}
+
+/// Settings recorded in an export manifest that the export itself doesn't need
+struct ManifestSettings {
+    intro_bars: usize,
+    preset: String, // Focus name, "none" for a balanced mix
+}
+
+/// Params to export instead of sampling: a saved scene, or every segment of a manifest
+fn saved_params(scene: Option<String>, manifest: Option<std::path::PathBuf>) -> Result<Option<Vec<MusicalParams>>> {
+    if let Some(name) = scene {
+        return Ok(Some(vec![SceneLibrary::open()?.load(&name)?]));
+    }
+    match manifest {
+        Some(path) => {
+            let manifest = ExportManifest::load(&path)?;
+            if manifest.segments.is_empty() {
+                anyhow::bail!("Manifest {} has no segments", path.display());
+            }
+            Ok(Some(manifest.segments.into_iter().map(|segment| segment.params).collect()))
+        }
+        None => Ok(None),
+    }
+}
 
-    let metrics = collector.collect_smoothed(samples, 200);
-    let params = mapper.map(&metrics);
-    
-    mapper.print_mapping_info(&metrics, &params);
+fn export_snapshot(mut collector: MetricsCollector, composer: SystemComposer, mapper: MetricsMapper, output: &str, format_str: &str, options: ExportOptions, verbosity: Verbosity) -> Result<()> {
+    let ExportOptions { bars, samples, sample_interval_ms, snapshots, snapshot_interval_secs, saved, manifest } = options;
+    status!(verbosity, "🎵 SysSonic - Export Snapshot");
 
-    let format = match format_str.to_lowercase().as_str() {
-        "wav" => ExportFormat::Wav,
-        "flac" => ExportFormat::Flac,
-        "midi" => ExportFormat::Midi,
-        _ => {
-            eprintln!("❌ Unknown format '{}'. Using WAV.", format_str);
-            ExportFormat::Wav
+    let format = ExportFormat::from_name(format_str).unwrap_or_else(|| {
+        eprintln!("❌ Unknown format '{}'. Using WAV.", format_str);
+        ExportFormat::Wav
+    });
+
+    // What each segment was made from, for the manifest
+    let mut made: Vec<ManifestSegment> = Vec::new();
+    if let Some(saved) = saved {
+        for params in &saved {
+            status!(verbosity, "🎬 Exporting saved params: {}", params.now_playing());
         }
-    };
+        if let [params] = saved.as_slice() {
+            status!(verbosity, "🎹 Generating composition ({} bars)...", bars);
+            composer.compose_and_export(params, bars, output, format, None)?;
+        } else {
+            status!(verbosity, "🎹 Generating {} segments ({} bars each)...", saved.len(), bars);
+            composer.compose_and_export_sequence(&saved, bars, SNAPSHOT_CROSSFADE_SECS, output, format)?;
+        }
+        made.extend(saved.into_iter().map(|params| ManifestSegment::new(None, params)));
+    } else if snapshots <= 1 {
+        status!(verbosity, "📊 Collecting {} samples...", samples);
+        let metrics = collector.collect_averaged(samples, sample_interval_ms);
+        let params = mapper.map(&metrics);
+
+        mapper.print_mapping_info(&metrics, &params);
 
-    println!("🎹 Generating composition ({} bars)...", bars);
-    composer.compose_and_export(&params, bars, output, format)?;
+        status!(verbosity, "🎹 Generating composition ({} bars)...", bars);
+        composer.compose_and_export(&params, bars, output, format, None)?;
+        made.push(ManifestSegment::new(Some(metrics), params));
+    } else {
+        // Ctrl+C stops sampling early and exports the snapshots taken so far
+        let running = install_ctrlc_handler()?;
+        let mut segments = Vec::with_capacity(snapshots);
+        while segments.len() < snapshots && running.load(Ordering::SeqCst) {
+            let next = Instant::now() + Duration::from_secs_f32(snapshot_interval_secs.max(0.0));
+            status!(verbosity, "📸 Snapshot {}/{}: collecting {} samples...", segments.len() + 1, snapshots, samples);
+            let metrics = collector.collect_averaged(samples, sample_interval_ms);
+            let params = mapper.map(&metrics);
+            status!(verbosity, "   {}", params.now_playing());
+            segments.push(params.clone());
+            made.push(ManifestSegment::new(Some(metrics), params));
 
-    println!("✅ Export complete!");
+            if segments.len() < snapshots {
+                sleep_unless_interrupted(next.saturating_duration_since(Instant::now()).as_secs_f32(), &running);
+            }
+        }
+
+        status!(verbosity, "🎹 Generating {} segments ({} bars each)...", segments.len(), bars);
+        composer.compose_and_export_sequence(&segments, bars, SNAPSHOT_CROSSFADE_SECS, output, format)?;
+    }
+    status!(verbosity, "✅ Exported to: {}", output);
+
+    if let Some(settings) = manifest {
+        let manifest = ExportManifest {
+            system_info: SystemInfo::current(),
+            format: format.name().to_string(),
+            bars,
+            intro_bars: settings.intro_bars,
+            preset: settings.preset,
+            seed: mapper.melody_seed(),
+            segments: made,
+        };
+        manifest.save_beside(output)?;
+        status!(verbosity, "📝 Manifest written to: {}", ExportManifest::path_for(output).display());
+    }
+    Ok(())
+}
+
+fn scene_command(mut collector: MetricsCollector, mapper: MetricsMapper, action: SceneAction, sample_interval_ms: u64, verbosity: Verbosity) -> Result<()> {
+    let scenes = SceneLibrary::open()?;
+    match action {
+        SceneAction::Save { name, samples } => {
+            status!(verbosity, "📊 Collecting {} samples...", samples);
+            let params = mapper.map(&collector.collect_averaged(samples, sample_interval_ms));
+            scenes.save(&name, &params)?;
+            status!(verbosity, "🎬 Saved scene '{}': {}", name, params.now_playing());
+        }
+        SceneAction::List => {
+            // Plain names on stdout, one per line, even with --quiet
+            for name in scenes.list()? {
+                println!("{}", name);
+            }
+        }
+    }
     Ok(())
 }
 
-fn monitor_metrics(interval_secs: f32, count: usize) -> Result<()> {
-    println!("📊 SysSonic - Metrics Monitor");
-    println!("Press Ctrl+C to stop\n");
+fn monitor_metrics(mut collector: MetricsCollector, mapper: MetricsMapper, interval_secs: f32, count: usize, record: Option<&Path>, verbosity: Verbosity) -> Result<()> {
+    status!(verbosity, "📊 SysSonic - Metrics Monitor");
+    status!(verbosity, "Press Ctrl+C to stop\n");
 
-    let mut collector = MetricsCollector::new();
-    let mapper = MetricsMapper::new();
+    let running = install_ctrlc_handler()?;
+    let mut recording = match record {
+        Some(path) => Some(std::fs::OpenOptions::new().create(true).append(true).open(path)?),
+        None => None,
+    };
 
     let mut iteration = 0;
     loop {
         if count > 0 && iteration >= count {
             break;
         }
+        if !running.load(Ordering::SeqCst) {
+            break;
+        }
 
         let metrics = collector.collect();
         let params = mapper.map(&metrics);
-        
-        mapper.print_mapping_info(&metrics, &params);
+        if let Some(file) = recording.as_mut() {
+            metrics::record_metrics(file, &metrics)?;
+        }
+
+        // The mapping table is the whole point of monitor mode
+        if verbosity >= Verbosity::Normal {
+            println!("{}", mapper.format_mapping_info(&metrics, &params));
+        }
 
         if count > 0 {
             iteration += 1;
             if iteration < count {
-                std::thread::sleep(Duration::from_secs_f32(interval_secs));
+                sleep_unless_interrupted(interval_secs, &running);
             }
         } else {
-            std::thread::sleep(Duration::from_secs_f32(interval_secs));
+            sleep_unless_interrupted(interval_secs, &running);
+        }
+    }
+
+    status!(verbosity, "\n✅ Monitoring complete!");
+    Ok(())
+}
+
+fn debug_snapshot(mut collector: MetricsCollector, mapper: MetricsMapper, config: &AppConfig, output: &str, verbosity: Verbosity) -> Result<()> {
+    status!(verbosity, "🐛 SysSonic - Debug Snapshot");
+    status!(verbosity, "📊 Collecting {} samples...", config.sample_count);
+
+    let snapshot = DebugSnapshot::capture(&mut collector, &mapper, config);
+    snapshot.save(Path::new(output))?;
+
+    status!(verbosity, "✅ Snapshot written to: {} (attach it to your bug report)", output);
+    Ok(())
+}
+
+fn benchmark(mut collector: MetricsCollector, mapper: MetricsMapper, composer: SystemComposer, sample_rate: u32, iterations: usize, bars: usize, verbosity: Verbosity) -> Result<()> {
+    status!(verbosity, "⏱  SysSonic - Benchmark");
+    status!(verbosity, "Running {} iterations ({} bar render each, {} Hz)...\n", iterations, bars, sample_rate);
+
+    let mut collect_times = Vec::with_capacity(iterations);
+    let mut map_times = Vec::with_capacity(iterations);
+    let mut render_times = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations.max(1) {
+        let start = Instant::now();
+        let metrics = collector.collect();
+        collect_times.push(start.elapsed());
+
+        let start = Instant::now();
+        let params = mapper.map(&metrics);
+        map_times.push(start.elapsed());
+
+        // Render to memory so the device and disk stay out of the measurement
+        let start = Instant::now();
+        let buffer = composer.render_to_buffer(&params, bars, sample_rate);
+        render_times.push(start.elapsed());
+        drop(buffer);
+    }
+
+    if verbosity >= Verbosity::Normal {
+        println!("{:<10} {:>10} {:>10} {:>10} {:>10}", "stage", "min", "median", "max", "mean");
+        print_timings("collect", &mut collect_times);
+        print_timings("map", &mut map_times);
+        print_timings("render", &mut render_times);
+    }
+
+    Ok(())
+}
+
+fn demo_tour(composer: SystemComposer, bars: usize, verbosity: Verbosity) -> Result<()> {
+    status!(verbosity, "🎵 SysSonic - Demo Tour");
+    status!(verbosity, "Scripted metrics, {} bars per scene. Press Ctrl+C to stop\n", bars);
+
+    let mapper = MetricsMapper::new();
+    let running = install_ctrlc_handler()?;
+    let scenes = syssonic_core::demo::tour();
+
+    for (i, scene) in scenes.iter().enumerate() {
+        if !running.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let params = mapper.map(&scene.metrics);
+        status!(verbosity, "▶ {}/{} {} ({})", i + 1, scenes.len(), scene.title, params.now_playing());
+        status!(verbosity, "   {}", scene.narration);
+        mapper.print_mapping_info(&scene.metrics, &params);
+
+        composer.compose_and_play(&params, bars)?;
+    }
+
+    status!(verbosity, "\n✅ Tour complete! Try `syssonic live` to hear your own machine.");
+    Ok(())
+}
+
+fn doctor(verbosity: Verbosity) {
+    status!(verbosity, "🩺 SysSonic - Capability Report\n");
+
+    for subsystem in metrics::Subsystem::ALL {
+        let report = metrics::diagnose(subsystem);
+        println!("{} {}", if report.available { "✅" } else { "➖" }, report);
+    }
+
+    match SystemComposer::new() {
+        Ok(_) => println!("✅ Audio output: OK"),
+        Err(e) => println!("❌ Audio output: {} (exports still work)", e),
+    }
+}
+
+fn list_metrics(mut collector: MetricsCollector, verbosity: Verbosity) {
+    status!(verbosity, "📋 SysSonic - Collected Metrics\n");
+
+    // Rates are deltas between readings, so the first one only sets the baseline
+    collector.collect();
+    std::thread::sleep(Duration::from_secs(1));
+    let metrics = collector.collect();
+
+    for group in metrics::inventory(&metrics) {
+        println!("{}", group.subsystem);
+        for value in &group.values {
+            let (icon, note) = match value.source {
+                metrics::ValueSource::Measured => ("✅", ""),
+                metrics::ValueSource::Fallback => ("⚠️", " (fallback)"),
+                metrics::ValueSource::Unavailable => ("➖", ""),
+            };
+            println!("  {} {:<24} {}{}", icon, value.field, value.value, note);
         }
+        println!();
     }
+}
+
+/// Print min/median/max/mean in milliseconds for one benchmark stage
+fn print_timings(stage: &str, times: &mut [Duration]) {
+    times.sort();
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let mean = times.iter().map(|&d| ms(d)).sum::<f64>() / times.len() as f64;
+
+    println!(
+        "{:<10} {:>8.2}ms {:>8.2}ms {:>8.2}ms {:>8.2}ms",
+        stage,
+        ms(times[0]),
+        ms(times[times.len() / 2]),
+        ms(times[times.len() - 1]),
+        mean
+    );
+}
 
-    println!("\n✅ Monitoring complete!");
+#[cfg(unix)]
+fn run_daemon(socket: Option<std::path::PathBuf>, silent: bool, verbosity: Verbosity) -> Result<()> {
+    use syssonic_core::daemon::{default_socket_path, Daemon};
+
+    let socket = socket.unwrap_or_else(default_socket_path);
+    status!(verbosity, "🎵 SysSonic - Daemon");
+    status!(verbosity, "Listening on {} (send: play, stop, panic, freeze, unfreeze, volume <0.0-1.0>, status)", socket.display());
+    status!(verbosity, "Press Ctrl+C to stop\n");
+
+    // Same settings file as the tray app
+    let config = AppConfig::load().unwrap_or_default();
+    let running = install_ctrlc_handler()?;
+    Daemon::new(config, silent).run(&socket, &running)?;
+
+    status!(verbosity, "\n✅ Daemon stopped");
     Ok(())
 }
 
+/// Install a Ctrl+C handler that clears the returned flag.
+///
+/// Loops check the flag between compositions so the current bar finishes and
+/// the audio device is released cleanly. A second Ctrl+C exits immediately.
+fn install_ctrlc_handler() -> Result<Arc<AtomicBool>> {
+    let running = Arc::new(AtomicBool::new(true));
+    let handler_flag = running.clone();
+
+    ctrlc::set_handler(move || {
+        if !handler_flag.swap(false, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        println!("\n🛑 Stopping after the current composition (Ctrl+C again to force quit)...");
+    })?;
+
+    Ok(running)
+}
+
+/// Sleep for `secs`, waking early if Ctrl+C was pressed
+fn sleep_unless_interrupted(secs: f32, running: &AtomicBool) {
+    let deadline = Instant::now() + Duration::from_secs_f32(secs);
+    while running.load(Ordering::SeqCst) && Instant::now() < deadline {
+        std::thread::sleep(deadline.saturating_duration_since(Instant::now()).min(Duration::from_millis(100)));
+    }
+}
+
+/// With `quantize`, wait for the next bar line of the previous compositions,
+/// then start the bar grid again at `tempo` for the one about to play
+fn wait_for_downbeat(clock: &mut BarClock, quantize: bool, tempo: f32, running: &AtomicBool) {
+    if quantize {
+        sleep_unless_interrupted(clock.until_next_bar(Instant::now()).as_secs_f32(), running);
+    }
+    clock.start(Instant::now(), tempo);
+}
+
 fn test_audio() -> Result<()> {
     println!("🎵 SysSonic - Audio Test");
     println!("Playing test composition...\n");
@@ -1,53 +1,181 @@
-use crate::audio_thread::{AudioCommand, AudioEvent, AudioThread};
-use crate::config::AppConfig;
-use crate::mapper::{MetricsMapper, MusicalParams};
-use crate::metrics::{SystemMetrics, MetricsCollector};
 use std::path::PathBuf;
 use std::sync::Mutex;
+use syssonic_core::audio_thread::{AudioCommand, AudioEvent, AudioThread};
+use syssonic_core::composer::{ExportFormat, Focus, SystemComposer};
+use syssonic_core::config::{AppConfig, MAX_BARS, MIN_BARS};
+use syssonic_core::idle::IdleDetector;
+use syssonic_core::mapper::{MappingExplanation, MetricsMapper, MusicalParams, SCALE_NAMES};
+use syssonic_core::metrics::{self, EnvelopeFollower, Subsystem, SubsystemReport, SystemMetrics, MetricsCollector};
+use syssonic_core::scene::SceneLibrary;
+use syssonic_core::snapshot::{DebugSnapshot, SystemInfo};
+use syssonic_core::timeline::NoteEvent;
 use tauri::State;
 
 // Application state shared across commands
 pub struct AppState {
     pub audio_thread: Mutex<AudioThread>,
     pub metrics_collector: Mutex<MetricsCollector>,
-    pub mapper: MetricsMapper,
+    pub mapper: Mutex<MetricsMapper>,
+    pub envelope: Mutex<EnvelopeFollower>, // Attack/release between Start presses
     pub config: Mutex<AppConfig>,
+    pub idle: Mutex<IdleDetector>,
+    pub last_params: Mutex<Option<MusicalParams>>, // Most recently played composition
+    pub frozen: Mutex<Option<MusicalParams>>,      // Held params replayed by Start and Export
+    pub pending_events: Mutex<Vec<AudioEvent>>,    // Audio events not yet polled by the window
 }
 
 impl AppState {
     pub fn new() -> Self {
+        let config = AppConfig::load().unwrap_or_default();
+
+        let audio_thread = AudioThread::new();
+        let (sample_rate, bit_depth) = config.output_format();
+        let _ = audio_thread.send_command(AudioCommand::SetVolume(config.volume));
+        let _ = audio_thread.send_command(AudioCommand::SetOutputFormat { sample_rate, bit_depth });
+        let _ = audio_thread.send_command(AudioCommand::SetBufferSize(config.buffer_size()));
+        let _ = audio_thread.send_command(AudioCommand::SetFocus(config.focus()));
+        let _ = audio_thread.send_command(AudioCommand::SetEffects(config.effects));
+        let _ = audio_thread.send_command(AudioCommand::SetVoiceLimit(Some(config.voice_limit.clone())));
+        let _ = audio_thread.send_command(AudioCommand::SetLayers(config.layers()));
+        let _ = audio_thread.send_command(AudioCommand::SetDrumMap(config.drum_map));
+        let _ = audio_thread.send_command(AudioCommand::SetAutoGain(config.auto_gain));
+        let _ = audio_thread.send_command(AudioCommand::SetQuantize(config.quantize_to_bar));
+
         Self {
-            audio_thread: Mutex::new(AudioThread::new()),
-            metrics_collector: Mutex::new(MetricsCollector::new()),
-            mapper: MetricsMapper::new(),
-            config: Mutex::new(AppConfig::load().unwrap_or_default()),
+            audio_thread: Mutex::new(audio_thread),
+            metrics_collector: Mutex::new(config.build_collector()),
+            mapper: Mutex::new(config.build_mapper()),
+            envelope: Mutex::new(EnvelopeFollower::new(config.envelope)),
+            idle: Mutex::new(IdleDetector::new(config.idle_threshold)),
+            last_params: Mutex::new(None),
+            frozen: Mutex::new(None),
+            pending_events: Mutex::new(Vec::new()),
+            config: Mutex::new(config),
         }
     }
+
+    /// Take new events from the audio thread, keeping a copy for the window.
+    ///
+    /// The tray menu and the window both follow audio events, but the audio
+    /// thread hands each event out once, so they are fanned out here.
+    pub fn collect_audio_events(&self) -> Vec<AudioEvent> {
+        let events = self.audio_thread.lock().unwrap().poll_events();
+        self.pending_events.lock().unwrap().extend(events.iter().cloned());
+        events
+    }
 }
 
 // === Audio Control Commands ===
 
 #[tauri::command]
 pub fn start_audio(state: State<AppState>) -> Result<(), String> {
+    let (bars, (samples, interval_ms)) = {
+        let config = state.config.lock().unwrap();
+        (config.bars(), config.sampling())
+    };
+
+    // Frozen: replay the held moment, whatever the metrics say now
+    if let Some(params) = state.frozen.lock().unwrap().clone() {
+        let audio = state.audio_thread.lock().unwrap();
+        return audio
+            .send_command(AudioCommand::Play(params, bars))
+            .map_err(|e| e.to_string());
+    }
+
     let mut collector = state.metrics_collector.lock().unwrap();
-    let metrics = collector.collect_smoothed(3, 200);
+    let metrics = collector.collect_averaged(samples, interval_ms);
 
-    let params = state.mapper.map(&metrics);
+    let params = state.mapper.lock().unwrap().map(&state.envelope.lock().unwrap().follow(&metrics));
 
     let audio = state.audio_thread.lock().unwrap();
+
+    // Quiet machine: skip this composition, and tell the UI when that changes
+    let mut idle = state.idle.lock().unwrap();
+    let was_idle = idle.is_idle();
+    if idle.update(&metrics) != was_idle {
+        audio.report_idle(idle.is_idle());
+    }
+    if idle.is_idle() {
+        return Ok(());
+    }
+
+    *state.last_params.lock().unwrap() = Some(params.clone());
     audio
-        .send_command(AudioCommand::Play(params, 4))
+        .send_command(AudioCommand::Play(params, bars))
         .map_err(|e| e.to_string())
 }
 
+/// Hold the last-played params so Start keeps looping them, or release them
 #[tauri::command]
-pub fn stop_audio(state: State<AppState>) -> Result<(), String> {
-    let audio = state.audio_thread.lock().unwrap();
-    audio
-        .send_command(AudioCommand::Stop)
+pub fn set_frozen(state: State<AppState>, frozen: bool) -> Result<(), String> {
+    let mut held = state.frozen.lock().unwrap();
+    if frozen == held.is_some() {
+        return Ok(());
+    }
+
+    *held = if frozen {
+        // Nothing played yet: hold what the machine sounds like right now
+        let last = state.last_params.lock().unwrap().clone();
+        Some(last.unwrap_or_else(|| {
+            let (samples, interval_ms) = state.config.lock().unwrap().sampling();
+            let metrics = state.metrics_collector.lock().unwrap().collect_averaged(samples, interval_ms);
+            state.mapper.lock().unwrap().map(&metrics)
+        }))
+    } else {
+        None
+    };
+
+    state.audio_thread.lock().unwrap().report_frozen(frozen);
+    Ok(())
+}
+
+// === Scene Commands ===
+
+/// Save what is playing (the frozen or last-played params) as a named scene
+#[tauri::command]
+pub fn save_scene(state: State<AppState>, name: String) -> Result<(), String> {
+    let held = state.frozen.lock().unwrap().clone();
+    let params = held.or_else(|| state.last_params.lock().unwrap().clone()).unwrap_or_else(|| {
+        // Nothing played yet: capture what the machine sounds like right now
+        let (samples, interval_ms) = state.config.lock().unwrap().sampling();
+        let metrics = state.metrics_collector.lock().unwrap().collect_averaged(samples, interval_ms);
+        state.mapper.lock().unwrap().map(&metrics)
+    });
+
+    let scenes = SceneLibrary::open().map_err(|e| e.to_string())?;
+    scenes.save(&name, &params).map_err(|e| e.to_string())
+}
+
+/// Freeze on a saved scene, so Start plays it and Export exports it
+#[tauri::command]
+pub fn load_scene(state: State<AppState>, name: String) -> Result<MusicalParams, String> {
+    let scenes = SceneLibrary::open().map_err(|e| e.to_string())?;
+    let params = scenes.load(&name).map_err(|e| e.to_string())?;
+
+    *state.frozen.lock().unwrap() = Some(params.clone());
+    state.audio_thread.lock().unwrap().report_frozen(true);
+    Ok(params)
+}
+
+#[tauri::command]
+pub fn list_scenes() -> Result<Vec<String>, String> {
+    SceneLibrary::open()
+        .and_then(|scenes| scenes.list())
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn stop_audio(state: State<AppState>) -> Result<(), String> {
+    state.audio_thread.lock().unwrap().stop().map_err(|e| e.to_string())
+}
+
+/// Panic button: silence everything now, even if a normal Stop is stuck
+#[tauri::command]
+pub fn emergency_stop(state: State<AppState>) -> Result<(), String> {
+    state.audio_thread.lock().unwrap().emergency_stop();
+    Ok(())
+}
+
 #[tauri::command]
 pub fn pause_audio(state: State<AppState>) -> Result<(), String> {
     let audio = state.audio_thread.lock().unwrap();
@@ -78,15 +206,35 @@ pub fn set_volume(state: State<AppState>, volume: f32) -> Result<(), String> {
         .map_err(|e| e.to_string())
 }
 
+/// Bars per composition played by Start
+#[tauri::command]
+pub fn set_bars(state: State<AppState>, bars: usize) -> Result<(), String> {
+    if !(MIN_BARS..=MAX_BARS).contains(&bars) {
+        return Err(format!("Bars must be between {} and {}", MIN_BARS, MAX_BARS));
+    }
+
+    let mut config = state.config.lock().unwrap();
+    config.bars = bars;
+    config.save().map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_audio_state(state: State<AppState>) -> Result<serde_json::Value, String> {
     let audio = state.audio_thread.lock().unwrap();
     Ok(serde_json::json!({
         "playing": audio.is_playing(),
         "volume": audio.get_volume(),
+        "idle": state.idle.lock().unwrap().is_idle(),
+        "frozen": state.frozen.lock().unwrap().is_some(),
     }))
 }
 
+/// Recent output samples (-1..1, oldest first) for the oscilloscope
+#[tauri::command]
+pub fn get_waveform(state: State<AppState>, num_samples: usize) -> Result<Vec<f32>, String> {
+    Ok(state.audio_thread.lock().unwrap().waveform(num_samples))
+}
+
 // === Metrics Commands ===
 
 #[tauri::command]
@@ -99,7 +247,33 @@ pub fn get_current_metrics(state: State<AppState>) -> Result<SystemMetrics, Stri
 pub fn get_musical_params(state: State<AppState>) -> Result<MusicalParams, String> {
     let mut collector = state.metrics_collector.lock().unwrap();
     let metrics = collector.collect();
-    Ok(state.mapper.map(&metrics))
+    Ok(state.mapper.lock().unwrap().map(&metrics))
+}
+
+/// Why the current metrics sound the way they do: metric → parameter, one entry per mapping
+#[tauri::command]
+pub fn get_mapping_explanation(state: State<AppState>) -> Result<Vec<MappingExplanation>, String> {
+    let mut collector = state.metrics_collector.lock().unwrap();
+    let metrics = collector.collect();
+    let mapper = state.mapper.lock().unwrap();
+    let params = mapper.map(&metrics);
+    Ok(mapper.explain_mapping(&metrics, &params))
+}
+
+/// Scheduled notes of a composition from the current metrics (for a piano roll)
+#[tauri::command]
+pub fn get_composition_events(state: State<AppState>) -> Result<Vec<NoteEvent>, String> {
+    let mut collector = state.metrics_collector.lock().unwrap();
+    let metrics = collector.collect();
+    let params = state.mapper.lock().unwrap().map(&metrics);
+
+    let config = state.config.lock().unwrap();
+    let composer = SystemComposer::new_silent()
+        .with_focus(config.focus())
+        .with_effects(config.effects)
+        .with_voice_limit(Some(config.voice_limit.clone()))
+        .with_layers(config.layers());
+    Ok(composer.arrange(&params, config.bars()))
 }
 
 // === Export Commands ===
@@ -111,9 +285,16 @@ pub fn export_audio(
     format: String,
     bars: usize,
 ) -> Result<(), String> {
-    let mut collector = state.metrics_collector.lock().unwrap();
-    let metrics = collector.collect_smoothed(5, 200);
-    let params = state.mapper.map(&metrics);
+    if ExportFormat::from_name(&format).is_none() {
+        return Err(format!("Unknown export format: {}", format));
+    }
+
+    // A frozen moment exports exactly as it plays
+    let params = state.frozen.lock().unwrap().clone().unwrap_or_else(|| {
+        let (samples, interval_ms) = state.config.lock().unwrap().sampling();
+        let metrics = state.metrics_collector.lock().unwrap().collect_averaged(samples, interval_ms);
+        state.mapper.lock().unwrap().map(&metrics)
+    });
 
     let audio = state.audio_thread.lock().unwrap();
     audio
@@ -126,8 +307,24 @@ pub fn export_audio(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn list_export_formats() -> Vec<&'static str> {
+    ExportFormat::ALL.iter().map(|f| f.name()).collect()
+}
+
 // === Config Commands ===
 
+#[tauri::command]
+pub fn list_scales() -> Vec<&'static str> {
+    SCALE_NAMES.to_vec()
+}
+
+/// Mix presets for the `focus` setting: a balanced mix, then one per subsystem
+#[tauri::command]
+pub fn list_presets() -> Vec<&'static str> {
+    std::iter::once("none").chain(Focus::ALL.iter().map(|f| f.name())).collect()
+}
+
 #[tauri::command]
 pub fn get_config(state: State<AppState>) -> Result<AppConfig, String> {
     let config = state.config.lock().unwrap();
@@ -136,8 +333,45 @@ pub fn get_config(state: State<AppState>) -> Result<AppConfig, String> {
 
 #[tauri::command]
 pub fn save_config(state: State<AppState>, new_config: AppConfig) -> Result<(), String> {
+    // Scale, curves, sampling and smoothing live in the mapper and collector
+    *state.mapper.lock().unwrap() = new_config.build_mapper();
+    *state.metrics_collector.lock().unwrap() = new_config.build_collector();
+
     let mut config = state.config.lock().unwrap();
     *config = new_config;
+    *state.idle.lock().unwrap() = IdleDetector::new(config.idle_threshold);
+    *state.envelope.lock().unwrap() = EnvelopeFollower::new(config.envelope);
+
+    let (sample_rate, bit_depth) = config.output_format();
+    let audio = state.audio_thread.lock().unwrap();
+    audio
+        .send_command(AudioCommand::SetOutputFormat { sample_rate, bit_depth })
+        .map_err(|e| e.to_string())?;
+    audio
+        .send_command(AudioCommand::SetBufferSize(config.buffer_size()))
+        .map_err(|e| e.to_string())?;
+    audio
+        .send_command(AudioCommand::SetFocus(config.focus()))
+        .map_err(|e| e.to_string())?;
+    audio
+        .send_command(AudioCommand::SetEffects(config.effects))
+        .map_err(|e| e.to_string())?;
+    audio
+        .send_command(AudioCommand::SetVoiceLimit(Some(config.voice_limit.clone())))
+        .map_err(|e| e.to_string())?;
+    audio
+        .send_command(AudioCommand::SetLayers(config.layers()))
+        .map_err(|e| e.to_string())?;
+    audio
+        .send_command(AudioCommand::SetDrumMap(config.drum_map))
+        .map_err(|e| e.to_string())?;
+    audio
+        .send_command(AudioCommand::SetAutoGain(config.auto_gain))
+        .map_err(|e| e.to_string())?;
+    audio
+        .send_command(AudioCommand::SetQuantize(config.quantize_to_bar))
+        .map_err(|e| e.to_string())?;
+
     config.save().map_err(|e| e.to_string())
 }
 
@@ -175,6 +409,15 @@ pub fn update_config_field(
                 config.enable_gpu_monitoring = v;
             }
         }
+        "focus" => {
+            if let Some(v) = value.as_str() {
+                config.focus = v.to_string();
+                let audio = state.audio_thread.lock().unwrap();
+                audio
+                    .send_command(AudioCommand::SetFocus(config.focus()))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
         _ => return Err(format!("Unknown config field: {}", field)),
     }
 
@@ -185,16 +428,31 @@ pub fn update_config_field(
 
 #[tauri::command]
 pub fn poll_audio_events(state: State<AppState>) -> Result<Vec<AudioEvent>, String> {
-    let audio = state.audio_thread.lock().unwrap();
-    Ok(audio.poll_events())
+    state.collect_audio_events();
+    Ok(std::mem::take(&mut *state.pending_events.lock().unwrap()))
 }
 
 // === System Info ===
 
 #[tauri::command]
-pub fn get_system_info() -> Result<serde_json::Value, String> {
-    Ok(serde_json::json!({
-        "platform": std::env::consts::OS,
-        "arch": std::env::consts::ARCH,
-    }))
+pub fn get_system_info() -> Result<SystemInfo, String> {
+    Ok(SystemInfo::current())
+}
+
+/// Probe one optional sensor: `nvidia`, `amd`, `battery`, or `fans`
+#[tauri::command]
+pub fn diagnose_subsystem(name: String) -> Result<SubsystemReport, String> {
+    let subsystem = Subsystem::from_name(&name).ok_or_else(|| format!("Unknown subsystem: {}", name))?;
+    Ok(metrics::diagnose(subsystem))
+}
+
+// === Debug Snapshot ===
+
+#[tauri::command]
+pub fn export_debug_snapshot(state: State<AppState>, path: String) -> Result<(), String> {
+    let config = state.config.lock().unwrap().clone();
+    let mut collector = state.metrics_collector.lock().unwrap();
+    let snapshot = DebugSnapshot::capture(&mut collector, &state.mapper.lock().unwrap(), &config);
+
+    snapshot.save(&PathBuf::from(path)).map_err(|e| e.to_string())
 }
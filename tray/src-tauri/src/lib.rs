@@ -1,19 +1,112 @@
 // Module declarations
-mod audio_thread;
 mod commands;
-mod composer;
-mod config;
-mod mapper;
-mod metrics;
 
 use commands::AppState;
 use single_instance::SingleInstance;
+use std::sync::Mutex;
+use std::time::Duration;
+use syssonic_core::audio_thread::AudioEvent;
 use tauri::{
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{CheckMenuItem, CheckMenuItemBuilder, MenuBuilder, MenuItem, MenuItemBuilder, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager,
+    AppHandle, Manager, Wry,
 };
 
+/// Longest quit waits for the audio thread (e.g. to finish writing an export)
+const QUIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the tray menu catches up with the audio thread
+const MENU_SYNC_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Tray menu items that mirror the audio state
+struct TrayMenu {
+    start: MenuItem<Wry>,
+    stop: MenuItem<Wry>,
+    volume: Submenu<Wry>,
+    volume_steps: Vec<(u32, CheckMenuItem<Wry>)>, // Percent and its item
+    shown: Mutex<Option<MenuState>>,              // Last displayed
+}
+
+/// What the tray menu shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MenuState {
+    playing: bool,
+    volume_percent: u32,
+    no_device: bool, // Last playback attempt found no output device
+}
+
+impl TrayMenu {
+    /// Enable Start or Stop and check the current volume, if anything changed
+    fn show(&self, state: MenuState) {
+        let mut shown = self.shown.lock().unwrap();
+        if *shown == Some(state) {
+            return;
+        }
+
+        let _ = self.start.set_enabled(!state.playing);
+        let _ = self.start.set_text(if state.no_device {
+            "▶ Start Sonification (no audio device)"
+        } else {
+            "▶ Start Sonification"
+        });
+        let _ = self.stop.set_enabled(state.playing);
+        let _ = self.volume.set_text(format!("Volume ({}%)", state.volume_percent));
+        for (step, item) in &self.volume_steps {
+            let _ = item.set_checked(*step == state.volume_percent);
+        }
+        *shown = Some(state);
+    }
+
+    /// Force the next `show` to redraw (clicking a check item toggles it on its own)
+    fn invalidate(&self) {
+        *self.shown.lock().unwrap() = None;
+    }
+}
+
+/// Keep the tray menu in step with the audio thread on a background thread.
+///
+/// Playing and volume come from the audio thread's shared state; device loss
+/// only shows up as an event, so events are followed too (and still queued
+/// for the window).
+fn sync_tray_menu(app: AppHandle) {
+    std::thread::spawn(move || {
+        let state: tauri::State<AppState> = app.state();
+        let menu: tauri::State<TrayMenu> = app.state();
+        let mut no_device = false;
+
+        loop {
+            for event in state.collect_audio_events() {
+                match event {
+                    AudioEvent::NoAudioDevice => no_device = true,
+                    AudioEvent::Playing => no_device = false,
+                    _ => {}
+                }
+            }
+            let audio = state.audio_thread.lock().unwrap();
+            let shown = MenuState {
+                playing: audio.is_playing(),
+                volume_percent: (audio.get_volume() * 100.0).round() as u32,
+                no_device,
+            };
+            drop(audio);
+            menu.show(shown);
+
+            std::thread::sleep(MENU_SYNC_INTERVAL);
+        }
+    });
+}
+
+/// Shut the audio thread down cleanly, then exit
+fn quit(app: &AppHandle) {
+    let state: tauri::State<AppState> = app.state();
+    if let Ok(mut audio) = state.audio_thread.lock() {
+        if !audio.shutdown(QUIT_TIMEOUT) {
+            eprintln!("Audio thread didn't finish within {:?}, exiting anyway", QUIT_TIMEOUT);
+        }
+    }
+    app.exit(0);
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Ensure only one instance is running
@@ -29,19 +122,24 @@ pub fn run() {
             // Initialize application state
             app.manage(AppState::new());
 
+            // Stop audio cleanly on Ctrl+C (e.g. when launched from a terminal)
+            let ctrlc_handle = app.handle().clone();
+            ctrlc::set_handler(move || quit(&ctrlc_handle))?;
+
             // Create system tray menu
             let show_hide = MenuItemBuilder::with_id("show_hide", "Show/Hide Window").build(app)?;
             let separator1 = tauri::menu::PredefinedMenuItem::separator(app)?;
 
             let start = MenuItemBuilder::with_id("start", "▶ Start Sonification").build(app)?;
             let stop = MenuItemBuilder::with_id("stop", "⏹ Stop").build(app)?;
+            let panic = MenuItemBuilder::with_id("panic", "🛑 Silence Now").build(app)?;
 
             let separator2 = tauri::menu::PredefinedMenuItem::separator(app)?;
 
-            let volume_25 = MenuItemBuilder::with_id("vol_25", "25%").build(app)?;
-            let volume_50 = MenuItemBuilder::with_id("vol_50", "50%").build(app)?;
-            let volume_75 = MenuItemBuilder::with_id("vol_75", "75%").build(app)?;
-            let volume_100 = MenuItemBuilder::with_id("vol_100", "100%").build(app)?;
+            let volume_25 = CheckMenuItemBuilder::with_id("vol_25", "25%").build(app)?;
+            let volume_50 = CheckMenuItemBuilder::with_id("vol_50", "50%").build(app)?;
+            let volume_75 = CheckMenuItemBuilder::with_id("vol_75", "75%").build(app)?;
+            let volume_100 = CheckMenuItemBuilder::with_id("vol_100", "100%").build(app)?;
 
             let volume_menu = tauri::menu::SubmenuBuilder::new(app, "Volume")
                 .item(&volume_25)
@@ -57,22 +155,38 @@ pub fn run() {
 
             let separator4 = tauri::menu::PredefinedMenuItem::separator(app)?;
 
-            let quit = MenuItemBuilder::with_id("quit", "❌ Quit").build(app)?;
+            let quit_item = MenuItemBuilder::with_id("quit", "❌ Quit").build(app)?;
 
             let menu = MenuBuilder::new(app)
                 .item(&show_hide)
                 .item(&separator1)
                 .item(&start)
                 .item(&stop)
+                .item(&panic)
                 .item(&separator2)
                 .item(&volume_menu)
                 .item(&separator3)
                 .item(&export)
                 .item(&settings)
                 .item(&separator4)
-                .item(&quit)
+                .item(&quit_item)
                 .build()?;
 
+            // Labels and checkmarks follow the audio thread from here on
+            app.manage(TrayMenu {
+                start: start.clone(),
+                stop: stop.clone(),
+                volume: volume_menu.clone(),
+                volume_steps: vec![
+                    (25, volume_25.clone()),
+                    (50, volume_50.clone()),
+                    (75, volume_75.clone()),
+                    (100, volume_100.clone()),
+                ],
+                shown: Mutex::new(None),
+            });
+            sync_tray_menu(app.handle().clone());
+
             // Create tray icon
             let _tray = TrayIconBuilder::new()
                 .menu(&menu)
@@ -98,22 +212,31 @@ pub fn run() {
                             let _ = window.emit("tray-command", "stop");
                         }
                     }
+                    "panic" => {
+                        // Handled here rather than in the window, which may be the thing that's stuck
+                        let state: tauri::State<AppState> = app.state();
+                        state.audio_thread.lock().unwrap().emergency_stop();
+                    }
                     "vol_25" => {
+                        app.state::<TrayMenu>().invalidate();
                         if let Some(window) = app.get_webview_window("main") {
                             let _ = window.emit("tray-command", serde_json::json!({"action": "volume", "value": 0.25}));
                         }
                     }
                     "vol_50" => {
+                        app.state::<TrayMenu>().invalidate();
                         if let Some(window) = app.get_webview_window("main") {
                             let _ = window.emit("tray-command", serde_json::json!({"action": "volume", "value": 0.50}));
                         }
                     }
                     "vol_75" => {
+                        app.state::<TrayMenu>().invalidate();
                         if let Some(window) = app.get_webview_window("main") {
                             let _ = window.emit("tray-command", serde_json::json!({"action": "volume", "value": 0.75}));
                         }
                     }
                     "vol_100" => {
+                        app.state::<TrayMenu>().invalidate();
                         if let Some(window) = app.get_webview_window("main") {
                             let _ = window.emit("tray-command", serde_json::json!({"action": "volume", "value": 1.0}));
                         }
@@ -130,9 +253,7 @@ pub fn run() {
                             let _ = window.emit("tray-command", "settings");
                         }
                     }
-                    "quit" => {
-                        std::process::exit(0);
-                    }
+                    "quit" => quit(app),
                     _ => {}
                 })
                 .on_tray_icon_event(|tray, event| {
@@ -165,18 +286,32 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::start_audio,
             commands::stop_audio,
+            commands::emergency_stop,
             commands::pause_audio,
             commands::resume_audio,
+            commands::set_frozen,
+            commands::save_scene,
+            commands::load_scene,
+            commands::list_scenes,
             commands::set_volume,
+            commands::set_bars,
             commands::get_audio_state,
+            commands::get_waveform,
             commands::get_current_metrics,
             commands::get_musical_params,
+            commands::get_mapping_explanation,
+            commands::get_composition_events,
             commands::export_audio,
+            commands::list_export_formats,
+            commands::list_scales,
+            commands::list_presets,
             commands::get_config,
             commands::save_config,
             commands::update_config_field,
             commands::poll_audio_events,
             commands::get_system_info,
+            commands::diagnose_subsystem,
+            commands::export_debug_snapshot,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
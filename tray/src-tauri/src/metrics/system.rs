@@ -1,311 +0,0 @@
-use sysinfo::{System, Networks, Disks, Components};
-use std::time::{Duration, Instant};
-
-#[derive(Debug, Clone)]
-pub struct SystemMetrics {
-    // Original metrics
-    pub cpu_usage: f32,          // 0-100%
-    pub memory_usage: f32,       // 0-100%
-    pub disk_read_bytes: u64,    // bytes/sec
-    pub disk_write_bytes: u64,   // bytes/sec
-    pub network_rx_bytes: u64,   // bytes/sec
-    pub network_tx_bytes: u64,   // bytes/sec
-    pub temperature: f32,        // °C (average)
-    pub timestamp: Instant,
-
-    // Easy wins (from sysinfo)
-    pub load_avg_1: f32,         // 1-minute load average
-    pub load_avg_5: f32,         // 5-minute load average
-    pub load_avg_15: f32,        // 15-minute load average
-    pub swap_total: u64,         // Total swap in bytes
-    pub swap_used: u64,          // Used swap in bytes
-    pub per_core_usage: Vec<f32>, // Per-core CPU usage (0-100%)
-    pub process_count: usize,    // Number of running processes
-
-    // GPU metrics (optional - runtime detection)
-    pub gpu_nvidia: Option<NvidiaGpuMetrics>,
-    pub gpu_amd: Option<AmdGpuMetrics>,
-
-    // Battery metrics (optional - laptops only)
-    pub battery: Option<BatteryMetrics>,
-
-    // Fan metrics (optional - Linux/supported hardware)
-    pub fan_speeds: Option<Vec<FanMetric>>,
-
-    // Per-process metrics (top N by CPU)
-    pub top_processes: Vec<ProcessMetric>,
-}
-
-// GPU metric structs (will be implemented in separate modules)
-#[derive(Debug, Clone)]
-pub struct NvidiaGpuMetrics {
-    pub utilization: f32,        // 0-100%
-    pub temperature: f32,         // °C
-    pub memory_used: u64,         // bytes
-    pub memory_total: u64,        // bytes
-    pub power_draw: f32,          // watts
-    pub fan_speed: Option<f32>,   // 0-100%
-}
-
-#[derive(Debug, Clone)]
-pub struct AmdGpuMetrics {
-    pub utilization: f32,        // 0-100%
-    pub temperature: f32,         // °C
-    pub memory_used: u64,         // bytes
-    pub memory_total: u64,        // bytes
-    pub power_draw: Option<f32>,  // watts (if available)
-}
-
-#[derive(Debug, Clone)]
-pub struct BatteryMetrics {
-    pub state_of_charge: f32,    // 0-100%
-    pub state: BatteryState,      // Charging/Discharging/Full/Empty
-    pub power_rate: f32,          // watts (positive=charging, negative=discharging)
-    pub temperature: Option<f32>, // °C
-    pub time_to_full: Option<f32>, // minutes
-    pub time_to_empty: Option<f32>, // minutes
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum BatteryState {
-    Charging,
-    Discharging,
-    Full,
-    Empty,
-    Unknown,
-}
-
-#[derive(Debug, Clone)]
-pub struct FanMetric {
-    pub label: String,            // Fan sensor label
-    pub rpm: u32,                 // RPM reading
-}
-
-#[derive(Debug, Clone)]
-pub struct ProcessMetric {
-    pub name: String,             // Process name
-    pub pid: u32,                 // Process ID
-    pub cpu_usage: f32,           // CPU % (0-100)
-    pub memory_usage: u64,        // Memory in bytes
-}
-
-pub struct MetricsCollector {
-    system: System,
-    networks: Networks,
-    disks: Disks,
-    components: Components,
-    last_metrics: Option<SystemMetrics>,
-    last_update: Instant,
-}
-
-impl MetricsCollector {
-    pub fn new() -> Self {
-        Self {
-            system: System::new_all(),
-            networks: Networks::new_with_refreshed_list(),
-            disks: Disks::new_with_refreshed_list(),
-            components: Components::new_with_refreshed_list(),
-            last_metrics: None,
-            last_update: Instant::now(),
-        }
-    }
-
-    pub fn collect(&mut self) -> SystemMetrics {
-        // Refresh all data
-        self.system.refresh_cpu_all();
-        self.system.refresh_memory();
-        self.networks.refresh();
-        self.disks.refresh();
-        self.components.refresh();
-
-        // Calculate time delta for rate calculations
-        let now = Instant::now();
-        let delta = now.duration_since(self.last_update).as_secs_f32();
-        self.last_update = now;
-
-        // CPU Usage (average across all cores)
-        let cpu_usage = self.system.global_cpu_usage();
-
-        // Memory Usage
-        let memory_usage = (self.system.used_memory() as f32 / self.system.total_memory() as f32) * 100.0;
-
-        // Disk I/O (calculate rates if we have previous data)
-        let (disk_read_bytes, disk_write_bytes) = if let Some(prev) = &self.last_metrics {
-            let read_delta = self.disks.iter()
-                .map(|d| d.usage().read_bytes)
-                .sum::<u64>()
-                .saturating_sub(prev.disk_read_bytes);
-            let write_delta = self.disks.iter()
-                .map(|d| d.usage().write_bytes)
-                .sum::<u64>()
-                .saturating_sub(prev.disk_write_bytes);
-            
-            ((read_delta as f32 / delta) as u64, (write_delta as f32 / delta) as u64)
-        } else {
-            (0, 0)
-        };
-
-        // Network I/O (calculate rates)
-        let (network_rx_bytes, network_tx_bytes) = if let Some(prev) = &self.last_metrics {
-            let rx_delta = self.networks.iter()
-                .map(|(_, data)| data.received())
-                .sum::<u64>()
-                .saturating_sub(prev.network_rx_bytes);
-            let tx_delta = self.networks.iter()
-                .map(|(_, data)| data.transmitted())
-                .sum::<u64>()
-                .saturating_sub(prev.network_tx_bytes);
-            
-            ((rx_delta as f32 / delta) as u64, (tx_delta as f32 / delta) as u64)
-        } else {
-            (0, 0)
-        };
-
-        // Temperature (average across all sensors)
-        let temperatures: Vec<f32> = self.components.iter()
-            .filter_map(|c| c.temperature().map(|t| t as f32))
-            .collect();
-        let temperature = if !temperatures.is_empty() {
-            temperatures.iter().sum::<f32>() / temperatures.len() as f32
-        } else {
-            45.0 // Default fallback
-        };
-
-        // Easy wins: Load average
-        let load_avg = self.system.load_average();
-        let load_avg_1 = load_avg.one as f32;
-        let load_avg_5 = load_avg.five as f32;
-        let load_avg_15 = load_avg.fifteen as f32;
-
-        // Easy wins: Swap usage
-        let swap_total = self.system.total_swap();
-        let swap_used = self.system.used_swap();
-
-        // Easy wins: Per-core CPU usage
-        let per_core_usage: Vec<f32> = self.system.cpus()
-            .iter()
-            .map(|cpu| cpu.cpu_usage())
-            .collect();
-
-        // Easy wins: Process count
-        let process_count = self.system.processes().len();
-
-        // GPU metrics (will be populated by separate modules)
-        // For now, these return None - will be implemented in gpu_nvidia.rs and gpu_amd.rs
-        let gpu_nvidia = super::gpu_nvidia::collect_nvidia_metrics();
-        let gpu_amd = super::gpu_amd::collect_amd_metrics();
-
-        // Battery metrics (will be populated by battery module)
-        let battery = super::battery::collect_battery_metrics();
-
-        // Fan metrics (will be populated by fans module)
-        let fan_speeds = super::fans::collect_fan_metrics();
-
-        // Top processes by CPU usage
-        let top_processes = super::processes::collect_top_processes(&self.system);
-
-        let metrics = SystemMetrics {
-            cpu_usage,
-            memory_usage,
-            disk_read_bytes,
-            disk_write_bytes,
-            network_rx_bytes,
-            network_tx_bytes,
-            temperature,
-            timestamp: now,
-            load_avg_1,
-            load_avg_5,
-            load_avg_15,
-            swap_total,
-            swap_used,
-            per_core_usage,
-            process_count,
-            gpu_nvidia,
-            gpu_amd,
-            battery,
-            fan_speeds,
-            top_processes,
-        };
-
-        self.last_metrics = Some(metrics.clone());
-        metrics
-    }
-
-    pub fn collect_smoothed(&mut self, samples: usize, interval_ms: u64) -> SystemMetrics {
-        let mut accumulated = vec![];
-
-        for _ in 0..samples {
-            accumulated.push(self.collect());
-            std::thread::sleep(Duration::from_millis(interval_ms));
-        }
-
-        // Average the samples for smoother transitions
-        let cpu_avg = accumulated.iter().map(|m| m.cpu_usage).sum::<f32>() / samples as f32;
-        let mem_avg = accumulated.iter().map(|m| m.memory_usage).sum::<f32>() / samples as f32;
-        let temp_avg = accumulated.iter().map(|m| m.temperature).sum::<f32>() / samples as f32;
-
-        // Use max for I/O metrics (more interesting musically)
-        let disk_read = accumulated.iter().map(|m| m.disk_read_bytes).max().unwrap_or(0);
-        let disk_write = accumulated.iter().map(|m| m.disk_write_bytes).max().unwrap_or(0);
-        let net_rx = accumulated.iter().map(|m| m.network_rx_bytes).max().unwrap_or(0);
-        let net_tx = accumulated.iter().map(|m| m.network_tx_bytes).max().unwrap_or(0);
-
-        // Average load averages
-        let load_avg_1 = accumulated.iter().map(|m| m.load_avg_1).sum::<f32>() / samples as f32;
-        let load_avg_5 = accumulated.iter().map(|m| m.load_avg_5).sum::<f32>() / samples as f32;
-        let load_avg_15 = accumulated.iter().map(|m| m.load_avg_15).sum::<f32>() / samples as f32;
-
-        // Swap usage (average)
-        let swap_total = accumulated.last().map(|m| m.swap_total).unwrap_or(0);
-        let swap_used = accumulated.iter().map(|m| m.swap_used).sum::<u64>() / samples as u64;
-
-        // Per-core usage (average each core across samples)
-        let core_count = accumulated.first().map(|m| m.per_core_usage.len()).unwrap_or(0);
-        let mut per_core_usage = vec![0.0f32; core_count];
-        for sample in &accumulated {
-            for (i, &usage) in sample.per_core_usage.iter().enumerate() {
-                if i < per_core_usage.len() {
-                    per_core_usage[i] += usage;
-                }
-            }
-        }
-        for core_usage in &mut per_core_usage {
-            *core_usage /= samples as f32;
-        }
-
-        // Process count (average)
-        let process_count = accumulated.iter().map(|m| m.process_count).sum::<usize>() / samples;
-
-        // GPU/Battery/Fans: Take last sample (no averaging needed for these)
-        let gpu_nvidia = accumulated.last().and_then(|m| m.gpu_nvidia.clone());
-        let gpu_amd = accumulated.last().and_then(|m| m.gpu_amd.clone());
-        let battery = accumulated.last().and_then(|m| m.battery.clone());
-        let fan_speeds = accumulated.last().and_then(|m| m.fan_speeds.clone());
-
-        // Top processes: Take last sample
-        let top_processes = accumulated.last().map(|m| m.top_processes.clone()).unwrap_or_default();
-
-        SystemMetrics {
-            cpu_usage: cpu_avg,
-            memory_usage: mem_avg,
-            disk_read_bytes: disk_read,
-            disk_write_bytes: disk_write,
-            network_rx_bytes: net_rx,
-            network_tx_bytes: net_tx,
-            temperature: temp_avg,
-            timestamp: Instant::now(),
-            load_avg_1,
-            load_avg_5,
-            load_avg_15,
-            swap_total,
-            swap_used,
-            per_core_usage,
-            process_count,
-            gpu_nvidia,
-            gpu_amd,
-            battery,
-            fan_speeds,
-            top_processes,
-        }
-    }
-}
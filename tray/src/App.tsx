@@ -3,6 +3,13 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import "./App.css";
 
+interface GpuClocks {
+  core_mhz: number;
+  core_max_mhz: number;
+  memory_mhz: number;
+  memory_max_mhz: number;
+}
+
 interface SystemMetrics {
   cpu_usage: number;
   memory_usage: number;
@@ -11,7 +18,9 @@ interface SystemMetrics {
   disk_write: number;
   network_rx: number;
   network_tx: number;
+  connection_count?: number;
   temperature: number;
+  temperature_available: boolean;
   load_avg_1: number;
   load_avg_5: number;
   load_avg_15: number;
@@ -20,21 +29,26 @@ interface SystemMetrics {
   per_core_usage: number[];
   process_count: number;
   gpu_nvidia?: {
-    name: string;
+    name?: string;
     utilization: number;
     temperature: number;
     memory_used: number;
     memory_total: number;
     power_draw: number;
     fan_speed?: number;
+    clocks?: GpuClocks;
   };
   gpu_amd?: {
+    name?: string;
     utilization: number;
     temperature: number;
     power_draw: number;
     memory_used: number;
     memory_total: number;
+    clocks?: GpuClocks;
   };
+  gpu_vendor?: string;
+  gpu_name?: string;
   battery?: {
     state_of_charge: number;
     state: string;
@@ -55,8 +69,15 @@ interface SystemMetrics {
   }>;
 }
 
+interface MusicalContext {
+  scale_name: string;
+  root_note: string;
+  key_signature: string;
+}
+
 interface MusicalParams {
   tempo: number;
+  context: MusicalContext;
   melody_notes: number[];
   bass_note: number;
   percussion_density: number;
@@ -67,6 +88,14 @@ interface MusicalParams {
 interface AudioState {
   playing: boolean;
   volume: number;
+  idle?: boolean;
+  frozen?: boolean;
+}
+
+interface SubsystemReport {
+  subsystem: string;
+  available: boolean;
+  detail: string;
 }
 
 interface AppConfig {
@@ -74,6 +103,7 @@ interface AppConfig {
   auto_start: boolean;
   theme: string;
   update_interval_ms: number;
+  bars: number;
   enable_gpu_monitoring: boolean;
 }
 
@@ -83,6 +113,10 @@ function App() {
   const [audioState, setAudioState] = useState<AudioState>({ playing: false, volume: 0.8 });
   const [config, setConfig] = useState<AppConfig | null>(null);
   const [activeTab, setActiveTab] = useState<"dashboard" | "settings">("dashboard");
+  const [exportFormats, setExportFormats] = useState<string[]>(["wav"]);
+  const [exportFormat, setExportFormat] = useState("wav");
+  const [noAudioDevice, setNoAudioDevice] = useState(false);
+  const [diagnostics, setDiagnostics] = useState<SubsystemReport[]>([]);
 
   // Load config on mount
   useEffect(() => {
@@ -97,6 +131,25 @@ function App() {
     loadConfig();
   }, []);
 
+  // Probe optional sensors when the settings tab opens
+  useEffect(() => {
+    if (activeTab !== "settings") return;
+    Promise.all(
+      ["nvidia", "amd", "battery", "fans", "temperature"].map((name) =>
+        invoke<SubsystemReport>("diagnose_subsystem", { name })
+      )
+    )
+      .then(setDiagnostics)
+      .catch((e) => console.error("Failed to diagnose subsystems:", e));
+  }, [activeTab]);
+
+  // Export formats come from the backend so the list can't drift
+  useEffect(() => {
+    invoke<string[]>("list_export_formats")
+      .then(setExportFormats)
+      .catch((e) => console.error("Failed to list export formats:", e));
+  }, []);
+
   // Poll metrics every 2 seconds
   useEffect(() => {
     const pollMetrics = async () => {
@@ -122,6 +175,18 @@ function App() {
       try {
         const state = await invoke<AudioState>("get_audio_state");
         setAudioState(state);
+
+        // Unit variants serialize as strings, the rest as { Variant: payload }
+        const events = await invoke<any[]>("poll_audio_events");
+        for (const event of events) {
+          if (event === "NoAudioDevice") {
+            setNoAudioDevice(true);
+          } else if (event === "Playing") {
+            setNoAudioDevice(false);
+          } else if (typeof event === "object" && "Error" in event) {
+            console.error("Audio error:", event.Error);
+          }
+        }
       } catch (e) {
         console.error("Failed to poll audio state:", e);
       }
@@ -175,6 +240,14 @@ function App() {
     }
   };
 
+  const handleEmergencyStop = async () => {
+    try {
+      await invoke("emergency_stop");
+    } catch (e) {
+      console.error("Failed to silence audio:", e);
+    }
+  };
+
   const handlePause = async () => {
     try {
       await invoke("pause_audio");
@@ -191,6 +264,14 @@ function App() {
     }
   };
 
+  const handleToggleFreeze = async () => {
+    try {
+      await invoke("set_frozen", { frozen: !audioState.frozen });
+    } catch (e) {
+      console.error("Failed to toggle freeze:", e);
+    }
+  };
+
   const handleVolumeChange = async (vol: number) => {
     try {
       await invoke("set_volume", { volume: vol });
@@ -203,10 +284,11 @@ function App() {
   const handleExport = async () => {
     try {
       const timestamp = new Date().toISOString().replace(/[:.]/g, "-");
-      const filename = `syssonic-${timestamp}.wav`;
+      const extension = exportFormat === "midi" ? "mid" : exportFormat;
+      const filename = `syssonic-${timestamp}.${extension}`;
       await invoke("export_audio", {
         path: filename,
-        format: "wav",
+        format: exportFormat,
         bars: 4
       });
       alert(`Exported to ${filename}`);
@@ -261,9 +343,20 @@ function App() {
               <button onClick={handleResume} disabled={audioState.playing}>
                 ▶ Resume
               </button>
+              <button onClick={handleEmergencyStop} title="Silence all audio immediately">
+                🛑 Silence Now
+              </button>
+              <button onClick={handleToggleFreeze} title="Keep replaying the current sound instead of following the metrics">
+                {audioState.frozen ? "🔥 Unfreeze" : "🧊 Freeze"}
+              </button>
               <button onClick={handleExport}>
                 💾 Export
               </button>
+              <select value={exportFormat} onChange={(e) => setExportFormat(e.target.value)}>
+                {exportFormats.map((format) => (
+                  <option key={format} value={format}>{format.toUpperCase()}</option>
+                ))}
+              </select>
             </div>
             <div className="volume-control">
               <label>Volume: {Math.round(audioState.volume * 100)}%</label>
@@ -279,6 +372,15 @@ function App() {
             <div className={`status-badge ${audioState.playing ? "playing" : "stopped"}`}>
               {audioState.playing ? "🔊 Playing" : "🔇 Stopped"}
             </div>
+            {noAudioDevice && (
+              <div className="status-badge">🔇 No audio output device: playback unavailable, export still works</div>
+            )}
+            {audioState.idle && (
+              <div className="status-badge idle">💤 Idle (waiting for activity)</div>
+            )}
+            {audioState.frozen && (
+              <div className="status-badge frozen">🧊 Frozen (replaying held params)</div>
+            )}
           </section>
 
           {/* System Metrics */}
@@ -310,6 +412,9 @@ function App() {
                   <div className="metric-bar">
                     <div className="bar-fill" style={{ width: `${Math.min(metrics.temperature / 100 * 100, 100)}%` }}></div>
                   </div>
+                  {!metrics.temperature_available && (
+                    <div className="metric-detail">No sensor found (fallback value)</div>
+                  )}
                 </div>
 
                 <div className="metric-card">
@@ -338,6 +443,11 @@ function App() {
                   <div className="metric-detail">
                     ↑ TX: {formatBytes(metrics.network_tx)}/s
                   </div>
+                  {metrics.connection_count != null && (
+                    <div className="metric-detail">
+                      ⇄ Connections: {metrics.connection_count}
+                    </div>
+                  )}
                 </div>
               </section>
 
@@ -363,9 +473,14 @@ function App() {
               {(metrics.gpu_nvidia || metrics.gpu_amd) && (
                 <section className="gpu-section">
                   <h2>GPU Metrics</h2>
+                  {metrics.gpu_vendor && (
+                    <div className="gpu-active">
+                      Sonifying: {metrics.gpu_name ?? `${metrics.gpu_vendor} GPU`}
+                    </div>
+                  )}
                   {metrics.gpu_nvidia && (
                     <div className="gpu-card">
-                      <h3>NVIDIA {metrics.gpu_nvidia.name}</h3>
+                      <h3>{metrics.gpu_nvidia.name ?? "NVIDIA GPU"}</h3>
                       <div className="gpu-stats">
                         <div>Utilization: {metrics.gpu_nvidia.utilization.toFixed(0)}%</div>
                         <div>Temperature: {metrics.gpu_nvidia.temperature.toFixed(1)}°C</div>
@@ -376,12 +491,17 @@ function App() {
                         {metrics.gpu_nvidia.fan_speed && (
                           <div>Fan: {metrics.gpu_nvidia.fan_speed.toFixed(0)}%</div>
                         )}
+                        {metrics.gpu_nvidia.clocks && (
+                          <div>
+                            Clocks: {metrics.gpu_nvidia.clocks.core_mhz.toFixed(0)} / {metrics.gpu_nvidia.clocks.memory_mhz.toFixed(0)} MHz
+                          </div>
+                        )}
                       </div>
                     </div>
                   )}
                   {metrics.gpu_amd && (
                     <div className="gpu-card">
-                      <h3>AMD GPU</h3>
+                      <h3>{metrics.gpu_amd.name ?? "AMD GPU"}</h3>
                       <div className="gpu-stats">
                         <div>Utilization: {metrics.gpu_amd.utilization.toFixed(0)}%</div>
                         <div>Temperature: {metrics.gpu_amd.temperature.toFixed(1)}°C</div>
@@ -389,6 +509,11 @@ function App() {
                         <div>
                           VRAM: {formatBytes(metrics.gpu_amd.memory_used)} / {formatBytes(metrics.gpu_amd.memory_total)}
                         </div>
+                        {metrics.gpu_amd.clocks && (
+                          <div>
+                            Clocks: {metrics.gpu_amd.clocks.core_mhz.toFixed(0)} / {metrics.gpu_amd.clocks.memory_mhz.toFixed(0)} MHz
+                          </div>
+                        )}
                       </div>
                     </div>
                   )}
@@ -452,6 +577,10 @@ function App() {
               {musicalParams && (
                 <section className="musical-section">
                   <h2>Musical Parameters</h2>
+                  <p className="now-playing">
+                    Now playing: {musicalParams.context.root_note} {musicalParams.context.scale_name},{" "}
+                    {musicalParams.tempo.toFixed(0)} BPM
+                  </p>
                   <div className="musical-grid">
                     <div className="musical-card">
                       <div className="musical-label">Tempo</div>
@@ -542,6 +671,36 @@ function App() {
                   }}
                 />
               </div>
+
+              <div className="setting-item">
+                <label>Bars per composition</label>
+                <input
+                  type="number"
+                  min={1}
+                  max={64}
+                  value={config.bars}
+                  onChange={async (e) => {
+                    const val = parseInt(e.target.value);
+                    try {
+                      await invoke("set_bars", { bars: val });
+                      setConfig({ ...config, bars: val });
+                    } catch (e) {
+                      console.error("Failed to set bars:", e);
+                    }
+                  }}
+                />
+              </div>
+
+              <div className="setting-item">
+                <label>Hardware</label>
+                {diagnostics.map((report) => (
+                  <div key={report.subsystem}>
+                    {report.available
+                      ? `${report.subsystem}: OK, ${report.detail}`
+                      : `${report.subsystem}: not available (${report.detail})`}
+                  </div>
+                ))}
+              </div>
             </div>
           )}
         </main>